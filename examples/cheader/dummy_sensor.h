@@ -0,0 +1,23 @@
+/* Legacy header of a dummy temperature sensor, used as example input for the C header importer. */
+#ifndef DUMMY_SENSOR_H
+#define DUMMY_SENSOR_H
+
+#define DUMMY_SENSOR_I2C_ADDRESS    0x48
+
+#define TEMP_CFG_REG                0x01U
+#define TEMP_CFG_EN_MSK             (0x01U << 0)
+#define TEMP_CFG_EN_POS             0
+#define TEMP_CFG_ODR_MSK            0x0EU     /* Output data rate */
+#define TEMP_CFG_ODR_POS            1
+#define TEMP_CFG_RANGE_POS          4
+#define TEMP_CFG_RANGE_MASK         (0x3U << TEMP_CFG_RANGE_POS)
+
+#define TEMP_STATUS_REG             0x02
+#define TEMP_STATUS_DRDY_MSK        BIT(0)
+#define TEMP_STATUS_OVF_Pos         1
+#define TEMP_STATUS_OVF_Msk         0x1UL     // right-aligned, used with _Pos
+
+#define TEMP_OUT_ADDR               0x10
+#define TEMP_OUT_VAL_MSK            0xFFFFU
+
+#endif /* DUMMY_SENSOR_H */
@@ -0,0 +1,81 @@
+#!/usr/bin/env python3
+import argparse
+import random
+import sys
+from typing import List, Optional
+
+from reginald.bits import Bits
+from reginald.byte_transforms import ByteOrder, mask_to_byte_transforms
+
+# Randomized round-trip checks of the byte transforms: For random field masks
+# (contiguous or not) in registers of random width, and both byte orders, a
+# random field value is packed byte by byte, and has to equal the value packed
+# through the field's mask. Unpacking the bytes again has to give the value
+# Bits.extract_this_field_from() extracts:
+#
+#   python3 scripts/check_byte_transforms.py --iterations 100000
+#
+# Failures are reported with the seed and iteration to reproduce them.
+
+
+def random_mask(bitwidth: int, rng: random.Random) -> int:
+    if rng.random() < 0.5:
+        width = rng.randint(1, bitwidth)
+        return ((1 << width) - 1) << rng.randint(0, bitwidth - width)
+    return rng.getrandbits(bitwidth) or 1
+
+
+def check(bitwidth: int, mask: int, byte_order: ByteOrder, value: int) -> Optional[str]:
+    bytewidth = (bitwidth + 7) // 8
+    bits = Bits.from_mask(mask)
+    order = 'little' if byte_order == ByteOrder.LITTLE else 'big'
+
+    # Expected register value, with all other bits zero:
+    reg_val = (value << bits.lsb_position()) & bits.get_bitmask()
+
+    packed = [0] * bytewidth
+    for t in mask_to_byte_transforms(mask, byte_order, bytewidth):
+        packed[t.byte] |= t.pack(value)
+    if int.from_bytes(bytes(packed), order) != reg_val:
+        return f"packed 0x{int.from_bytes(bytes(packed), order):X}, expected 0x{reg_val:X}"
+
+    # Unpack from a register where all other bits are set:
+    reg_bytes = list((reg_val | ~mask & ((1 << (8 * bytewidth)) - 1)).to_bytes(bytewidth, order))
+    unpacked = 0
+    for t in mask_to_byte_transforms(mask, byte_order, bytewidth):
+        unpacked |= t.unpack(reg_bytes[t.byte])
+    expected = bits.extract_this_field_from(int.from_bytes(bytes(reg_bytes), order))
+    if unpacked != expected:
+        return f"unpacked 0x{unpacked:X}, expected 0x{expected:X}"
+
+    return None
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Randomized round-trip checks of the reginald byte transforms.")
+    parser.add_argument('--iterations', type=int, default=10000, help="number of random masks to check (default: 10000)")
+    parser.add_argument('--seed', type=int, default=0, help="random seed (default: 0)")
+    parser.add_argument('--max-bitwidth', type=int, default=128, help="maximum register width in bits (default: 128)")
+    opts = parser.parse_args()
+
+    rng = random.Random(opts.seed)
+    failures = []  # type: List[str]
+
+    for iteration in range(opts.iterations):
+        bitwidth = rng.randint(1, opts.max_bitwidth)
+        mask = random_mask(bitwidth, rng)
+        byte_order = rng.choice(list(ByteOrder))
+        value = rng.getrandbits(Bits.from_mask(mask).total_width())
+        error = check(bitwidth, mask, byte_order, value)
+        if error is not None:
+            failures.append(f"Iteration {iteration}: {bitwidth} bits, mask 0x{mask:X}, {byte_order.name.lower()}-endian, "
+                            f"value 0x{value:X}: {error}")
+
+    for failure in failures:
+        print(failure)
+    print(f"{opts.iterations} iterations, {len(failures)} failure(s)")
+    sys.exit(1 if len(failures) > 0 else 0)
+
+
+if __name__ == '__main__':
+    main()
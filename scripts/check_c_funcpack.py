@@ -0,0 +1,210 @@
+#!/usr/bin/env python3
+import argparse
+import os
+import subprocess
+import sys
+import tempfile
+import warnings
+from typing import List, NamedTuple, Optional
+
+from reginald.cli import builtin_generators
+from reginald.error import ReginaldException
+from reginald.input.load_listing import load_register_map
+
+# Compile-and-run checks of the c.funcpack output: Every case is a small
+# listing, for which a header is generated and compiled (with all warnings as
+# errors) together with a test program, which is then run:
+#
+#   python3 scripts/check_c_funcpack.py --cc gcc
+#
+# Cases cover combinations of features that the example maps do not.
+
+
+class Case(NamedTuple):
+    name: str
+    listing: str
+    main: str
+    support: str = ""  # Definitions outside of main(), e.g. register access callbacks.
+    args: List[str] = []
+
+
+CASES = [
+    Case(name="pack_checked with a ranged view",
+         listing="""
+map_name: stress
+default_register_bitwidth: 8
+registers:
+  VIEWS:
+    adr: 0x0
+    fields:
+      MODE: {bits: "3-0", access: [r, w], min: 1, max: 9}
+      TOP: {bits: 3, access: [r, w], view: true, min: 0, max: 0}
+""",
+         main="""
+  struct stress_views r = {.mode = 9};
+  uint8_t out = 0;
+  assert(stress_views_pack_checked(&r, &out) == 0 && out == 9);
+  r.mode = 10;
+  assert(stress_views_pack_checked(&r, &out) == -1);
+"""),
+    Case(name="write_unlocked of a register with separate read and write layouts",
+         listing="""
+map_name: stress
+default_register_bitwidth: 8
+layouts:
+  STATE:
+    fields:
+      BUSY: {bits: 0, access: r}
+  CMD:
+    fields:
+      GO: {bits: 1, access: w}
+registers:
+  KEY:
+    adr: 0x0
+    fields:
+      KEY: {bits: "7-0", access: w}
+  LOCKED:
+    adr: 0x1
+    layout_read: STATE
+    layout_write: CMD
+    unlock: {key_register: KEY, keys: [0xA5, 0x5A]}
+""",
+         main="""
+  uint64_t log[3] = {0};
+  const struct stress_locked_w r = {.go = 1};
+  assert(stress_locked_write_unlocked(&r, 0x1, log_write, log) == 0);
+  assert(log[0] == 0xA5 && log[1] == 0x5A && log[2] == 0x2);
+""",
+         support="""
+static int log_write(void *ctx, uint8_t adr, uint64_t val) {
+  static unsigned count = 0;
+  (void) adr;
+  ((uint64_t *) ctx)[count++] = val;
+  return 0;
+}
+"""),
+    Case(name="write_verified of a register with a volatile field",
+         listing="""
+map_name: stress
+default_register_bitwidth: 8
+registers:
+  CTRL:
+    adr: 0x0
+    fields:
+      EN: {bits: 0, access: [r, w]}
+      DONE: {bits: 1, access: [r, w], volatile: true}
+""",
+         main="""
+  uint64_t reg = 0;
+  const struct stress_ctrl r = {.en = 1, .done = 0};
+  assert(STRESS_REG_CTRL__VERIFY_MASK == 0x1);
+  assert(stress_ctrl_write_verified(&r, 0x0, hw_write, hw_read, &reg, NULL) == 0);
+  assert(reg == 0x3);
+""",
+         support="""
+// Hardware that sets DONE as soon as EN is written:
+static int hw_write(void *ctx, uint8_t adr, uint64_t val) {
+  (void) adr;
+  *(uint64_t *) ctx = val | ((val & 0x1) << 1);
+  return 0;
+}
+
+static int hw_read(void *ctx, uint8_t adr, uint64_t *val) {
+  (void) adr;
+  *val = *(uint64_t *) ctx;
+  return 0;
+}
+""",
+         args=['--write-verified']),
+    Case(name="parameters in constants, layouts, always write values, and variants",
+         listing="""
+map_name: stress
+default_register_bitwidth: 8
+params: {LO: 2, HI: 9, KEY: 0x2A}
+constants:
+  MAGIC: {val: "${KEY} + 1"}
+layouts:
+  MODE:
+    fields:
+      MODE: {bits: "3-0", access: [r, w], min: "${LO}", max: "${HI}"}
+registers:
+  CTRL:
+    adr: 0x0
+    extends: MODE
+    always_write: {mask: "0xF0", val: "(${KEY} << 4) & 0xF0"}
+  PKT:
+    adr: 0x1
+    variant_tag: TYPE
+    fields:
+      TYPE: {bits: 0, access: [r, w]}
+    variants:
+      PACKED:
+        tag: 1
+        fields:
+          LEN: {bits: "7-1", access: [r, w], max: "${HI} * 2"}
+""",
+         main="""
+  assert(STRESS_CONST_MAGIC == 0x2B);
+  assert(STRESS_REG_CTRL__ALWAYSWRITE_VALUE == 0xA0);
+  struct stress_ctrl ctrl = {.mode = 2};
+  uint8_t out = 0;
+  assert(stress_ctrl_pack_checked(&ctrl, &out) == 0 && out == 0xA2);
+  ctrl.mode = 1;
+  assert(stress_ctrl_pack_checked(&ctrl, &out) == -1);
+  struct stress_pkt_packed pkt = {.len = 18};
+  assert(stress_pkt_packed_pack_checked(&pkt, &out) == 0);
+  pkt.len = 19;
+  assert(stress_pkt_packed_pack_checked(&pkt, &out) == -1);
+"""),
+]
+
+
+def check(case: Case, cc: str, tmp: str) -> Optional[str]:
+    listing = os.path.join(tmp, "listing.yaml")
+    header = os.path.join(tmp, "out.h")
+    source = os.path.join(tmp, "main.c")
+    binary = os.path.join(tmp, "main")
+
+    with open(listing, 'w') as f:
+        f.write(case.listing)
+    try:
+        rmap = load_register_map(listing)
+        builtin_generators['c.funcpack'].generate(rmap, listing, header, case.args)
+    except ReginaldException as e:
+        return f"generation failed: {e}"
+
+    with open(source, 'w') as f:
+        f.write('#include <assert.h>\n#include <stddef.h>\n#include <stdint.h>\n#include "out.h"\n')
+        f.write(f"{case.support}\n")
+        f.write(f"int main(void) {{{case.main}  return 0;\n}}\n")
+
+    result = subprocess.run([cc, "-std=c11", "-Wall", "-Wextra", "-Werror", "-o", binary, source],
+                            capture_output=True, text=True)
+    if result.returncode != 0:
+        return f"compilation failed:\n{result.stderr}"
+    result = subprocess.run([binary], capture_output=True, text=True)
+    if result.returncode != 0:
+        return f"test program failed:\n{result.stderr}"
+    return None
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Compile-and-run checks of the reginald c.funcpack output.")
+    parser.add_argument('--cc', default='cc', help="C compiler (default: cc)")
+    opts = parser.parse_args()
+
+    warnings.simplefilter("ignore")
+    failures = 0
+    for case in CASES:
+        with tempfile.TemporaryDirectory() as tmp:
+            error = check(case, opts.cc, tmp)
+        if error is not None:
+            failures += 1
+            print(f"{case.name}: {error}")
+
+    print(f"{len(CASES)} case(s), {failures} failure(s)")
+    sys.exit(1 if failures > 0 else 0)
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,185 @@
+#!/usr/bin/env python3
+import argparse
+import copy
+import json
+import os
+import random
+import shutil
+import sys
+import tempfile
+import traceback
+import warnings
+from typing import Any, Dict, List, Tuple
+
+import yaml
+
+from reginald.cli import builtin_generators
+from reginald.error import ReginaldException
+from reginald.input.load_listing import guess_input_format, load_register_map
+
+# Mutation fuzzer for the input importers: Every iteration mutates one of the
+# seed listings, loads it, and runs the selected generators on it. Malformed
+# input has to be reported as a ReginaldException, anything else escaping is
+# a crash:
+#
+#   python3 scripts/fuzz_importers.py --iterations 5000 examples/*/*.*
+#
+# YAML/JSON listings are mutated structurally (values replaced by values of
+# the wrong type/range, keys dropped), all other formats on the text level
+# (lines dropped/duplicated, numbers replaced). A share of the listings is
+# additionally mutated on the byte level (bytes flipped, dropped, or inserted),
+# to exercise the parsers themselves. Crashes are grouped by exception type
+# and location, with the seed and iteration to reproduce them.
+
+NASTY_VALUES = [
+    -1, 0, 1, 7, 64, 2**31, 2**40, 2**64, 2**100, -2**64, 1.5, float('nan'), True, None, "", "x", "-1", "0x", "31-0",
+    "7-8", "99-0", "3-", "-", "1,2", "0b", "${", "${X}", "{n}", [], [1, -1], {}, {"x": 1},
+]
+
+NASTY_NUMBERS = ["-1", "0", "0x", "0xFFFFFFFFFFFFFFFFFFFF", "99999999999999999999", "1e9", "-0x10", "0b", "x"]
+
+# Syntax characters of the supported formats, and bytes that are not valid UTF-8:
+NASTY_BYTES = b":-[]{},\"'#&*!|>%@`<>/\\\t\n \x00\x80\xff"
+
+
+def mutate_data(data: Any, rng: random.Random) -> Any:
+    # Pick a random node, and replace/drop it:
+    paths = []  # type: List[Tuple[Any, Any]]
+
+    def walk(node: Any):
+        if isinstance(node, dict):
+            for key in list(node.keys()):
+                paths.append((node, key))
+                walk(node[key])
+        elif isinstance(node, list):
+            for idx in range(len(node)):
+                paths.append((node, idx))
+                walk(node[idx])
+
+    walk(data)
+    if len(paths) == 0:
+        return copy.deepcopy(rng.choice(NASTY_VALUES))
+
+    parent, key = rng.choice(paths)
+    if isinstance(parent, dict) and rng.random() < 0.2:
+        del parent[key]
+    elif isinstance(parent, dict) and rng.random() < 0.1:
+        parent[rng.choice(NASTY_VALUES[17:24])] = parent.pop(key)
+    else:
+        parent[key] = copy.deepcopy(rng.choice(NASTY_VALUES))
+    return data
+
+
+def mutate_text(text: str, rng: random.Random) -> str:
+    lines = text.splitlines()
+    if len(lines) == 0:
+        return text
+    idx = rng.randrange(len(lines))
+    choice = rng.random()
+    if choice < 0.25:
+        del lines[idx]
+    elif choice < 0.4:
+        lines.insert(idx, lines[idx])
+    elif choice < 0.5:
+        lines = lines[:idx]
+    else:
+        words = lines[idx].split(" ")
+        numeric = [i for i, w in enumerate(words) if any(c.isdigit() for c in w)]
+        if len(numeric) > 0:
+            i = rng.choice(numeric)
+            words[i] = "".join(c for c in words[i] if not c.isalnum()) + rng.choice(NASTY_NUMBERS)
+        lines[idx] = " ".join(words)
+    return "\n".join(lines) + "\n"
+
+
+def mutate_bytes(raw: bytes, rng: random.Random) -> bytes:
+    if len(raw) == 0:
+        return raw
+    idx = rng.randrange(len(raw))
+    choice = rng.random()
+    if choice < 0.3:
+        return raw[:idx] + raw[idx + 1:]
+    elif choice < 0.6:
+        return raw[:idx] + bytes([rng.choice(NASTY_BYTES)]) + raw[idx:]
+    elif choice < 0.9:
+        return raw[:idx] + bytes([rng.choice(NASTY_BYTES)]) + raw[idx + 1:]
+    return raw[:idx] + bytes([raw[idx] ^ (1 << rng.randrange(8))]) + raw[idx + 1:]
+
+
+def mutate_file(seed_file: str, out_dir: str, rng: random.Random, rounds: int, byte_level: float) -> str:
+    # Companion files (e.g. CSV column maps, includes) are copied along:
+    for f in os.listdir(os.path.dirname(seed_file) or "."):
+        src = os.path.join(os.path.dirname(seed_file), f)
+        if os.path.isfile(src):
+            shutil.copy(src, os.path.join(out_dir, f))
+
+    out_file = os.path.join(out_dir, os.path.basename(seed_file))
+    with open(seed_file) as f:
+        text = f.read()
+
+    fmt = guess_input_format(seed_file)
+    if fmt in ['yaml', 'json']:
+        data = yaml.safe_load(text) if fmt == 'yaml' else json.loads(text)
+        for _ in range(rounds):
+            data = mutate_data(data, rng)
+        text = yaml.safe_dump(data) if fmt == 'yaml' else json.dumps(data, allow_nan=True)
+    else:
+        for _ in range(rounds):
+            text = mutate_text(text, rng)
+
+    raw = text.encode()
+    if rng.random() < byte_level:
+        for _ in range(rounds):
+            raw = mutate_bytes(raw, rng)
+
+    with open(out_file, 'wb') as f:
+        f.write(raw)
+    return out_file
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Fuzz the reginald input importers with mutated listings.")
+    parser.add_argument('seed_files', nargs='+', help="listings to mutate")
+    parser.add_argument('--iterations', type=int, default=1000, help="number of mutated listings to load (default: 1000)")
+    parser.add_argument('--seed', type=int, default=0, help="random seed (default: 0)")
+    parser.add_argument('--rounds', type=int, default=3, help="maximum mutations per listing (default: 3)")
+    parser.add_argument('--byte-level', type=float, default=0.25,
+                        help="share of listings that are also mutated on the byte level (default: 0.25)")
+    parser.add_argument('--generator', action='append', dest='generators', choices=list(builtin_generators.keys()),
+                        help="generator to run on listings that load (repeatable, default: c.funcpack and md.doc)")
+    parser.add_argument('--keep', metavar='DIR', help="copy the first listing of every crash into DIR")
+    opts = parser.parse_args()
+    generators = opts.generators if opts.generators is not None else ['c.funcpack', 'md.doc']
+
+    seed_files = [f for f in opts.seed_files if guess_input_format(f) != 'yaml' or not f.endswith('.columns.yaml')]
+    rng = random.Random(opts.seed)
+    crashes = {}  # type: Dict[Tuple[str, str], List[int]]
+    warnings.simplefilter("ignore")
+
+    for iteration in range(opts.iterations):
+        with tempfile.TemporaryDirectory() as tmp:
+            seed_file = rng.choice(seed_files)
+            listing = mutate_file(seed_file, tmp, rng, rng.randint(1, opts.rounds), opts.byte_level)
+            try:
+                rmap = load_register_map(listing)
+                for generator in generators:
+                    builtin_generators[generator].generate(rmap, listing, os.path.join(tmp, "out"), [])
+            except ReginaldException:
+                continue
+            except Exception as e:
+                frame = traceback.extract_tb(e.__traceback__)[-1]
+                key = (type(e).__name__, f"{os.path.relpath(frame.filename)}:{frame.lineno}")
+                if key not in crashes and opts.keep is not None:
+                    os.makedirs(opts.keep, exist_ok=True)
+                    shutil.copy(listing, os.path.join(opts.keep, f"crash{iteration}_{os.path.basename(listing)}"))
+                    print(f"{key[0]} at {key[1]} ({seed_file}, iteration {iteration}): {e}")
+                crashes.setdefault(key, []).append(iteration)
+
+    for (exc, location), iterations in sorted(crashes.items()):
+        print(f"{exc} at {location}: {len(iterations)}x, first in iteration {iterations[0]}")
+    print(f"{opts.iterations} iterations, {len(crashes)} distinct crash(es)")
+    sys.exit(1 if len(crashes) > 0 else 0)
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,97 @@
+#!/usr/bin/env python3
+import argparse
+import gc
+import os
+import tempfile
+import time
+import tracemalloc
+import warnings
+import xml.etree.ElementTree as ET
+
+from reginald.cli import builtin_generators
+from reginald.input.convert_yaml import YAMLConverter
+from reginald.input.parse_svd import SVDConverter
+from reginald.input.validate_map import MapValidator
+
+# Time and memory of every stage of loading a large map, and of running a
+# generator on it. The map is a synthetic SVD file (of the size of a complete
+# SoC description by default), every peripheral with its own registers:
+#
+#   python3 scripts/profile_large_maps.py --peripherals 400 --registers 50
+#
+# Memory is measured with tracemalloc, which slows everything down: Use the
+# times only to compare the stages with each other.
+
+
+def write_svd(file_name: str, peripherals: int, registers: int, fields: int):
+    with open(file_name, 'w') as f:
+        f.write('<?xml version="1.0" encoding="utf-8"?>\n<device schemaVersion="1.3">\n  <name>LARGE</name>\n'
+                '  <addressUnitBits>8</addressUnitBits>\n  <width>32</width>\n  <resetValue>0</resetValue>\n  <peripherals>\n')
+        for p in range(peripherals):
+            f.write(f"    <peripheral><name>P{p}</name><description>Peripheral {p}.</description>"
+                    f"<baseAddress>0x{0x40000000 + p * 0x1000:X}</baseAddress><registers>\n")
+            for r in range(registers):
+                f.write(f"      <register><name>R{r}</name><description>Register {r} of peripheral {p}.</description>"
+                        f"<addressOffset>0x{r * 4:X}</addressOffset><access>read-write</access><fields>\n")
+                for i in range(fields):
+                    f.write(f"        <field><name>F{i}</name><description>Field {i}.</description>"
+                            f"<bitOffset>{i * (32 // fields)}</bitOffset><bitWidth>{32 // fields}</bitWidth></field>\n")
+                f.write("      </fields></register>\n")
+            f.write("    </registers></peripheral>\n")
+        f.write("  </peripherals>\n</device>\n")
+
+
+def report(stage: str, start: float):
+    current, peak = tracemalloc.get_traced_memory()
+    print(f"{stage:<24} {current / 1e6:9.1f} MB live {peak / 1e6:9.1f} MB peak {time.time() - start:8.1f} s", flush=True)
+    tracemalloc.reset_peak()
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Time and memory of loading a large (synthetic) map.")
+    parser.add_argument('--peripherals', type=int, default=400, help="number of peripherals (default: 400)")
+    parser.add_argument('--registers', type=int, default=50, help="registers per peripheral (default: 50)")
+    parser.add_argument('--fields', type=int, default=8, choices=[1, 2, 4, 8, 16, 32], help="fields per register (default: 8)")
+    parser.add_argument('--generator', choices=list(builtin_generators.keys()), default='c.funcpack',
+                        help="generator to run on the loaded map (default: c.funcpack)")
+    opts = parser.parse_args()
+    warnings.simplefilter("ignore")
+
+    with tempfile.TemporaryDirectory() as tmp:
+        svd_file = os.path.join(tmp, "large.svd")
+        write_svd(svd_file, opts.peripherals, opts.registers, opts.fields)
+        print(f"{opts.peripherals * opts.registers} registers, {opts.peripherals * opts.registers * opts.fields} fields, "
+              f"{os.path.getsize(svd_file) / 1e6:.1f} MB of SVD")
+
+        # The stages of load_register_map() (for SVD input), with the result
+        # of each stage released as soon as the next one does not need it:
+        tracemalloc.start()
+        start = time.time()
+        tree = ET.parse(svd_file)
+        report("XML tree", start)
+
+        start = time.time()
+        listing = SVDConverter(tree.getroot()).convert()
+        report("listing", start)
+        del tree
+        gc.collect()
+        report("  without XML tree", start)
+
+        start = time.time()
+        rmap = YAMLConverter(listing, None).convert()
+        report("datamodel", start)
+        del listing
+        gc.collect()
+        report("  without listing", start)
+
+        start = time.time()
+        MapValidator(rmap).validate()
+        report("validation", start)
+
+        start = time.time()
+        builtin_generators[opts.generator].generate(rmap, svd_file, os.path.join(tmp, "out"), [])
+        report(opts.generator, start)
+
+
+if __name__ == '__main__':
+    main()
@@ -1 +1,4 @@
 __version__ = "v0.0.3"
+
+from reginald.api import generate, load  # noqa: E402, F401
+from reginald.decode import decode_bytes, decode_register  # noqa: E402, F401
@@ -1,23 +1,43 @@
 import sys
+import warnings
 
-from reginald.cli import parse_args
-from reginald.error import ReginaldException
-from reginald.input.convert_yaml import YAMLConverter
-from reginald.input.parse_yaml import YAML_RegisterMap
-from reginald.input.validate_map import MapValidator
+from reginald.cli import parse_args, parse_command
+from reginald.error import ReginaldException, ReginaldWarning
+from reginald.input.load_listing import load_register_map
+from reginald.input.prune_map import MapPruner, YAML_UsageManifest
+
+
+def show_warning(message, category, filename, lineno, file=None, line=None):
+    if issubclass(category, ReginaldWarning):
+        print(f"Warning: {message}", file=sys.stderr)
+    else:
+        print(warnings.formatwarning(message, category, filename, lineno, line), file=sys.stderr, end="")
 
 
 def main():
 
+    # Report every reginald warning, not just the first one at any location:
+    warnings.simplefilter("always", ReginaldWarning)
+    warnings.showwarning = show_warning
+
     try:
 
+        # Run a builtin command if one was selected:
+        command = parse_command(sys.argv[1:])
+        if command is not None:
+            cmd, cmd_args = command
+            cmd.run(cmd_args)
+            exit(0)
+
         # Parse command line args:
         cli, generator = parse_args()
 
         # Open, parse, and validate input file:
-        r = YAML_RegisterMap.from_yaml_file(cli.input_file)
-        r = YAMLConverter(r).convert()
-        MapValidator(r).validate()
+        r = load_register_map(cli.input_file, cli.input_format, cli.doc_lang, cli.params, cli.overlays, cli.permissive)
+
+        # Only keep registers that are in use, if requested:
+        if cli.usage_manifest is not None:
+            r = MapPruner(r, YAML_UsageManifest.from_yaml_file(cli.usage_manifest)).prune()
 
         # Generate output using selected generator:
         generator.generate(r, cli.input_file, cli.output_file, cli.generator_args)
@@ -0,0 +1,54 @@
+from typing import Dict, List, Optional
+
+from reginald.cli import builtin_generators
+from reginald.datamodel import RegisterMap
+from reginald.error import ReginaldException
+from reginald.input.load_listing import load_register_map
+from reginald.input.prune_map import MapPruner, YAML_UsageManifest
+
+# In-process API, for build systems that drive generation from python instead
+# of running the command line tool and parsing its output:
+#
+#   import reginald
+#   reginald.generate("chip.yaml", "chip.h", "c.funcpack", ["--no-enums"])
+#
+# Arguments mirror the command line options. Errors are raised as
+# ReginaldException, warnings are issued as ReginaldWarning through python's
+# warnings module.
+
+
+def load(input_file: str,
+         input_format: Optional[str] = None,
+         doc_lang: Optional[str] = None,
+         params: Optional[Dict[str, int]] = None,
+         overlays: Optional[List[str]] = None,
+         permissive: bool = False,
+         usage_manifest: Optional[str] = None) -> RegisterMap:
+    rmap = load_register_map(input_file, input_format, doc_lang, params or {}, overlays or [], permissive)
+
+    if usage_manifest is not None:
+        rmap = MapPruner(rmap, YAML_UsageManifest.from_yaml_file(usage_manifest)).prune()
+
+    return rmap
+
+
+def generate(input_file: str,
+             output_file: str,
+             generator: str,
+             generator_args: Optional[List[str]] = None,
+             input_format: Optional[str] = None,
+             doc_lang: Optional[str] = None,
+             params: Optional[Dict[str, int]] = None,
+             overlays: Optional[List[str]] = None,
+             permissive: bool = False,
+             usage_manifest: Optional[str] = None):
+    if generator not in builtin_generators:
+        raise ReginaldException(f"Unknown generator '{generator}'!")
+
+    rmap = load(input_file, input_format, doc_lang, params, overlays, permissive, usage_manifest)
+
+    try:
+        builtin_generators[generator].generate(rmap, input_file, output_file, generator_args or [])
+    except SystemExit as e:
+        # Generators parse their arguments with argparse, which exits on invalid arguments:
+        raise ReginaldException(f"{generator}: Invalid generator arguments {generator_args}! (exit code {e.code})")
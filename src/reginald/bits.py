@@ -1,11 +1,14 @@
 from itertools import groupby
-from math import ceil, log2
 from operator import itemgetter
-from typing import List
+from typing import Dict, List, Optional
 
 from pydantic import NonNegativeInt, PositiveInt
 from pydantic.dataclasses import dataclass
 
+# The byte transforms used to live here, and can still be imported from here:
+from reginald.byte_transforms import (ByteOrder, ByteTransform,
+                                      mask_to_byte_masks,
+                                      mask_to_byte_transforms)
 from reginald.error import ReginaldException
 
 
@@ -103,7 +106,6 @@ class Bits:
         if len(ranges) == 1:
             return ranges[0]
         else:
-            breakpoint()
             raise ReginaldException(
                 f"Cannot specify bit range for a field that is non-continous! (Field mask: {hex(self.get_bitmask())})")
 
@@ -130,7 +132,6 @@ class Bits:
 
     def bitwise_not(self, maximum_width: PositiveInt):
         if self.msb_position() + 1 > maximum_width:
-            breakpoint()
             raise ValueError("Inversion width too small for range")
 
         bitlist_is = self.bitlist
@@ -143,7 +144,7 @@ class Bits:
 
     @classmethod
     def bitwise_or(cls, a, b):
-        bitlist_or = a.bitlist
+        bitlist_or = list(a.bitlist)
 
         for bit in b.bitlist:
             if bit not in bitlist_or:
@@ -167,14 +168,24 @@ class Bits:
     def extract_this_field_from(self, val: NonNegativeInt) -> NonNegativeInt:
         return (self.get_bitmask() & val) >> self.lsb_position()
 
+    def get_byte_masks(self) -> Dict[NonNegativeInt, NonNegativeInt]:
+        return mask_to_byte_masks(self.get_bitmask())
+
+    def get_byte_transforms(self, byte_order: Optional[ByteOrder] = None,
+                            bytewidth: Optional[PositiveInt] = None) -> List[ByteTransform]:
+        return mask_to_byte_transforms(self.get_bitmask(), byte_order, bytewidth)
+
+
+def byte_swap(val: NonNegativeInt, bytewidth: PositiveInt) -> NonNegativeInt:
+    return int.from_bytes(val.to_bytes(bytewidth, 'little'), 'big')
+
 
 def fits_into_bitwidth(val: int, bitwidth: int) -> bool:
-    if val == 0:
-        return True
+    # Exact for values of any width (no floating point):
     if val < 0:
-        return ceil(log2(val * -1)) <= (bitwidth - 1)
+        return (-val - 1).bit_length() <= bitwidth - 1
     else:
-        return ceil(log2(val + 1)) <= bitwidth
+        return val.bit_length() <= bitwidth
 
 
 def consecutive_groups(iterable, ordering=lambda x: x):
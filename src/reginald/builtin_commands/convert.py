@@ -0,0 +1,28 @@
+import argparse
+from typing import List
+
+from reginald.command import CLICommand
+from reginald.input.dump_listing import OUTPUT_FORMATS, write_listing
+from reginald.input.load_listing import INPUT_FORMATS, load_listing
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Convert a register description into a YAML or JSON listing."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald convert", description=self.description())
+        parser.add_argument('input_file',
+                            help="input register description")
+        parser.add_argument('output_file',
+                            help="output listing")
+        parser.add_argument('--input-format', choices=list(INPUT_FORMATS.keys()),
+                            help="format of the input file. If not given, it is inferred from the file extension")
+        parser.add_argument('--output-format', choices=list(OUTPUT_FORMATS.keys()),
+                            help="format of the output file. If not given, it is inferred from the file extension")
+        parsed_args = parser.parse_args(args)
+
+        listing = load_listing(parsed_args.input_file, parsed_args.input_format)
+        output_format = write_listing(listing, parsed_args.output_file, parsed_args.output_format)
+
+        print(f"{parsed_args.output_file}: Written as {output_format} listing.")
@@ -0,0 +1,169 @@
+import argparse
+import py_compile
+import shlex
+import shutil
+import subprocess
+import tempfile
+import warnings
+from os import path
+from typing import Callable, List, Optional
+
+from reginald.command import CLICommand
+from reginald.error import ReginaldException, ReginaldWarning
+from reginald.input.convert_yaml import YAMLConverter
+from reginald.input.lint_docs import DocLinter
+from reginald.input.load_listing import INPUT_FORMATS, load_listing
+from reginald.input.validate_map import MapValidator
+
+# Runs every stage of the pipeline against a project and reports all problems
+# at once, instead of stopping at the first one:
+#   - parse:    Loading the listing (includes, parameters, schema).
+#   - resolve:  Conversion into a register map and validation.
+#   - lint:     Documentation lints.
+#   - generate: A trial run of each backend, into a temporary directory.
+#   - compile:  A syntax check of the generated output, where possible
+#               (C headers with the C compiler, gdb scripts with python).
+# Later stages are skipped if the map cannot be resolved.
+
+# Generators that need no extra arguments, checked by default:
+DEFAULT_GENERATORS = ['c.funcpack', 'c.macromap', 'md.doc', 'md.mapanalysis', 'md.bitprovenance', 'gdb.regdecode', 'json.stats',
+                      'json.ir']
+
+# Output file extension by generator family:
+OUTPUT_EXTENSIONS = {
+    'c': '.h',
+    'md': '.md',
+    'gdb': '.py',
+    'json': '.json',
+}
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Run parsing, validation, lints, and a trial generation of each backend, and report all problems found."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald doctor", description=self.description())
+        parser.add_argument('input_file',
+                            help="input register description")
+        parser.add_argument('--input-format', choices=list(INPUT_FORMATS.keys()),
+                            help="format of the input file. If not given, it is inferred from the file extension")
+        parser.add_argument('--doc-lang',
+                            help="documentation language to check, for listings with multi-language docs")
+        parser.add_argument('--generator', action='append', metavar='"NAME [ARGS]"',
+                            help="backend to try, with its arguments. Can be given multiple times "
+                            f"(default: {', '.join(DEFAULT_GENERATORS)})")
+        parser.add_argument('--permissive', action='store_true',
+                            help="report unknown keys in yaml/json listings as warnings instead of errors")
+        parser.add_argument('--cc', default='cc',
+                            help="C compiler used to check generated headers (default: cc). Skipped if not found")
+        parsed_args = parser.parse_args(args)
+
+        doctor = Doctor()
+
+        listing = doctor.step("parse", lambda: load_listing(parsed_args.input_file, parsed_args.input_format,
+                                                           permissive=parsed_args.permissive))
+        rmap = None
+        if listing is not None:
+            def resolve():
+                rmap = YAMLConverter(listing, parsed_args.doc_lang).convert()
+                MapValidator(rmap).validate()
+                return rmap
+            rmap = doctor.step("resolve", resolve)
+
+        if rmap is None:
+            doctor.skip("lint", "map could not be resolved")
+            doctor.skip("generate", "map could not be resolved")
+        else:
+            def lint():
+                findings = DocLinter(rmap).lint()
+                if len(findings) > 0:
+                    raise ReginaldException("\n".join(str(f) for f in findings))
+            doctor.step("lint", lint)
+
+            generators = parsed_args.generator or DEFAULT_GENERATORS
+            with tempfile.TemporaryDirectory(prefix="reginald-doctor-") as tmpdir:
+                for selection in generators:
+                    self._check_generator(doctor, rmap, parsed_args, shlex.split(selection), tmpdir)
+
+        doctor.summarize()
+
+    def _check_generator(self, doctor: 'Doctor', rmap, parsed_args: argparse.Namespace, selection: List[str], tmpdir: str):
+        # Imported here, as the CLI module imports all commands:
+        from reginald.cli import builtin_generators
+
+        name, gen_args = selection[0], selection[1:]
+        if name not in builtin_generators:
+            doctor.fail(f"generate {name}", "Unknown generator.")
+            return
+
+        family = name.split('.')[0]
+        output_file = path.join(tmpdir, name + OUTPUT_EXTENSIONS.get(family, '.txt'))
+
+        def generate():
+            builtin_generators[name].generate(rmap, parsed_args.input_file, output_file, gen_args)
+            return output_file
+        if doctor.step(f"generate {name}", generate) is None:
+            return
+
+        if output_file.endswith('.h'):
+            if shutil.which(parsed_args.cc) is None:
+                doctor.skip(f"compile {name}", f"compiler '{parsed_args.cc}' not found")
+            else:
+                doctor.step(f"compile {name}", lambda: compile_c_header(parsed_args.cc, output_file))
+        elif output_file.endswith('.py'):
+            doctor.step(f"compile {name}", lambda: compile_python(output_file))
+
+
+class Doctor:
+    def __init__(self):
+        self.failures = 0
+
+    def step(self, name: str, action: Callable):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always", ReginaldWarning)
+            try:
+                result = action()
+            except ReginaldException as e:
+                self.fail(name, str(e), caught)
+                return None
+            except SystemExit:
+                # Invalid generator arguments, already reported by argparse:
+                self.fail(name, "Invalid arguments.", caught)
+                return None
+
+        warns = [str(w.message) for w in caught if issubclass(w.category, ReginaldWarning)]
+        print(f"[ ok ] {name}" + (f" ({len(warns)} warning(s))" if len(warns) > 0 else ""))
+        for w in warns:
+            print(f"         Warning: {w}")
+        return result
+
+    def fail(self, name: str, msg: str, caught: Optional[list] = None):
+        self.failures += 1
+        print(f"[FAIL] {name}")
+        for w in caught or []:
+            if issubclass(w.category, ReginaldWarning):
+                print(f"         Warning: {w.message}")
+        for line in msg.splitlines():
+            print(f"         {line}")
+
+    def skip(self, name: str, reason: str):
+        print(f"[skip] {name}: {reason}")
+
+    def summarize(self):
+        if self.failures > 0:
+            raise ReginaldException(f"doctor: {self.failures} check(s) failed.")
+        print("doctor: All checks passed.")
+
+
+def compile_c_header(cc: str, file_name: str):
+    result = subprocess.run([cc, '-std=c11', '-fsyntax-only', '-x', 'c', file_name], capture_output=True, text=True)
+    if result.returncode != 0:
+        raise ReginaldException(result.stderr.strip())
+
+
+def compile_python(file_name: str):
+    try:
+        py_compile.compile(file_name, doraise=True)
+    except py_compile.PyCompileError as e:
+        raise ReginaldException(str(e))
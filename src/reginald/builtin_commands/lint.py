@@ -0,0 +1,33 @@
+import argparse
+from typing import List
+
+from reginald.command import CLICommand
+from reginald.error import ReginaldException
+from reginald.input.lint_docs import DocLinter
+from reginald.input.load_listing import INPUT_FORMATS, load_register_map
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Check documentation strings for placeholders and references to unknown names."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald lint", description=self.description())
+        parser.add_argument('input_file',
+                            help="input register description")
+        parser.add_argument('--input-format', choices=list(INPUT_FORMATS.keys()),
+                            help="format of the input file. If not given, it is inferred from the file extension")
+        parser.add_argument('--doc-lang',
+                            help="documentation language to check, for listings with multi-language docs")
+        parsed_args = parser.parse_args(args)
+
+        rmap = load_register_map(parsed_args.input_file, parsed_args.input_format, parsed_args.doc_lang)
+        findings = DocLinter(rmap).lint()
+
+        for finding in findings:
+            print(finding)
+
+        if len(findings) > 0:
+            raise ReginaldException(f"lint: {len(findings)} problem(s) found.")
+
+        print(f"{parsed_args.input_file}: No problems found.")
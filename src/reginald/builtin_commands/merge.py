@@ -0,0 +1,121 @@
+import argparse
+from math import ceil
+from typing import Any, Dict, List, Tuple
+
+from pydantic import ValidationError
+
+from reginald.command import CLICommand
+from reginald.error import ReginaldException
+from reginald.input.convert_yaml import YAMLConverter
+from reginald.input.dump_listing import OUTPUT_FORMATS, write_listing
+from reginald.input.include_yaml import MERGED_KEYS
+from reginald.input.load_listing import load_listing
+from reginald.input.mount_yaml import is_instance_array
+from reginald.input.parse_yaml import YAML_RegisterMap
+from reginald.input.validate_map import MapValidator
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Merge several register descriptions, each at an address offset, into one listing."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald merge", description=self.description())
+        parser.add_argument('output_file',
+                            help="output listing")
+        parser.add_argument('inputs', nargs='+', metavar='INPUT[@OFFSET]',
+                            help="input register descriptions (any supported format), optionally with an address offset, "
+                            "e.g. analog.yaml@0x1000")
+        parser.add_argument('--map-name',
+                            help="name of the merged map (default: name of the first input)")
+        parser.add_argument('--output-format', choices=list(OUTPUT_FORMATS.keys()),
+                            help="format of the output file. If not given, it is inferred from the file extension")
+        parsed_args = parser.parse_args(args)
+
+        inputs = [parse_input(i) for i in parsed_args.inputs]
+        merged = merge_listings([(file_name, load_listing(file_name), offset) for file_name, offset in inputs])
+        if parsed_args.map_name is not None:
+            merged.map_name = parsed_args.map_name
+
+        output_format = write_listing(merged, parsed_args.output_file, parsed_args.output_format)
+        print(f"{parsed_args.output_file}: Merged {len(inputs)} listings, written as {output_format} listing.")
+
+
+def parse_input(s: str) -> Tuple[str, int]:
+    file_name, sep, offset = s.rpartition('@')
+    if sep == "":
+        return s, 0
+    try:
+        return file_name, int(offset, 0)
+    except ValueError:
+        raise ReginaldException(f"merge: Invalid address offset in '{s}'")
+
+
+def merge_listings(inputs: List[Tuple[str, YAML_RegisterMap, int]]) -> YAML_RegisterMap:
+    first = inputs[0][1]
+    merged = first.model_dump(exclude_none=True)  # type: Dict[str, Any]
+    merged.pop('reserved_fill', None)  # Applied per register below.
+    for key in MERGED_KEYS:
+        merged[key] = {}
+
+    origins = {}  # type: Dict[Tuple[str, str], str]
+    for file_name, listing, offset in inputs:
+        if listing.address_unit_bits != first.address_unit_bits:
+            raise ReginaldException(f"merge: {file_name} uses {listing.address_unit_bits}-bit addresses, "
+                                    f"but {inputs[0][0]} uses {first.address_unit_bits}-bit addresses!")
+        data = listing.model_dump(exclude_none=True)
+
+        for name, entry in data['registers'].items():
+            if is_instance_array(entry.get('instances')):
+                entry['instances']['start'] += offset
+                templates = entry['registers'].values()
+            elif 'instances' in entry:
+                entry['instances'] = {inst: adr + offset for inst, adr in entry['instances'].items()}
+                templates = entry['registers'].values()
+            else:
+                entry['adr'] += offset
+                templates = [entry]
+            # Keep each input's default bitwidth and reserved fill:
+            for template in templates:
+                template.setdefault('bitwidth', listing.default_register_bitwidth)
+                if listing.reserved_fill is not None:
+                    template.setdefault('reserved_fill', listing.reserved_fill)
+
+        for key in MERGED_KEYS:
+            for name, entry in data[key].items():
+                if (key, name) in origins:
+                    raise ReginaldException(f"merge: {key} -> {name} is defined in both {origins[(key, name)]} and {file_name}!")
+                origins[(key, name)] = file_name
+                merged[key][name] = entry
+
+    try:
+        result = YAML_RegisterMap(**merged)
+    except ValidationError as e:
+        raise ReginaldException(f"merge: Merged listing is invalid:\n{e}")
+
+    check_overlaps(result, origins)
+    return result
+
+
+def check_overlaps(listing: YAML_RegisterMap, origins: Dict[Tuple[str, str], str]):
+    rmap = YAMLConverter(listing).convert()
+
+    # Physical register spans, tagged with the input they came from:
+    spans = []
+    for block in rmap.register_blocks.values():
+        origin = origins[('registers', block.name)]
+        for template in block.register_templates.values():
+            for instance_name, instance_adr in block.instances.items():
+                adr = instance_adr + template.adr
+                spans.append((adr, adr + ceil(template.bitwidth / rmap.address_unit_bits), instance_name + template.name, origin))
+
+    spans.sort()
+    for i, (start, end, name, origin) in enumerate(spans):
+        for other_start, _, other_name, other_origin in spans[i + 1:]:
+            if other_start >= end:
+                break
+            if other_origin != origin:
+                raise ReginaldException(f"merge: {name} ({origin}) at 0x{start:X} overlaps with "
+                                        f"{other_name} ({other_origin}) at 0x{other_start:X}!")
+
+    MapValidator(rmap).validate()
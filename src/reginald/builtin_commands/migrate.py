@@ -0,0 +1,66 @@
+import argparse
+import copy
+from typing import List
+
+import yaml
+from yaml.loader import SafeLoader
+
+from reginald.command import CLICommand
+from reginald.error import ReginaldException, warn
+from reginald.input.errors_yaml import format_yaml_error
+from reginald.input.migrate_yaml import (SCHEMA_VERSION, edit_listing_text,
+                                         listing_changes,
+                                         listing_schema_version,
+                                         migrate_listing)
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Upgrade a listing to the current schema version."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald migrate", description=self.description())
+        parser.add_argument('input_file',
+                            help="input register description yaml")
+        parser.add_argument('-o', '--output-file',
+                            help="write the upgraded listing here instead of modifying the input in place")
+        parser.add_argument('--reformat', action='store_true',
+                            help="rewrite the input in place even if its comments and formatting cannot be kept")
+        parsed_args = parser.parse_args(args)
+
+        output_file = parsed_args.output_file or parsed_args.input_file
+
+        try:
+            with open(parsed_args.input_file) as f:
+                text = f.read()
+            data = yaml.load(text, Loader=SafeLoader)
+        except FileNotFoundError:
+            raise ReginaldException(f"File {parsed_args.input_file} not found")
+        except yaml.YAMLError as e:
+            raise ReginaldException(format_yaml_error(e, parsed_args.input_file))
+
+        if not isinstance(data, dict):
+            raise ReginaldException(f"{parsed_args.input_file}: Listing is not a map!")
+
+        version = listing_schema_version(data)
+        if version == SCHEMA_VERSION and output_file == parsed_args.input_file:
+            print(f"{parsed_args.input_file}: Already at schema version {SCHEMA_VERSION}.")
+            return
+
+        migrated = {'schema': SCHEMA_VERSION} | migrate_listing(copy.deepcopy(data))
+        changes = listing_changes(data, migrated)
+
+        # Changes are applied to the text of the listing where possible, to keep its comments and formatting:
+        edited = edit_listing_text(text, data, migrated)
+        if edited is None:
+            if output_file == parsed_args.input_file and not parsed_args.reformat:
+                raise ReginaldException(f"{parsed_args.input_file}: The upgrade cannot keep the comments and formatting of the listing. "
+                                        f"Use --reformat to rewrite it anyway, or -o to write the upgraded listing to another file.")
+            warn(f"{output_file}: Written without the comments and formatting of {parsed_args.input_file}.")
+            edited = yaml.safe_dump(migrated, sort_keys=False, allow_unicode=True)
+        with open(output_file, 'w') as outfile:
+            outfile.write(edited)
+
+        print(f"{output_file}: Written with schema version {SCHEMA_VERSION} (was {version}).")
+        for change in changes:
+            print(f"  {change}")
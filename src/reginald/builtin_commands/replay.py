@@ -0,0 +1,55 @@
+import argparse
+from typing import List
+
+from tabulate import tabulate
+
+from reginald.command import CLICommand
+from reginald.error import ReginaldException, warn
+from reginald.input.load_listing import INPUT_FORMATS, load_register_map
+from reginald.simulate import (STRICTNESS_LEVELS, RegisterFileSimulator,
+                               load_trace)
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Replay a register access trace against a simulated device and report access violations."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald replay", description=self.description())
+        parser.add_argument('input_file',
+                            help="input register description")
+        parser.add_argument('trace_file',
+                            help="trace of register accesses: CSV with columns op,register,value[,width] or JSON list of objects")
+        parser.add_argument('--input-format', choices=list(INPUT_FORMATS.keys()),
+                            help="format of the input file. If not given, it is inferred from the file extension")
+        parser.add_argument('--all', action='store_true',
+                            help="show the final state of all registers, not only of changed ones")
+        parser.add_argument('--strictness', choices=STRICTNESS_LEVELS, default='error',
+                            help="stop at the first violation (panic), fail after the replay (error), or only report violations (log). "
+                                 "Default: error")
+        parsed_args = parser.parse_args(args)
+
+        rmap = load_register_map(parsed_args.input_file, parsed_args.input_format)
+        sim = RegisterFileSimulator(rmap, parsed_args.strictness)
+        sim.replay(load_trace(parsed_args.trace_file), parsed_args.trace_file)
+
+        rows = []
+        for reg in sorted(sim.registers.values(), key=lambda r: r.adr):
+            val = sim.state[reg.name]
+            changed = val != reg.reset_val
+            if changed or parsed_args.all:
+                rows.append([f"0x{reg.adr:X}", reg.name,
+                             "?" if reg.reset_val is None else f"0x{reg.reset_val:X}",
+                             "?" if val is None else f"0x{val:X}",
+                             "*" if changed else ""])
+        print(tabulate(rows, headers=["Address", "Register", "Reset", "Final", "Changed"], tablefmt="simple"))
+
+        if len(sim.violations) > 0:
+            if parsed_args.strictness == 'log':
+                for violation in sim.violations:
+                    warn(violation)
+                return
+            print("")
+            for violation in sim.violations:
+                print(violation)
+            raise ReginaldException(f"replay: {len(sim.violations)} violation(s).")
@@ -0,0 +1,27 @@
+import argparse
+import json
+import sys
+from typing import List
+
+from reginald.command import CLICommand
+from reginald.input.schema_yaml import listing_json_schema
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Write a JSON Schema of the listing format, for editor validation and completion."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald schema", description=self.description())
+        parser.add_argument('output_file', nargs='?',
+                            help="output file (default: stdout)")
+        parsed_args = parser.parse_args(args)
+
+        schema = json.dumps(listing_json_schema(), indent=2) + "\n"
+
+        if parsed_args.output_file is None:
+            sys.stdout.write(schema)
+        else:
+            with open(parsed_args.output_file, 'w') as outfile:
+                outfile.write(schema)
+            print(f"{parsed_args.output_file}: Written listing schema.")
@@ -0,0 +1,88 @@
+import argparse
+from math import ceil
+from typing import List
+
+from tabulate import tabulate
+
+from reginald.command import CLICommand
+from reginald.datamodel import Register
+from reginald.error import ReginaldException
+from reginald.input.load_listing import INPUT_FORMATS, load_register_map
+from reginald.transport import Transport, add_transport_args, open_transport
+
+
+class Command(CLICommand):
+    def description(self) -> str:
+        return "Read all registers of a live device and check them against the register map."
+
+    def run(self, args: List[str]):
+        parser = argparse.ArgumentParser(prog="reginald verify", description=self.description())
+        parser.add_argument('input_file',
+                            help="input register description")
+        parser.add_argument('--input-format', choices=list(INPUT_FORMATS.keys()),
+                            help="format of the input file. If not given, it is inferred from the file extension")
+        add_transport_args(parser)
+        parser.add_argument('--byte-order', choices=['little', 'big'], default='little',
+                            help="byte order of registers wider than one byte (default: little)")
+        parsed_args = parser.parse_args(args)
+
+        rmap = load_register_map(parsed_args.input_file, parsed_args.input_format)
+
+        with open_transport(parsed_args) as transport:
+            rows, failures = verify_registers(rmap.physical_registers.values(), transport, parsed_args.byte_order)
+
+        print(tabulate(rows, headers=["Address", "Register", "Value", "Result"], tablefmt="simple"))
+
+        if failures > 0:
+            raise ReginaldException(f"verify: {failures} of {len(rows)} registers failed.")
+
+
+def is_write_only(reg: Register) -> bool:
+    # Registers without any access information are assumed to be readable.
+    if len(reg.fields) == 0:
+        return False
+    if any(len(field.access) == 0 for field in reg.fields.values()):
+        return False
+    return not reg.is_readable()
+
+
+def verify_registers(regs, transport: Transport, byte_order: str):
+    rows = []
+    failures = 0
+
+    for reg in sorted(regs, key=lambda r: r.adr):
+        if is_write_only(reg):
+            continue
+
+        # Reading would change the state of the device:
+        if len(reg.get_read_side_effects()) > 0:
+            rows.append([f"0x{reg.adr:X}", reg.name, "", f"Skipped (read side effects: {', '.join(reg.get_read_side_effects())})"])
+            continue
+
+        val = int.from_bytes(transport.read(reg.adr, ceil(reg.bitwidth / 8)), byte_order)
+
+        problems = []
+        # Bits updated by hardware do not keep their reset value:
+        stable_mask = ~reg.get_volatile_mask()
+        if reg.reset_val is not None and val & stable_mask != reg.reset_val & stable_mask:
+            problems.append(f"differs from reset value 0x{reg.reset_val:X}")
+
+        if reg.always_write is not None:
+            mask = reg.always_write.get_check_mask()
+            if val & mask != reg.always_write.value & mask:
+                problems.append(f"always_write bits differ from 0x{reg.always_write.value & mask:X}")
+
+        for field in reg.fields.values():
+            if field.enum is None or not field.is_readable() and len(field.access) > 0:
+                continue
+            field_val = field.extract_from(val)
+            if field.lookup_enum_entry_name(field_val) is None:
+                problems.append(f"{field.name}: 0x{field_val:X} is not a valid enum value")
+
+        if len(problems) > 0:
+            failures += 1
+            rows.append([f"0x{reg.adr:X}", reg.name, f"0x{val:X}", "; ".join(problems)])
+        else:
+            rows.append([f"0x{reg.adr:X}", reg.name, f"0x{val:X}", "OK"])
+
+    return rows, failures
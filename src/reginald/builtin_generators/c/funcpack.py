@@ -6,8 +6,10 @@ from typing import Any, Dict, List
 
 from tabulate import tabulate
 
-from reginald.datamodel import (Docs, Field, RegEnum, Register, RegisterBlock,
-                                RegisterMap)
+from reginald.datamodel import (AccessMode, DocRefRenderer, Docs, Field,
+                                RegEnum, Register, RegisterBlock, RegisterMap,
+                                plain_doc_ref)
+from reginald.error import ReginaldException, warn
 from reginald.generator import OutputGenerator
 from reginald.utils import (c_fitting_unsigned_type, c_sanitize,
                             str_pad_to_length)
@@ -28,6 +30,11 @@ ARGS = {
            action=argparse.BooleanOptionalAction,
            help="prefix a field enum with the register name",
            default=True),
+    'field_enum_docs':
+    GenArg(flag='--field-enum-docs',
+           action=argparse.BooleanOptionalAction,
+           help="list the values a field accepts (from its enum) in the field's documentation",
+           default=False),
     'registers_as_bitfields':
     GenArg(flag='--registers-as-bitfields',
            action=argparse.BooleanOptionalAction,
@@ -58,6 +65,70 @@ ARGS = {
            action=argparse.BooleanOptionalAction,
            help="include '_Generic' packing/unpacking macros",
            default=True),
+    'fingerprint':
+    GenArg(flag='--fingerprint',
+           action=argparse.BooleanOptionalAction,
+           help="include the register map fingerprint define",
+           default=True),
+    'device_config':
+    GenArg(flag='--device-config',
+           action=argparse.BooleanOptionalAction,
+           help="include a struct holding the complete device configuration, and functions to apply/read it back",
+           default=False),
+    'transactions':
+    GenArg(flag='--transactions',
+           action=argparse.BooleanOptionalAction,
+           help="include functions that write the registers of a transaction in the required order",
+           default=True),
+    'split_values':
+    GenArg(flag='--split-values',
+           action=argparse.BooleanOptionalAction,
+           help="include functions that combine/split values that are split across several registers",
+           default=True),
+    'block_instances':
+    GenArg(flag='--block-instances',
+           action=argparse.BooleanOptionalAction,
+           help="include an instance struct and array for every register block with multiple instances, to loop over them",
+           default=False),
+    'write_verified':
+    GenArg(flag='--write-verified',
+           action=argparse.BooleanOptionalAction,
+           help="include functions that write a register and verify the write by reading it back",
+           default=False),
+    'decoder':
+    GenArg(flag='--decoder',
+           action=argparse.BooleanOptionalAction,
+           help="include a function that decodes a packed register value by address into a tagged union of all register structs",
+           default=False),
+    'example_checks':
+    GenArg(flag='--example-checks',
+           action=argparse.BooleanOptionalAction,
+           help="include functions that check the listing's register examples against the packing/unpacking functions",
+           default=False),
+    'domain_table':
+    GenArg(flag='--domain-table',
+           action=argparse.BooleanOptionalAction,
+           help="include a table of the clock and power domain of every register, and a function to look them up by address",
+           default=False),
+    'byte_addresses':
+    GenArg(flag='--byte-addresses',
+           action=argparse.BooleanOptionalAction,
+           help="include the byte address of every register, for maps that are not byte-addressed",
+           default=False),
+    'section_order':
+    GenArg(flag='--section-order',
+           action="store",
+           help="order in which sections are generated. Sections not listed follow in default order",
+           default=['constants', 'shared-enums', 'registers', 'generic-macros', 'device-config', 'transactions', 'split-values',
+                    'decoder', 'domain-table'],
+           kwargs={"nargs": "+",
+                   "choices": ['constants', 'shared-enums', 'registers', 'generic-macros', 'device-config', 'transactions',
+                               'split-values', 'decoder', 'domain-table']}),
+    'section_spacing':
+    GenArg(flag='--section-spacing',
+           action="store",
+           help="number of blank lines before each section header. If not given, spacing is left as generated",
+           default=None, kwargs={"type": int}),
     'add_include':
     GenArg(flag='--add-include',
            action="store",
@@ -66,6 +137,15 @@ ARGS = {
 }
 
 
+# Mask defines emitted for registers with side effect fields: Access mode -> description
+SIDE_EFFECT_MASKS = {
+    AccessMode.WRITE_1_CLEAR: "write-1-to-clear",
+    AccessMode.WRITE_1_SET: "write-1-to-set",
+    AccessMode.WRITE_1_TOGGLE: "write-1-to-toggle",
+    AccessMode.READ_CLEAR: "read-clears",
+}
+
+
 class Generator(OutputGenerator):
 
     def __init__(self):
@@ -77,6 +157,9 @@ class Generator(OutputGenerator):
 
     def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
         opts = parse_args(args)
+        if opts.device_config and not (opts.registers and opts.register_functions):
+            raise ReginaldException("c.funcpack: --device-config requires --registers and --register-functions")
+        rmap = rmap.with_rendered_doc_refs(c_doc_ref_renderer(rmap, opts))
         input_file_base = path.basename(input_file)
         output_file_base = path.basename(output_file)
 
@@ -105,39 +188,33 @@ class Generator(OutputGenerator):
             self.emit(f"#include \"{include}\"")
         self.emit(f"")
 
-        if opts.enums:
-            if len(rmap.enums) > 0:
-                self.generate_shared_enums(rmap)
-
-        for block in rmap.register_blocks.values():
-            for template in block.register_templates.values():
-
-                if not register_content_to_generate(template, opts):
-                    continue
-
-                self.emit("")
-                self.emit(str_pad_to_length(f"// ==== {block.name+template.name} register ", "=", 80))
-                if not template.docs.empty():
-                    self.emit(template.docs.as_multi_line(prefix="// "))
-                self.emit(f"")
-
-                if opts.registers:
-                    self.generate_register_defines(rmap, block, template)
-
-                if opts.enums:
-                    self.generate_register_enums(rmap, block, template, opts)
-
-                if len(template.fields) != 0:
-                    # Generate structs + funcs since register has fields
-
-                    if opts.registers:
-                        self.generate_register_struct(rmap, block, template, opts)
+        if opts.fingerprint:
+            self.emit(f"#define {c_macro(rmap.map_name)}_FINGERPRINT (0x{rmap.fingerprint:08X}U) //!< Register map fingerprint")
+            self.emit(f"")
 
-                    if opts.register_functions:
-                        self.generate_register_funcs(rmap, block, template, opts)
+        if rmap.address_unit_bits != 8:
+            self.emit(f"#define {c_macro(rmap.map_name)}_ADDRESS_UNIT_BITS ({rmap.address_unit_bits}U) //!< Bits per register address")
+            self.emit(f"")
 
-        if opts.generic_macros:
-            self.generate_generic_macros(rmap)
+        sections = {
+            'constants': self.generate_constants,
+            'shared-enums': self.generate_shared_enums,
+            'registers': self.generate_registers,
+            'generic-macros': self.generate_generic_macros,
+            'device-config': self.generate_device_config,
+            'transactions': self.generate_transactions,
+            'split-values': self.generate_split_values,
+            'decoder': self.generate_decoder,
+            'domain-table': self.generate_domain_table,
+        }
+
+        section_order = list(dict.fromkeys(opts.section_order + ARGS['section_order'].default))
+        for before, after in [('shared-enums', 'registers'), ('registers', 'device-config'), ('registers', 'transactions'),
+                              ('registers', 'split-values'), ('registers', 'decoder')]:
+            if section_order.index(before) > section_order.index(after):
+                raise ReginaldException(f"c.funcpack: Section '{before}' has to be placed before section '{after}'")
+        for section in section_order:
+            sections[section](rmap, opts)
 
         self.emit(f"")
         self.emit(f"#endif /* {c_macro(output_file_base)} */")
@@ -153,8 +230,30 @@ class Generator(OutputGenerator):
         else:
             self.out.extend(s)
 
-    def generate_shared_enums(self, rmap: RegisterMap):
-        self.emit(str_pad_to_length(f"// ==== Shared enums ", "=", 80))
+    def emit_section_header(self, title: str, opts):
+        if opts.section_spacing is not None:
+            while len(self.out) > 0 and self.out[-1] == "":
+                self.out.pop()
+            self.emit([""] * opts.section_spacing)
+        self.emit(str_pad_to_length(f"// ==== {title} ", "=", 80))
+
+    def generate_constants(self, rmap: RegisterMap, opts):
+        if len(rmap.constants) == 0:
+            return
+
+        self.emit_section_header("Constants", opts)
+        self.emit(f"")
+        for constant in rmap.constants.values():
+            suffix = "U" if constant.value < 2**32 else "ULL"
+            self.emit(doxy_comment(constant.docs))
+            self.emit(f"#define {c_macro(rmap.map_name)}_CONST_{c_macro(constant.name)} (0x{constant.value:X}{suffix})")
+        self.emit(f"")
+
+    def generate_shared_enums(self, rmap: RegisterMap, opts):
+        if not opts.enums or len(rmap.enums) == 0:
+            return
+
+        self.emit_section_header("Shared enums", opts)
         self.emit(f"")
         for enum in rmap.enums.values():
             self.emit(doxy_comment(enum.docs))
@@ -163,9 +262,100 @@ class Generator(OutputGenerator):
                 self.emit(doxy_comment(entry.docs, prefix="  "))
                 self.emit(f"  {name_shared_enum(rmap, enum).upper()}_{c_sanitize(entry.name).upper()} = 0x{entry.value:X}U,")
             self.emit(f"}};")
+            self.generate_enum_from_raw(name_shared_enum(rmap, enum), name_shared_enum(rmap, enum).upper(), enum)
+            self.emit(f"")
+
+    def generate_registers(self, rmap: RegisterMap, opts):
+        if opts.register_functions and any(field.float_format == 'f16'
+                                           for block in rmap.register_blocks.values()
+                                           for template in block.register_templates.values()
+                                           for field in template.fields.values()):
+            self.generate_f16_funcs(rmap, opts)
+
+        if opts.write_verified and opts.registers and opts.register_functions:
+            self.generate_verify_mismatch(rmap, opts)
+
+        # Grouped registers are emitted group by group, ungrouped registers follow:
+        group_order = {group: idx for idx, group in enumerate(rmap.groups)}
+        blocks = sorted(rmap.register_blocks.values(), key=lambda b: group_order.get(b.group, len(group_order)))
+        current_group = None
+        for block in blocks:
+            if len(group_order) > 0 and (block is blocks[0] or block.group != current_group):
+                current_group = block.group
+                self.emit("")
+                self.emit_section_header(f"{block.group} group" if block.group is not None else "Other registers", opts)
+
+            if opts.block_instances and opts.registers and len(block.instances) > 1:
+                self.generate_block_instances(rmap, block, opts)
+
+            for template in block.register_templates.values():
+
+                if not register_content_to_generate(template, opts):
+                    continue
+
+                self.emit("")
+                self.emit_section_header(f"{block.name+template.name} register", opts)
+                if not template.docs.empty():
+                    self.emit(template.docs.as_multi_line(prefix="// "))
+                self.emit(f"")
+
+                if opts.registers:
+                    self.generate_register_defines(rmap, block, template, opts)
+
+                if opts.enums:
+                    self.generate_register_enums(rmap, block, template, opts)
+
+                if not c_packable(template):
+                    warn(f"c.funcpack: registers -> {block.name+template.name}: Register is wider than 64 bits, "
+                         f"only its address and enums are generated.")
+                    continue
+
+                if len(template.fields) != 0:
+                    # Generate structs + funcs since register has fields
+                    for view in template.get_layout_views():
+
+                        if opts.registers:
+                            self.generate_register_struct(rmap, block, view, opts)
+
+                        if opts.register_functions:
+                            self.generate_register_funcs(rmap, block, view, opts)
+
+                    if opts.registers and opts.register_functions and template.variant_tag is not None:
+                        self.generate_register_variant_dispatch(rmap, block, template)
+
+                if opts.register_functions and template.unlock is not None:
+                    self.generate_register_unlock(rmap, block, template, opts)
+
+    def generate_block_instances(self, rmap: RegisterMap, block: RegisterBlock, opts):
+        struct_name = name_block_instance_struct(rmap, block)
+        macro_count = f"{c_macro(rmap.map_name)}_{c_macro(block.name)}_INSTANCE_COUNT"
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+
+        self.emit("")
+        self.emit_section_header(f"{block.name} block instances", opts)
+        self.emit("")
+        self.emit(doxy_comment(Docs(brief=f"Instance of the {block.name} block.", doc=None)))
+        self.emit(f"struct {struct_name} {{")
+        self.emit(f"  {adr_type} base; //!< Block start address")
+        self.emit(f"}};")
+        self.emit(f"")
+        self.emit(f"#define {macro_count} ({len(block.instances)}U) //!< Number of {block.name} block instances")
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief=f"All instances of the {block.name} block.", doc=None)))
+        self.emit(f"static const struct {struct_name} {struct_name}s[{macro_count}] = {{")
+        for instance_name, instance_start in block.instances.items():
+            self.emit(f"  {{.base = 0x{instance_start:X}U}}, //!< {instance_name}")
+        self.emit(f"}};")
+
+        for template in block.register_templates.values():
             self.emit(f"")
+            brief = f"Address of the {block.name+template.name} register of a {block.name} block instance."
+            self.emit(doxy_comment(Docs(brief=brief, doc=None)))
+            self.emit(f"static inline {adr_type} {name_register_struct(rmap, block, template)}_adr(const struct {struct_name} *inst) {{")
+            self.emit(f"  return ({adr_type}) (inst->base + 0x{template.adr:X}U);")
+            self.emit(f"}}")
 
-    def generate_register_defines(self, rmap: RegisterMap, block: RegisterBlock, template: Register):
+    def generate_register_defines(self, rmap: RegisterMap, block: RegisterBlock, template: Register, opts):
         macro_reg_template = c_macro(block.name + template.name)
         macro_prefix = c_macro(rmap.map_name) + "_REG"
 
@@ -175,17 +365,66 @@ class Generator(OutputGenerator):
             defines.append([f"#define {macro_prefix}_{c_macro(instance_name+template.name)}",
                             f"(0x{template.adr+instance_start:X}U)",
                             f"//!< {instance_name+template.name} register address"])
+            if opts.byte_addresses and rmap.address_unit_bits != 8:
+                defines.append([f"#define {macro_prefix}_{c_macro(instance_name+template.name)}__BYTE_ADR",
+                                f"(0x{rmap.byte_address(template.adr+instance_start):X}U)",
+                                f"//!< {instance_name+template.name} register byte address"])
+            for alias in block.get_alias_names(template, instance_name):
+                defines.append([f"#define {macro_prefix}_{c_macro(alias)}",
+                                f"{macro_prefix}_{c_macro(instance_name+template.name)}",
+                                f"//!< @deprecated Renamed to {instance_name+template.name}"])
+            security = block.get_security(template, instance_name)
+            if security.secure is not None:
+                defines.append([f"#define {macro_prefix}_{c_macro(instance_name+template.name)}__SECURE",
+                                f"({int(security.secure)}U)",
+                                f"//!< 1 if {instance_name+template.name} is only accessible from the secure state"])
+            if security.privileged is not None:
+                defines.append([f"#define {macro_prefix}_{c_macro(instance_name+template.name)}__PRIVILEGED",
+                                f"({int(security.privileged)}U)",
+                                f"//!< 1 if {instance_name+template.name} is only accessible from privileged code"])
+
+        if block.array_stride is not None:
+            base = min(block.instances.values()) + template.adr
+            defines.append([f"#define {macro_prefix}_{macro_reg_template}__ADR(i)",
+                            f"(0x{base:X}U + (i) * 0x{block.array_stride:X}U)",
+                            f"//!< Address of {block.name+template.name} array register i"])
+            defines.append([f"#define {macro_prefix}_{macro_reg_template}__COUNT",
+                            f"({len(block.instances)}U)",
+                            f"//!< Number of {block.name+template.name} array registers"])
 
         if len(block.instances) > 1 and len(block.register_templates) > 1:
             defines.append([f"#define {macro_prefix}_{c_macro(block.name+template.name)}__OFFSET",
                             f"(0x{template.adr:X}U)",
                             f"//!< Offset of {block.name+template.name} register from {block.name} block start"])
 
+        if template.array is not None and template.array.index == 0:
+            array = template.array
+            for instance_name, instance_start in block.instances.items():
+                defines.append([f"#define {macro_prefix}_{c_macro(instance_name+array.name)}__ADR(i)",
+                                f"(0x{instance_start+template.adr:X}U + (i) * 0x{array.stride:X}U)",
+                                f"//!< Address of {instance_name+array.name} array register i"])
+            defines.append([f"#define {macro_prefix}_{c_macro(block.name+array.name)}__OFFSET(i)",
+                            f"(0x{template.adr:X}U + (i) * 0x{array.stride:X}U)",
+                            f"//!< Offset of {block.name+array.name} array register i from {block.name} block start"])
+            defines.append([f"#define {macro_prefix}_{c_macro(block.name+array.name)}__COUNT",
+                            f"({array.count}U)",
+                            f"//!< Number of {block.name+array.name} array registers"])
+
+        # Values of registers wider than the widest C integer have no literal:
+        if not c_packable(template):
+            self.emit(tabulate(defines, tablefmt='plain', disable_numparse=True))
+            return
+
         if template.reset_val is not None:
             defines.append([f"#define {macro_prefix}_{macro_reg_template}__RESET",
                             f"(0x{template.reset_val:X}U)",
                             f"//!< {block.name+template.name} register reset value"])
 
+        if template.has_recommended_val():
+            defines.append([f"#define {macro_prefix}_{macro_reg_template}__RECOMMENDED",
+                            f"(0x{template.get_recommended_val():X}U)",
+                            f"//!< {block.name+template.name} register recommended value (reset value with field defaults)"])
+
         if template.always_write is not None:
             defines.append([f"#define {macro_prefix}_{macro_reg_template}__ALWAYSWRITE_MASK",
                             f"(0x{template.always_write.bits.get_bitmask():X}U)",
@@ -193,6 +432,34 @@ class Generator(OutputGenerator):
             defines.append([f"#define {macro_prefix}_{macro_reg_template}__ALWAYSWRITE_VALUE",
                             f"(0x{template.always_write.value:X}U)",
                             f"//!< {block.name+template.name} register always write value"])
+            if template.always_write.dont_care != 0:
+                defines.append([f"#define {macro_prefix}_{macro_reg_template}__ALWAYSWRITE_DONTCARE",
+                                f"(0x{template.always_write.dont_care:X}U)",
+                                f"//!< {block.name+template.name} register always write bits that are undefined when read"])
+
+        for field in template.get_all_fields().values():
+            for limit, val in [('MIN', field.min), ('MAX', field.max)]:
+                if val is not None:
+                    defines.append([f"#define {macro_prefix}_{macro_reg_template}__{c_macro(field.name)}_{limit}",
+                                    f"(0x{val:X}U)",
+                                    f"//!< {field.name} valid range {limit.lower()}imum (raw)"])
+
+        if len(template.get_read_side_effects()) > 0:
+            defines.append([f"#define {macro_prefix}_{macro_reg_template}__READ_DESTRUCTIVE",
+                            f"(1U)",
+                            f"//!< Reading {block.name+template.name} has side effects, never cache or read speculatively"])
+
+        if template.is_volatile():
+            defines.append([f"#define {macro_prefix}_{macro_reg_template}__VOLATILE_MASK",
+                            f"(0x{template.get_volatile_mask():X}U)",
+                            f"//!< {block.name+template.name} bits updated by hardware, never cache"])
+
+        for mode, description in SIDE_EFFECT_MASKS.items():
+            mask = template.get_access_mask(mode)
+            if mask != 0:
+                defines.append([f"#define {macro_prefix}_{macro_reg_template}__{mode.to_str().upper()}_MASK",
+                                f"(0x{mask:X}U)",
+                                f"//!< {block.name+template.name} {description} bits"])
 
         self.emit(tabulate(defines, tablefmt='plain', disable_numparse=True))
 
@@ -206,17 +473,49 @@ class Generator(OutputGenerator):
                 self.emit(f"  {c_macro(name_register_enum(rmap, block,template, enum, opts))}_{c_macro(entry.name)} "
                           f"= 0x{entry.value:X}U,")
             self.emit(f"}};")
+            enum_name = name_register_enum(rmap, block, template, enum, opts)
+            self.generate_enum_from_raw(enum_name, c_macro(enum_name), enum)
+
+    def generate_enum_from_raw(self, enum_name: str, entry_prefix: str, enum: RegEnum):
+        # Enums with a catch-all entry get a conversion that maps all values
+        # without an entry to it, so unpacking always yields a valid entry:
+        catch_all = enum.get_catch_all()
+        if catch_all is None:
+            return
+        entry_names = {entry.name: f"{entry_prefix}_{c_sanitize(entry.name).upper()}" for entry in enum.entries.values()}
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief=f"Convert a raw field value, mapping values without an entry to "
+                                          f"{entry_names[catch_all.name]}.", doc=None)))
+        self.emit(f"static inline enum {enum_name} {enum_name}_from_raw(uint64_t raw) {{")
+        self.emit(f"  switch (raw) {{")
+        for entry in enum.entries.values():
+            if not entry.catch_all:
+                self.emit(f"    case 0x{entry.value:X}U: return {entry_names[entry.name]};")
+        self.emit(f"    default: return {entry_names[catch_all.name]};")
+        self.emit(f"  }}")
+        self.emit(f"}}")
 
     def generate_register_struct(self, rmap: RegisterMap, block: RegisterBlock, template: Register, opts):
         struct_name = name_register_struct(rmap, block, template)
 
         self.emit("")
-        self.emit(doxy_comment(template.docs, note="use pack/unpack/overwrite functions for conversion to/form packed register value"))
+        self.emit(doxy_comment(template.docs, note="use pack/unpack/overwrite functions for conversion to/form packed register value",
+                               warning=read_side_effect_warning(template)))
         self.emit(f"struct {struct_name} {{")
-        for field in template.fields.values():
+        for field in template.get_layout_fields().values():
+            if field.array is not None and field.array.index != 0:
+                continue
             field_type = register_struct_member_type(rmap, block, template, field, opts)
-            self.emit(doxy_comment(field.docs, prefix="  "))
-            if opts.registers_as_bitfields:
+            warning = None if field.read_side_effect is None else f"Reading has side effects ({field.read_side_effect})."
+            note = None if field.unit is None else f"Unit: {field.unit}"
+            if opts.field_enum_docs:
+                self.emit(doxy_comment(field_docs_with_enum(rmap, block, template, field, opts), prefix="  ", note=note, warning=warning))
+            else:
+                self.emit(doxy_comment(field.docs, prefix="  ", note=note, warning=warning))
+            if field.array is not None:
+                # Arrays of bitfields are not possible:
+                self.emit(f"  {field_type} {c_code(field.array.name)}[{field.array.count}];")
+            elif opts.registers_as_bitfields:
                 self.emit(f"  {field_type} {c_code(field.name)} : {field.bits.total_width()};")
             else:
                 self.emit(f"  {field_type} {c_code(field.name)};")
@@ -228,51 +527,518 @@ class Generator(OutputGenerator):
         macro_reg_template = c_macro(block.name + template.name)
         macro_prefix = c_macro(rmap.map_name) + "_REG"
 
+        # The read view of registers with separate layouts is only unpacked, the write view only packed:
+        if template.layout_view != 'read':
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(
+                brief="Convert register struct to packed register value.",
+                doc="All bits that are not part of a field or specified as 'always write' are kept as in 'val'.")))
+            self.emit(f"static inline {packed_type} {struct_name}_overwrite(const struct {struct_name} *r, {packed_type} val) {{")
+            if template.variant is not None:
+                # Always write bits of a variant include its tag:
+                assert template.always_write is not None
+                self.emit(f"  val &= ({packed_type}) ~0x{template.always_write.bits.get_bitmask():X}U;")
+                self.emit(f"  val |= 0x{template.always_write.value:X}U;")
+            elif template.always_write is not None:
+                self.emit(f"  val &= ~{macro_prefix}_{macro_reg_template}__ALWAYSWRITE_MASK;")
+                self.emit(f"  val |= {macro_prefix}_{macro_reg_template}__ALWAYSWRITE_VALUE;")
+            for field in template.get_layout_fields().values():
+                mask = field.bits.get_bitmask()
+                unpos_mask = field.bits.get_unpositioned_bits().get_bitmask()
+                shift = field.bits.lsb_position()
+                if field.array is None:
+                    member = c_byte_swap(f"r->{c_code(field.name)}", field)
+                    self.emit(
+                        f"  val = (val & ~0x{mask:X}U) | ({packed_type}) (({member} & 0x{unpos_mask:X}U) << {shift}U);")
+                elif field.array.index == 0:
+                    array = field.array
+                    self.emit(f"  for (unsigned i = 0; i < {array.count}U; i++) {{")
+                    self.emit(f"    const unsigned shift = {shift}U + i * {array.stride}U;")
+                    self.emit(f"    val = (val & ({packed_type}) ~(({packed_type}) 0x{unpos_mask:X}U << shift)) | "
+                              f"({packed_type}) (({packed_type}) (r->{c_code(array.name)}[i] & 0x{unpos_mask:X}U) << shift);")
+                    self.emit(f"  }}")
+            self.emit(f"  return val;")
+            self.emit(f"}}")
+
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(brief="Convert register struct to packed register value.", doc=None)))
+            self.emit(f"static inline {packed_type} {struct_name}_pack(const struct {struct_name} *r) {{")
+            self.emit(f"  return {struct_name}_overwrite(r, 0);")
+            self.emit(f"}}")
+
+        if template.layout_view != 'write':
+            for field in template.fields.values():
+                if field.enum is not None and field.enum.get_catch_all() is None and not enum_covers_field(field):
+                    warn(f"c.funcpack: registers -> {block.name+template.name} -> {field.name}: Enum {field.enum.name} does not cover "
+                         f"all values of the field. Unpacking may produce values without a matching enum entry.")
+
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(brief="Convert packed register value to register struct initialization", doc=None)))
+            self.emit(f"#define {c_macro(struct_name)}_UNPACK(_VAL_) {{ ".ljust(99, " ") + "\\")
+            for field in template.get_layout_fields().values():
+                mask = field.bits.get_bitmask()
+                shift = field.bits.lsb_position()
+                unpacked = field_from_raw(rmap, block, template, field, c_byte_swap(f"((_VAL_) & 0x{mask:X}U) >> {shift}U", field), opts)
+                if field.array is None:
+                    self.emit(f"  .{c_code(field.name)} = {unpacked},".ljust(99, " ") + "\\")
+                    continue
+                if field.array.index == 0:
+                    self.emit(f"  .{c_code(field.array.name)} = {{".ljust(99, " ") + "\\")
+                self.emit(f"    {unpacked},".ljust(99, " ") + "\\")
+                if field.array.index == field.array.count - 1:
+                    self.emit(f"  }},".ljust(99, " ") + "\\")
+            self.emit(f"}}")
+            self.emit(f"")
+
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(brief="Convert packed register value to into a register struct.", doc=None)))
+            self.emit(f"static inline void {struct_name}_unpack_into({packed_type} val, struct {struct_name} *s) {{")
+            for field in template.get_layout_fields().values():
+                mask = field.bits.get_bitmask()
+                shift = field.bits.lsb_position()
+                if field.array is None:
+                    unpacked = field_from_raw(rmap, block, template, field, c_byte_swap(f"(val & 0x{mask:X}U) >> {shift}U", field), opts)
+                    self.emit(f"  s->{c_code(field.name)} = {unpacked};")
+                elif field.array.index == 0:
+                    unpos_mask = field.bits.get_unpositioned_bits().get_bitmask()
+                    array = field.array
+                    unpacked = field_from_raw(rmap, block, template, field,
+                                              f"(val >> ({shift}U + i * {array.stride}U)) & 0x{unpos_mask:X}U", opts)
+                    self.emit(f"  for (unsigned i = 0; i < {array.count}U; i++) {{")
+                    self.emit(f"    s->{c_code(array.name)}[i] = {unpacked};")
+                    self.emit(f"  }}")
+            self.emit(f"}}")
+
+        if template.has_recommended_val():
+            self.generate_register_recommended(rmap, block, template, opts)
+
+        # Accessors of internal fields are only available if '<MAP>_INTERNAL'
+        # is defined. Struct members stay, to keep the layout:
+        if template.get_access_mask(AccessMode.WRITE_1_CLEAR) != 0:
+            self.generate_register_clear_flags(rmap, block, template)
+
+        for field in template.get_layout_fields().values():
+            if field.self_clearing:
+                self.emit_internal_guard(rmap, field, True)
+                self.generate_register_strobe(rmap, block, template, field)
+                self.emit_internal_guard(rmap, field, False)
+
+        for field in template.get_view_fields().values():
+            self.emit_internal_guard(rmap, field, True)
+            self.generate_register_view_funcs(rmap, block, template, field, opts)
+            self.emit_internal_guard(rmap, field, False)
+
+        for field in template.fields.values():
+            if field.array is not None and field.array.index != 0:
+                continue
+            if field.fixed_point is None and field.float_format is None:
+                continue
+            self.emit_internal_guard(rmap, field, True)
+            if field.fixed_point is not None:
+                self.generate_register_fixed_point_funcs(rmap, block, template, field, opts)
+            if field.float_format is not None:
+                self.generate_register_float_funcs(rmap, block, template, field, opts)
+            self.emit_internal_guard(rmap, field, False)
+
+        if template.layout_view != 'read' and any(field.has_range() for field in template.get_layout_fields().values()):
+            self.generate_register_pack_checked(rmap, block, template)
+
+        # Registers with variants are only written (and verified) through the struct of a variant:
+        if (opts.write_verified and opts.registers and verify_writable(template) and verify_mask(template) != 0
+                and len(template.get_read_side_effects()) == 0 and (template.variant_tag is None or template.variant is not None)):
+            self.generate_register_write_verified(rmap, block, template)
+
+        if opts.example_checks and len(template.examples) > 0:
+            self.generate_register_example_checks(rmap, block, template)
+
+    def generate_register_variant_dispatch(self, rmap: RegisterMap, block: RegisterBlock, template: Register):
+        assert template.variant_tag is not None
+        struct_name = name_register_struct(rmap, block, template)
+        packed_type = c_fitting_unsigned_type(template.bitwidth)
+        tag_field = template.fields[template.variant_tag]
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief=f"Variants of the register, selected by {tag_field.name}.", doc=None)))
+        self.emit(f"enum {struct_name}_variant_id {{")
+        self.emit(f"  {c_macro(struct_name)}_VARIANT_NONE = 0, //!< {tag_field.name} selects no variant")
+        for variant in template.variants.values():
+            self.emit(f"  {c_macro(struct_name)}_VARIANT_{c_macro(variant.name)}, //!< {tag_field.name} = 0x{variant.tag:X}")
+        self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="An unpacked register value of any variant.", doc="'id' selects the valid member of 'variant'.")))
+        self.emit(f"struct {struct_name}_variants {{")
+        self.emit(f"  enum {struct_name}_variant_id id;")
+        self.emit(f"  union {{")
+        for variant in template.variants.values():
+            variant_struct = name_register_struct(rmap, block, template.get_variant_view(variant.name))
+            self.emit(f"    struct {variant_struct} {c_code(variant.name)};")
+        self.emit(f"  }} variant;")
+        self.emit(f"}};")
+
         self.emit(f"")
         self.emit(doxy_comment(Docs(
-            brief="Convert register struct to packed register value.",
-            doc="All bits that are not part of a field or specified as 'always write' are kept as in 'val'.")))
-        self.emit(f"static inline {packed_type} {struct_name}_overwrite(const struct {struct_name} *r, {packed_type} val) {{")
+            brief=f"Convert packed register value into the struct of the variant selected by {tag_field.name}.",
+            doc=f"Returns 0, or -1 if the tag selects no variant (with 'id' set to {c_macro(struct_name)}_VARIANT_NONE).")))
+        self.emit(f"static inline int {struct_name}_unpack_variant({packed_type} val, struct {struct_name}_variants *out) {{")
+        unpos_mask = tag_field.bits.get_unpositioned_bits().get_bitmask()
+        self.emit(f"  switch ((val >> {tag_field.bits.lsb_position()}U) & 0x{unpos_mask:X}U) {{")
+        for variant in template.variants.values():
+            variant_struct = name_register_struct(rmap, block, template.get_variant_view(variant.name))
+            self.emit(f"    case 0x{variant.tag:X}U:")
+            self.emit(f"      out->id = {c_macro(struct_name)}_VARIANT_{c_macro(variant.name)};")
+            self.emit(f"      {variant_struct}_unpack_into(val, &out->variant.{c_code(variant.name)});")
+            self.emit(f"      return 0;")
+        self.emit(f"    default:")
+        self.emit(f"      out->id = {c_macro(struct_name)}_VARIANT_NONE;")
+        self.emit(f"      return -1;")
+        self.emit(f"  }}")
+        self.emit(f"}}")
+
+    def generate_register_pack_checked(self, rmap: RegisterMap, block: RegisterBlock, template: Register):
+        struct_name = name_register_struct(rmap, block, template)
+        packed_type = c_fitting_unsigned_type(template.bitwidth)
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="Convert register struct to packed register value, checking the valid field ranges.",
+                                    doc="Returns 0, or -1 (without writing 'out') if a field is outside of its valid range.")))
+        self.emit(f"static inline int {struct_name}_pack_checked(const struct {struct_name} *r, {packed_type} *out) {{")
+        # Views are not members of the register struct:
+        for field in template.get_layout_fields().values():
+            if not field.has_range():
+                continue
+            checks = []
+            if field.min is not None and field.min > 0:
+                checks.append(f"r->{field_member(field)} < 0x{field.min:X}U")
+            if field.max is not None and field.max < field.bits.get_unpositioned_bits().get_bitmask():
+                checks.append(f"r->{field_member(field)} > 0x{field.max:X}U")
+            if len(checks) > 0:
+                self.emit(f"  if ({' || '.join(checks)}) return -1;")
+        self.emit(f"  *out = {struct_name}_pack(r);")
+        self.emit(f"  return 0;")
+        self.emit(f"}}")
+
+    def generate_register_clear_flags(self, rmap: RegisterMap, block: RegisterBlock, template: Register):
+        struct_name = name_register_struct(rmap, block, template)
+        packed_type = c_fitting_unsigned_type(template.bitwidth)
+        macro_reg = f"{c_macro(rmap.map_name)}_REG_{c_macro(block.name + template.name)}"
+        write_1_mask = (template.get_access_mask(AccessMode.WRITE_1_CLEAR) | template.get_access_mask(AccessMode.WRITE_1_SET) |
+                        template.get_access_mask(AccessMode.WRITE_1_TOGGLE))
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Packed register value that clears the given write-1-to-clear flags.",
+            doc=f"All other bits are kept as in 'val' (e.g. the current register value), except for write-1 bits, "
+                f"which are written as 0 to leave them unchanged. Only bits in {macro_reg}__W1C_MASK are taken from 'flags'.")))
+        self.emit(f"static inline {packed_type} {struct_name}_clear_flags({packed_type} val, {packed_type} flags) {{")
+        self.emit(f"  return (val & ({packed_type}) ~0x{write_1_mask:X}U) | (flags & {macro_reg}__W1C_MASK);")
+        self.emit(f"}}")
+
+    def generate_register_strobe(self, rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field):
+        struct_name = name_register_struct(rmap, block, template)
+        packed_type = c_fitting_unsigned_type(template.bitwidth)
+        val = field.bits.get_bitmask()
         if template.always_write is not None:
-            self.emit(f"  val &= ~{macro_prefix}_{macro_reg_template}__ALWAYSWRITE_MASK;")
-            self.emit(f"  val |= {macro_prefix}_{macro_reg_template}__ALWAYSWRITE_VALUE;")
-        for field in template.fields.values():
-            mask = field.bits.get_bitmask()
-            unpos_mask = field.bits.get_unpositioned_bits().get_bitmask()
-            shift = field.bits.lsb_position()
-            self.emit(
-                f"  val = (val & ~0x{mask:X}U) | ({packed_type}) ((r->{c_code(field.name)} & 0x{unpos_mask:X}U) << {shift}U);")
-        self.emit(f"  return val;")
+            val |= template.always_write.value
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief=f"Packed register value that triggers {field.name}.",
+            doc=f"Sets only the self-clearing {field.name} bit (and the always-write bits), all other fields are written as 0.")))
+        self.emit(f"static inline {packed_type} {struct_name}_strobe_{c_code(field.name)}(void) {{")
+        self.emit(f"  return ({packed_type}) 0x{val:X}U;")
         self.emit(f"}}")
 
+    def generate_register_recommended(self, rmap: RegisterMap, block: RegisterBlock, template: Register, opts):
+        struct_name = name_register_struct(rmap, block, template)
+        defaults = [field.name for field in template.get_layout_fields().values() if field.default is not None]
+        val = template.get_recommended_val()
+
         self.emit(f"")
-        self.emit(doxy_comment(Docs(brief="Convert register struct to packed register value.", doc=None)))
-        self.emit(f"static inline {packed_type} {struct_name}_pack(const struct {struct_name} *r) {{")
-        self.emit(f"  return {struct_name}_overwrite(r, 0);")
+        self.emit(doxy_comment(Docs(
+            brief="Register struct with the recommended value of every field.",
+            doc=f"Fields with a default ({', '.join(defaults)}) are set to it, all other fields "
+                f"{'keep their reset value' if template.reset_val is not None else 'are 0'}.")))
+        self.emit(f"static inline struct {struct_name} {struct_name}_recommended(void) {{")
+        self.emit(f"  struct {struct_name} r;")
+        for field in template.get_layout_fields().values():
+            raw = f"0x{field.extract_from(val):X}U"
+            self.emit(f"  r.{field_member(field)} = {field_from_raw(rmap, block, template, field, raw, opts)};")
+        self.emit(f"  return r;")
         self.emit(f"}}")
 
+    def emit_internal_guard(self, rmap: RegisterMap, field: Field, begin: bool):
+        if not field.internal:
+            return
+        if begin:
+            self.emit(f"")
+            self.emit(f"#ifdef {c_macro(rmap.map_name)}_INTERNAL")
+        else:
+            self.emit(f"#endif /* {c_macro(rmap.map_name)}_INTERNAL */")
+
+    def generate_register_view_funcs(self, rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field, opts):
+        struct_name = name_register_struct(rmap, block, template)
+        packed_type = c_fitting_unsigned_type(template.bitwidth)
+        field_type = register_struct_member_type(rmap, block, template, field, opts)
+        mask = field.bits.get_bitmask()
+        unpos_mask = field.bits.get_unpositioned_bits().get_bitmask()
+        shift = field.bits.lsb_position()
+
         self.emit(f"")
-        self.emit(doxy_comment(Docs(brief="Convert packed register value to register struct initialization", doc=None)))
-        self.emit(f"#define {c_macro(struct_name)}_UNPACK(_VAL_) {{ ".ljust(99, " ") + "\\")
-        for field in template.fields.values():
-            mask = field.bits.get_bitmask()
-            field_type = register_struct_member_type(rmap, block, template, field, opts)
-            shift = field.bits.lsb_position()
-            self.emit(f"  .{c_code(field.name)} = ({field_type}) (((_VAL_) & 0x{mask:X}U) >> {shift}U),".ljust(99, " ") + "\\")
+        self.emit(doxy_comment(field_docs_with_enum(rmap, block, template, field, opts) if opts.field_enum_docs else field.docs,
+                               note=f"View: Extract {field.name} from a packed register value."))
+        self.emit(f"static inline {field_type} {struct_name}_get_{c_code(field.name)}({packed_type} val) {{")
+        self.emit(f"  return {field_from_raw(rmap, block, template, field, f'(val & 0x{mask:X}U) >> {shift}U', opts)};")
         self.emit(f"}}")
+
         self.emit(f"")
+        self.emit(doxy_comment(Docs(brief=f"View: Replace {field.name} in a packed register value.", doc=None)))
+        self.emit(f"static inline {packed_type} {struct_name}_set_{c_code(field.name)}({packed_type} val, {field_type} v) {{")
+        self.emit(f"  return (val & ~0x{mask:X}U) | ({packed_type}) ((v & 0x{unpos_mask:X}U) << {shift}U);")
+        self.emit(f"}}")
+
+    def generate_register_fixed_point_funcs(self, rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field, opts):
+        struct_name = name_register_struct(rmap, block, template)
+        field_type = register_struct_member_type(rmap, block, template, field, opts)
+        fixed_point = field.fixed_point
+        assert fixed_point is not None
+        width = field.bits.total_width()
+        unpos_mask = field.bits.get_unpositioned_bits().get_bitmask()
+        scale = c_float(fixed_point.scale)
+        offset = c_float(fixed_point.offset)
+
+        if fixed_point.signed:
+            # Sign-extend two's complement raw value:
+            sign = 1 << (width - 1)
+            raw_value = f"(float) ((int64_t) (raw ^ 0x{sign:X}U) - 0x{sign:X})"
+            raw_min, raw_max = -sign, sign - 1
+        else:
+            raw_value = f"(float) raw"
+            raw_min, raw_max = 0, unpos_mask
 
         self.emit(f"")
-        self.emit(doxy_comment(Docs(brief="Convert packed register value to into a register struct.", doc=None)))
-        self.emit(f"static inline void {struct_name}_unpack_into({packed_type} val, struct {struct_name} *s) {{")
-        for field in template.fields.values():
-            mask = field.bits.get_bitmask()
-            field_type = register_struct_member_type(rmap, block, template, field, opts)
-            shift = field.bits.lsb_position()
-            self.emit(f"  s->{c_code(field.name)} = ({field_type}) ((val & 0x{mask:X}U) >> {shift}U);")
+        unit = f" [{field.unit}]" if field.unit is not None else ""
+        self.emit(doxy_comment(Docs(brief=f"Convert a raw {field.name} value to its physical value{unit}.",
+                                    doc=f"Physical value = raw * {fixed_point.scale!r} + {fixed_point.offset!r}"
+                                        f"{' (raw is signed)' if fixed_point.signed else ''}")))
+        self.emit(f"static inline float {struct_name}_{c_code(field_member_name(field))}_to_float({field_type} raw) {{")
+        self.emit(f"  return {raw_value} * {scale} + {offset};")
+        self.emit(f"}}")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief=f"Convert a physical value{unit} to the nearest raw {field.name} value.",
+                                    doc=f"Values outside the representable range are saturated.")))
+        self.emit(f"static inline {field_type} {struct_name}_{c_code(field_member_name(field))}_from_float(float v) {{")
+        self.emit(f"  const float raw = (v - {offset}) / {scale};")
+        self.emit(f"  if (raw <= {c_float(raw_min)}) return ({field_type}) 0x{raw_min & unpos_mask:X}U;")
+        self.emit(f"  if (raw >= {c_float(raw_max)}) return ({field_type}) 0x{raw_max & unpos_mask:X}U;")
+        self.emit(f"  return ({field_type}) ((uint64_t) (int64_t) (raw + (raw >= 0.0f ? 0.5f : -0.5f)) & 0x{unpos_mask:X}U);")
+        self.emit(f"}}")
+
+    def generate_register_float_funcs(self, rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field, opts):
+        struct_name = name_register_struct(rmap, block, template)
+        field_type = register_struct_member_type(rmap, block, template, field, opts)
+        mapname_c = c_code(rmap.map_name)
+
+        self.emit(f"")
+        unit = f" [{field.unit}]" if field.unit is not None else ""
+        self.emit(doxy_comment(Docs(brief=f"Convert a raw {field.name} value to the {field.float_format} float{unit} it holds.", doc=None)))
+        self.emit(f"static inline float {struct_name}_{c_code(field_member_name(field))}_to_float({field_type} raw) {{")
+        if field.float_format == 'f16':
+            self.emit(f"  return {mapname_c}_f16_to_float(raw);")
+        else:
+            self.emit(f"  union {{ uint32_t u; float f; }} c;")
+            self.emit(f"  c.u = raw;")
+            self.emit(f"  return c.f;")
+        self.emit(f"}}")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief=f"Convert a float{unit} to the raw {field.float_format} {field.name} value.", doc=None)))
+        self.emit(f"static inline {field_type} {struct_name}_{c_code(field_member_name(field))}_from_float(float v) {{")
+        if field.float_format == 'f16':
+            self.emit(f"  return {mapname_c}_float_to_f16(v);")
+        else:
+            self.emit(f"  union {{ uint32_t u; float f; }} c;")
+            self.emit(f"  c.f = v;")
+            self.emit(f"  return c.u;")
+        self.emit(f"}}")
+
+    def generate_f16_funcs(self, rmap: RegisterMap, opts):
+        mapname_c = c_code(rmap.map_name)
+
+        self.emit("")
+        self.emit_section_header("Half-precision float conversion", opts)
+        self.emit("")
+        self.emit(doxy_comment(Docs(brief="Convert an IEEE-754 binary16 value to a float.", doc=None)))
+        self.emit(f"static inline float {mapname_c}_f16_to_float(uint16_t h) {{")
+        self.emit(f"  const uint32_t sign = (uint32_t) (h & 0x8000U) << 16;")
+        self.emit(f"  const uint32_t exp = (h >> 10) & 0x1FU;")
+        self.emit(f"  uint32_t mant = h & 0x3FFU;")
+        self.emit(f"  union {{ uint32_t u; float f; }} c;")
+        self.emit(f"  if (exp == 0x1FU) {{")
+        self.emit(f"    c.u = sign | 0x7F800000U | (mant << 13); // Inf/NaN")
+        self.emit(f"  }} else if (exp != 0U) {{")
+        self.emit(f"    c.u = sign | ((exp + 112U) << 23) | (mant << 13);")
+        self.emit(f"  }} else if (mant == 0U) {{")
+        self.emit(f"    c.u = sign; // Zero")
+        self.emit(f"  }} else {{")
+        self.emit(f"    // Subnormal: Normalize.")
+        self.emit(f"    uint32_t e = 113U;")
+        self.emit(f"    while ((mant & 0x400U) == 0U) {{")
+        self.emit(f"      mant <<= 1;")
+        self.emit(f"      e--;")
+        self.emit(f"    }}")
+        self.emit(f"    c.u = sign | (e << 23) | ((mant & 0x3FFU) << 13);")
+        self.emit(f"  }}")
+        self.emit(f"  return c.f;")
+        self.emit(f"}}")
+        self.emit("")
+        self.emit(doxy_comment(Docs(brief="Convert a float to an IEEE-754 binary16 value, rounding to nearest even.", doc=None)))
+        self.emit(f"static inline uint16_t {mapname_c}_float_to_f16(float f) {{")
+        self.emit(f"  union {{ uint32_t u; float f; }} c;")
+        self.emit(f"  c.f = f;")
+        self.emit(f"  const uint32_t sign = (c.u >> 16) & 0x8000U;")
+        self.emit(f"  const int32_t e = (int32_t) ((c.u >> 23) & 0xFFU) - 112;")
+        self.emit(f"  uint32_t mant = c.u & 0x7FFFFFU;")
+        self.emit(f"  uint32_t half, rem, mid;")
+        self.emit(f"  if (e == 0xFF - 112) return (uint16_t) (sign | 0x7C00U | (mant != 0U ? 0x200U : 0U)); // Inf/NaN")
+        self.emit(f"  if (e >= 0x1F) return (uint16_t) (sign | 0x7C00U); // Overflow: Inf")
+        self.emit(f"  if (e <= 0) {{")
+        self.emit(f"    // Subnormal or zero:")
+        self.emit(f"    if (e < -10) return (uint16_t) sign;")
+        self.emit(f"    const uint32_t shift = (uint32_t) (14 - e);")
+        self.emit(f"    mant |= 0x800000U;")
+        self.emit(f"    half = mant >> shift;")
+        self.emit(f"    rem = mant & ((1U << shift) - 1U);")
+        self.emit(f"    mid = 1U << (shift - 1U);")
+        self.emit(f"  }} else {{")
+        self.emit(f"    half = ((uint32_t) e << 10) | (mant >> 13);")
+        self.emit(f"    rem = mant & 0x1FFFU;")
+        self.emit(f"    mid = 0x1000U;")
+        self.emit(f"  }}")
+        self.emit(f"  if (rem > mid || (rem == mid && (half & 1U))) half++; // May carry into the exponent, which is correct.")
+        self.emit(f"  return (uint16_t) (sign | half);")
+        self.emit(f"}}")
+
+    def generate_verify_mismatch(self, rmap: RegisterMap, opts):
+        mapname_c = c_code(rmap.map_name)
+
+        self.emit("")
+        self.emit_section_header("Write verification", opts)
+        self.emit("")
+        self.emit(doxy_comment(Docs(brief="Returned by write_verified functions if the read back value differs from the written value.",
+                                    doc="Chosen to not collide with (negative) errno-style error codes.")))
+        self.emit(f"#define {c_macro(rmap.map_name)}_VERIFY_MISMATCH (-0x7FFF)")
+        self.emit("")
+        self.emit(doxy_comment(Docs(brief="Details of a failed register write verification.", doc=None)))
+        self.emit(f"struct {mapname_c}_verify_mismatch {{")
+        self.emit(f"  uint64_t written; //!< Value written to the register.")
+        self.emit(f"  uint64_t read;    //!< Value read back from the register.")
+        self.emit(f"  uint64_t mask;    //!< Bits that were compared.")
+        self.emit(f"}};")
+
+    def generate_register_write_verified(self, rmap: RegisterMap, block: RegisterBlock, template: Register):
+        mapname_c = c_code(rmap.map_name)
+        struct_name = name_register_struct(rmap, block, template)
+        packed_type = c_fitting_unsigned_type(template.bitwidth)
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+        variant_suffix = f"_{c_macro(template.variant)}" if template.variant is not None else ""
+        mask_macro = f"{c_macro(rmap.map_name)}_REG_{c_macro(block.name + template.name)}{variant_suffix}__VERIFY_MASK"
+
+        self.emit(f"")
+        self.emit(f"#define {mask_macro} (0x{verify_mask(template):X}U) //!< {block.name+template.name} bits that can be read back")
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Write a register and verify the write by reading it back.",
+            doc=f"Only bits of readable, non-volatile fields are compared (see {mask_macro}). Stops at (and returns) the first non-zero "
+                f"return value of 'write' or 'read'. Returns {c_macro(rmap.map_name)}_VERIFY_MISMATCH if the read back "
+                f"value differs, and fills 'mismatch' (if not NULL).")))
+        self.emit(f"static inline int {struct_name}_write_verified(const struct {struct_name} *r, {adr_type} adr,")
+        self.emit(f"    int (*write)(void *ctx, {adr_type} adr, uint64_t val),")
+        self.emit(f"    int (*read)(void *ctx, {adr_type} adr, uint64_t *val),")
+        self.emit(f"    void *ctx, struct {mapname_c}_verify_mismatch *mismatch) {{")
+        self.emit(f"  const {packed_type} val = {struct_name}_pack(r);")
+        self.emit(f"  uint64_t read_val;")
+        self.emit(f"  int err = write(ctx, adr, val);")
+        self.emit(f"  if (err) return err;")
+        self.emit(f"  err = read(ctx, adr, &read_val);")
+        self.emit(f"  if (err) return err;")
+        self.emit(f"  if (((read_val ^ val) & {mask_macro}) != 0) {{")
+        self.emit(f"    if (mismatch) {{")
+        self.emit(f"      mismatch->written = val;")
+        self.emit(f"      mismatch->read = read_val;")
+        self.emit(f"      mismatch->mask = {mask_macro};")
+        self.emit(f"    }}")
+        self.emit(f"    return {c_macro(rmap.map_name)}_VERIFY_MISMATCH;")
+        self.emit(f"  }}")
+        self.emit(f"  return 0;")
+        self.emit(f"}}")
+
+    def generate_register_unlock(self, rmap: RegisterMap, block: RegisterBlock, template: Register, opts):
+        assert template.unlock is not None
+        struct_name = name_register_struct(rmap, block, template)
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+        key_reg = rmap.register_by_name(template.unlock.key_register)
+        assert key_reg is not None
+        keys = ", ".join(f"0x{key:X}" for key in template.unlock.keys)
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief=f"Write the unlock sequence that is required before writing {block.name+template.name}.",
+            doc=f"Writes {keys} to {key_reg.name}, in order. Stops at (and returns) the first non-zero return value of 'write'.")))
+        self.emit(f"static inline int {struct_name}_unlock(int (*write)(void *ctx, {adr_type} adr, uint64_t val), void *ctx) {{")
+        self.emit(f"  int err;")
+        for key in template.unlock.keys:
+            self.emit(f"  err = write(ctx, 0x{key_reg.adr:X}U, 0x{key:X}U); // {key_reg.name}")
+            self.emit(f"  if (err) return err;")
+        self.emit(f"  return 0;")
+        self.emit(f"}}")
+
+        # Registers with separate read and write layouts are written from their write struct:
+        write_struct_name = name_register_struct(rmap, block, write_view(template))
+        if not opts.registers or len(write_view(template).fields) == 0:
+            return  # Guarded write requires the register struct and packing functions.
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Write the unlock sequence, followed by the register.",
+            doc="Stops at (and returns) the first non-zero return value of 'write'.")))
+        self.emit(f"static inline int {struct_name}_write_unlocked(const struct {write_struct_name} *r, {adr_type} adr,")
+        self.emit(f"    int (*write)(void *ctx, {adr_type} adr, uint64_t val), void *ctx) {{")
+        self.emit(f"  const int err = {struct_name}_unlock(write, ctx);")
+        self.emit(f"  if (err) return err;")
+        self.emit(f"  return write(ctx, adr, {write_struct_name}_pack(r));")
+        self.emit(f"}}")
+
+    def generate_register_example_checks(self, rmap: RegisterMap, block: RegisterBlock, template: Register):
+        struct_name = name_register_struct(rmap, block, template)
+        field_mask = 0
+        for field in template.get_layout_fields().values():
+            field_mask |= field.bits.get_bitmask()
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Check the register examples given in the listing against the packing/unpacking functions.",
+            doc="Returns 0 if all examples pass, or the (1-based) index of the first failing example.")))
+        self.emit(f"static inline int {struct_name}_check_examples(void) {{")
+        for idx, example in enumerate(template.examples):
+            self.emit(f"  {{")
+            self.emit(f"    const struct {struct_name} s = {c_macro(struct_name)}_UNPACK(0x{example.val:X}U);")
+            for field_name, field_val in example.fields.items():
+                if template.fields[field_name].view:
+                    self.emit(f"    if ({struct_name}_get_{c_code(field_name)}(0x{example.val:X}U) != 0x{field_val:X}U) return {idx + 1};")
+                else:
+                    self.emit(f"    if (s.{field_member(template.fields[field_name])} != 0x{field_val:X}U) return {idx + 1};")
+            if template.layout_view is None:  # Examples of registers with separate layouts are read values.
+                self.emit(f"    if (({struct_name}_pack(&s) & 0x{field_mask:X}U) != 0x{example.val & field_mask:X}U) return {idx + 1};")
+            self.emit(f"  }}")
+        self.emit(f"  return 0;")
         self.emit(f"}}")
 
-    def generate_generic_macros(self, rmap: RegisterMap):
+    def generate_generic_macros(self, rmap: RegisterMap, opts):
+        if not opts.generic_macros:
+            return
+
         macro_prefix = c_macro(rmap.map_name) + "_REG"
 
         self.emit(f"")
@@ -282,10 +1048,13 @@ class Generator(OutputGenerator):
         self.emit(f"#define {macro_prefix+'_OVERWRITE'}(_struct_ptr_, _val_) _Generic((_struct_ptr_), \\")
         for block in rmap.register_blocks.values():
             for template in block.register_templates.values():
-                struct_name = name_register_struct(rmap, block, template)
-                if len(template.fields) == 0:
+                if len(template.fields) == 0 or not c_packable(template):
                     continue  # Register does not have packing funcs if there are no fields.
-                self.emit(f"    struct {struct_name}* : {struct_name}_overwrite,  \\")
+                for view in template.get_layout_views():
+                    if view.layout_view == 'read':
+                        continue
+                    struct_name = name_register_struct(rmap, block, view)
+                    self.emit(f"    struct {struct_name}* : {struct_name}_overwrite,  \\")
         self.out[-1] = self.out[-1].replace(",", "")
         self.emit(f"  )(_struct_ptr_, _val_)")
 
@@ -294,10 +1063,13 @@ class Generator(OutputGenerator):
         self.emit(f"#define {macro_prefix+'_PACK'}(_struct_ptr_) _Generic((_struct_ptr_), \\")
         for block in rmap.register_blocks.values():
             for template in block.register_templates.values():
-                struct_name = name_register_struct(rmap, block, template)
-                if len(template.fields) == 0:
+                if len(template.fields) == 0 or not c_packable(template):
                     continue  # Register does not have packing funcs if there are no fields.
-                self.emit(f"    struct {struct_name}* : {struct_name}_pack,  \\")
+                for view in template.get_layout_views():
+                    if view.layout_view == 'read':
+                        continue
+                    struct_name = name_register_struct(rmap, block, view)
+                    self.emit(f"    struct {struct_name}* : {struct_name}_pack,  \\")
         self.out[-1] = self.out[-1].replace(",", "")
         self.emit(f"  )(_struct_ptr_)")
 
@@ -306,15 +1078,348 @@ class Generator(OutputGenerator):
         self.emit(f"#define {macro_prefix+'_UNPACK_INTO'}(_val_, _struct_ptr_) _Generic((_struct_ptr_), \\")
         for block in rmap.register_blocks.values():
             for template in block.register_templates.values():
-                struct_name = name_register_struct(rmap, block, template)
-                if len(template.fields) == 0:
+                if len(template.fields) == 0 or not c_packable(template):
                     continue  # Register does not have packing funcs if there are no fields.
-                self.emit(f"    struct {struct_name}* : {struct_name}_unpack_into,  \\")
+                for view in template.get_layout_views():
+                    if view.layout_view == 'write':
+                        continue
+                    struct_name = name_register_struct(rmap, block, view)
+                    self.emit(f"    struct {struct_name}* : {struct_name}_unpack_into,  \\")
         self.out[-1] = self.out[-1].replace(",", "")
         self.emit(f"  )(_val_,_struct_ptr_)")
 
         self.emit(f"")
 
+    def generate_device_config(self, rmap: RegisterMap, opts):
+        if not opts.device_config:
+            return
+
+        mapname_c = c_code(rmap.map_name)
+        macro_prefix = c_macro(rmap.map_name) + "_REG"
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+
+        # One member for every physical register that can be written:
+        members = []
+        for block in rmap.register_blocks.values():
+            for template in block.register_templates.values():
+                if not template.is_writable() or not c_packable(template):
+                    continue
+                if template.variant_tag is not None:
+                    warn(f"c.funcpack: registers -> {block.name+template.name}: Register has variants, "
+                         f"it is not part of the device configuration.")
+                    continue
+                for instance_name in block.instances.keys():
+                    members.append((instance_name + template.name, block, write_view(template)))
+
+        if len(members) == 0:
+            return
+
+        self.emit_section_header("Device configuration", opts)
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="Complete device configuration.",
+                                    doc="Contains one member for every writable register.")))
+        self.emit(f"struct {mapname_c}_config {{")
+        for reg_name, block, template in members:
+            self.emit(f"  struct {name_register_struct(rmap, block, template)} {c_code(reg_name)};")
+        self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Write a complete device configuration.",
+            doc="Registers are written in order of declaration. Stops at (and returns) the first non-zero return value of 'write'.")))
+        self.emit(f"static inline int {mapname_c}_config_apply(const struct {mapname_c}_config *c,")
+        self.emit(f"    int (*write)(void *ctx, {adr_type} adr, uint64_t val), void *ctx) {{")
+        self.emit(f"  int err;")
+        for reg_name, block, template in members:
+            struct_name = name_register_struct(rmap, block, template)
+            self.emit(f"  err = write(ctx, {macro_prefix}_{c_macro(reg_name)}, {struct_name}_pack(&c->{c_code(reg_name)}));")
+            self.emit(f"  if (err) return err;")
+        self.emit(f"  return 0;")
+        self.emit(f"}}")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Read back a complete device configuration.",
+            doc="Registers with read side effects or separate read and write layouts are skipped. "
+                "Stops at (and returns) the first non-zero return value of 'read'.")))
+        self.emit(f"static inline int {mapname_c}_config_read_back(struct {mapname_c}_config *c,")
+        self.emit(f"    int (*read)(void *ctx, {adr_type} adr, uint64_t *val), void *ctx) {{")
+        read_members = [(reg_name, block, template) for reg_name, block, template in members
+                        if len(template.get_read_side_effects()) == 0 and template.layout_view is None]
+        if len(read_members) == 0:
+            self.emit(f"  (void) c;")
+            self.emit(f"  (void) read;")
+            self.emit(f"  (void) ctx;")
+        else:
+            self.emit(f"  int err;")
+            self.emit(f"  uint64_t val;")
+        for reg_name, block, template in read_members:
+            struct_name = name_register_struct(rmap, block, template)
+            packed_type = c_fitting_unsigned_type(template.bitwidth)
+            self.emit(f"  err = read(ctx, {macro_prefix}_{c_macro(reg_name)}, &val);")
+            self.emit(f"  if (err) return err;")
+            self.emit(f"  {struct_name}_unpack_into(({packed_type}) val, &c->{c_code(reg_name)});")
+        self.emit(f"  return 0;")
+        self.emit(f"}}")
+        self.emit(f"")
+
+    def generate_transactions(self, rmap: RegisterMap, opts):
+        if not opts.transactions or len(rmap.transactions) == 0:
+            return
+        if not (opts.registers and opts.register_functions):
+            return  # Requires the register structs and packing functions.
+
+        mapname_c = c_code(rmap.map_name)
+        macro_prefix = c_macro(rmap.map_name) + "_REG"
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+
+        # Block and template of every physical register:
+        templates = {}
+        for block in rmap.register_blocks.values():
+            for template in block.register_templates.values():
+                for instance_name in block.instances.keys():
+                    templates[instance_name + template.name] = (block, template)
+
+        self.emit_section_header("Transactions", opts)
+        for transaction in rmap.transactions.values():
+            if not all(c_packable(templates[reg_name][1]) for reg_name in transaction.registers):
+                continue  # Registers wider than 64 bits have no structs.
+            order = ", ".join(transaction.registers)
+            self.emit(f"")
+            self.emit(doxy_comment(transaction.docs, note=f"Registers have to be written together, in order: {order}. "
+                                   f"Stops at (and returns) the first non-zero return value of 'write'."))
+            params = []
+            for reg_name in transaction.registers:
+                block, template = templates[reg_name]
+                params.append(f"const struct {name_register_struct(rmap, block, write_view(template))} *{c_code(reg_name)}")
+            self.emit(f"static inline int {mapname_c}_{c_code(transaction.name)}_write({', '.join(params)},")
+            self.emit(f"    int (*write)(void *ctx, {adr_type} adr, uint64_t val), void *ctx) {{")
+            self.emit(f"  int err;")
+            for reg_name in transaction.registers:
+                block, template = templates[reg_name]
+                struct_name = name_register_struct(rmap, block, write_view(template))
+                self.emit(f"  err = write(ctx, {macro_prefix}_{c_macro(reg_name)}, {struct_name}_pack({c_code(reg_name)}));")
+                self.emit(f"  if (err) return err;")
+            self.emit(f"  return 0;")
+            self.emit(f"}}")
+        self.emit(f"")
+
+    def generate_split_values(self, rmap: RegisterMap, opts):
+        if not opts.split_values or len(rmap.split_values) == 0:
+            return
+
+        mapname_c = c_code(rmap.map_name)
+
+        # Block and template of every physical register:
+        templates = {}
+        for block in rmap.register_blocks.values():
+            for template in block.register_templates.values():
+                for instance_name in block.instances.keys():
+                    templates[instance_name + template.name] = (block, template)
+
+        self.emit_section_header("Split values", opts)
+        for split_value in rmap.split_values.values():
+            layout = rmap.split_value_layout(split_value)
+            width = sum(bits.total_width() for _, _, bits, _ in layout)
+            regs = list({reg.name: reg for reg, _, _, _ in layout}.values())
+            if width > 64 or not all(c_packable(reg) for reg in regs):
+                warn(f"c.funcpack: split_values -> {split_value.name}: Value or one of its registers is wider than 64 bits, "
+                     f"skipped.")
+                continue
+            value_type = c_fitting_unsigned_type(width)
+            name_c = f"{mapname_c}_{c_code(split_value.name)}"
+
+            # Internal parts make the complete value internal:
+            internal = next((field for _, field, _, _ in layout if field is not None and field.internal), None)
+            if internal is not None:
+                self.emit_internal_guard(rmap, internal, True)
+
+            self.emit(f"")
+            self.emit(f"// {split_value.name} ({width} bits):")
+            for part, (reg, field, bits, lsb) in zip(split_value.parts, layout):
+                msb = lsb + bits.total_width() - 1
+                self.emit(f"//   [{msb}:{lsb}]".ljust(16) + f"<- {part.to_str()} [{bits.get_bitrange()}]")
+
+            # Packed register values:
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(brief=f"Combine {split_value.name} from packed register values.",
+                                        doc=split_value.docs.brief)))
+            params = ", ".join(f"{c_fitting_unsigned_type(reg.bitwidth)} {c_code(reg.name)}" for reg in regs)
+            self.emit(f"static inline {value_type} {name_c}_from_regs({params}) {{")
+            self.emit(f"  return ({value_type}) (")
+            for idx, (reg, _, bits, lsb) in enumerate(layout):
+                sep = ");" if idx == len(layout) - 1 else " |"
+                self.emit(f"    ((({value_type}) (({c_code(reg.name)} & 0x{bits.get_bitmask():X}U) >> {bits.lsb_position()}U)) "
+                          f"<< {lsb}U){sep}")
+            self.emit(f"}}")
+
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(brief=f"Split {split_value.name} into packed register values.",
+                                        doc="Bits that are not part of the value are kept as in the register values.")))
+            params = ", ".join(f"{c_fitting_unsigned_type(reg.bitwidth)} *{c_code(reg.name)}" for reg in regs)
+            self.emit(f"static inline void {name_c}_to_regs({value_type} val, {params}) {{")
+            for reg, _, bits, lsb in layout:
+                packed_type = c_fitting_unsigned_type(reg.bitwidth)
+                unpos_mask = bits.get_unpositioned_bits().get_bitmask()
+                self.emit(f"  *{c_code(reg.name)} = ({packed_type}) ((*{c_code(reg.name)} & ~0x{bits.get_bitmask():X}U) | "
+                          f"(((val >> {lsb}U) & 0x{unpos_mask:X}U) << {bits.lsb_position()}U));")
+            self.emit(f"}}")
+
+            # Register structs, if all parts are fields of registers with a single layout:
+            if (opts.registers and opts.register_functions and all(field is not None for _, field, _, _ in layout)
+                    and all(templates[reg.name][1].write_fields is None for reg in regs)):
+                params = [f"struct {name_register_struct(rmap, *templates[reg.name])} *{c_code(reg.name)}" for reg in regs]
+
+                self.emit(f"")
+                self.emit(doxy_comment(Docs(brief=f"Combine {split_value.name} from its parts.", doc=split_value.docs.brief)))
+                const_params = ", ".join(f"const {param}" for param in params)
+                self.emit(f"static inline {value_type} {name_c}_combine({const_params}) {{")
+                self.emit(f"  return ({value_type}) (")
+                for idx, (reg, field, _, lsb) in enumerate(layout):
+                    sep = ");" if idx == len(layout) - 1 else " |"
+                    self.emit(f"    ((({value_type}) {c_code(reg.name)}->{c_code(field.name)}) << {lsb}U){sep}")
+                self.emit(f"}}")
+
+                self.emit(f"")
+                self.emit(doxy_comment(Docs(brief=f"Split {split_value.name} into its parts.", doc=split_value.docs.brief)))
+                self.emit(f"static inline void {name_c}_split({value_type} val, {', '.join(params)}) {{")
+                for reg, field, bits, lsb in layout:
+                    block, template = templates[reg.name]
+                    field_type = register_struct_member_type(rmap, block, template, field, opts)
+                    unpos_mask = bits.get_unpositioned_bits().get_bitmask()
+                    self.emit(f"  {c_code(reg.name)}->{c_code(field.name)} = ({field_type}) ((val >> {lsb}U) & 0x{unpos_mask:X}U);")
+                self.emit(f"}}")
+
+            if internal is not None:
+                self.emit_internal_guard(rmap, internal, False)
+        self.emit(f"")
+
+    def generate_decoder(self, rmap: RegisterMap, opts):
+        if not opts.decoder or not (opts.registers and opts.register_functions):
+            return  # Requires the register structs and packing functions.
+
+        mapname_c = c_code(rmap.map_name)
+        mapname_macro = c_macro(rmap.map_name)
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+
+        # Every physical register with a struct, by address:
+        regs = []
+        for block in rmap.register_blocks.values():
+            for template in block.register_templates.values():
+                if len(template.fields) == 0 or not c_packable(template):
+                    continue
+                for instance_name, instance_adr in block.instances.items():
+                    regs.append((instance_adr + template.adr, instance_name + template.name, block, read_view(template)))
+        regs.sort(key=lambda r: r[0])
+
+        if len(regs) == 0:
+            return
+
+        self.emit_section_header("Decoder", opts)
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="Registers known to the decoder.", doc=None)))
+        self.emit(f"enum {mapname_c}_decoded_id {{")
+        self.emit(f"  {mapname_macro}_DECODED_NONE = 0, //!< No register at the address")
+        for _, reg_name, _, _ in regs:
+            self.emit(f"  {mapname_macro}_DECODED_{c_macro(reg_name)},")
+        self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="A decoded register value.",
+                                    doc="'id' selects the valid member of 'reg'. Registers with variants are "
+                                        "decoded into the struct of the variant selected by their tag.")))
+        self.emit(f"struct {mapname_c}_decoded {{")
+        self.emit(f"  enum {mapname_c}_decoded_id id;")
+        self.emit(f"  {adr_type} adr;")
+        self.emit(f"  union {{")
+        for _, reg_name, block, template in regs:
+            variants_suffix = "_variants" if template.variant_tag is not None else ""
+            self.emit(f"    struct {name_register_struct(rmap, block, template)}{variants_suffix} {c_code(reg_name)};")
+        self.emit(f"  }} reg;")
+        self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Decode a packed register value, given the register address.",
+            doc=f"Returns 0, or -1 if there is no register with fields at the address (with 'id' set to "
+                f"{mapname_macro}_DECODED_NONE).")))
+        self.emit(f"static inline int {mapname_c}_decode({adr_type} adr, uint64_t val, struct {mapname_c}_decoded *out) {{")
+        self.emit(f"  out->adr = adr;")
+        self.emit(f"  switch (adr) {{")
+        for adr, reg_name, block, template in regs:
+            struct_name = name_register_struct(rmap, block, template)
+            packed_type = c_fitting_unsigned_type(template.bitwidth)
+            self.emit(f"    case 0x{adr:X}U:")
+            self.emit(f"      out->id = {mapname_macro}_DECODED_{c_macro(reg_name)};")
+            if template.variant_tag is not None:
+                self.emit(f"      (void) {struct_name}_unpack_variant(({packed_type}) val, &out->reg.{c_code(reg_name)});")
+            else:
+                self.emit(f"      {struct_name}_unpack_into(({packed_type}) val, &out->reg.{c_code(reg_name)});")
+            self.emit(f"      return 0;")
+        self.emit(f"    default:")
+        self.emit(f"      out->id = {mapname_macro}_DECODED_NONE;")
+        self.emit(f"      return -1;")
+        self.emit(f"  }}")
+        self.emit(f"}}")
+        self.emit(f"")
+
+    def generate_domain_table(self, rmap: RegisterMap, opts):
+        if not opts.domain_table:
+            return
+
+        mapname_c = c_code(rmap.map_name)
+        mapname_macro = c_macro(rmap.map_name)
+        adr_type = c_fitting_unsigned_type(max(rmap.max_address.bit_length(), 1))
+
+        regs = [reg for _, reg in sorted(rmap.physical_registers.items())
+                if reg.clock_domain is not None or reg.power_domain is not None]
+        if len(regs) == 0:
+            return
+
+        self.emit_section_header("Domain table", opts)
+        for kind in ['clock', 'power']:
+            self.emit(f"")
+            self.emit(doxy_comment(Docs(brief=f"{kind.capitalize()} domains.", doc=None)))
+            self.emit(f"enum {mapname_c}_{kind}_domain {{")
+            self.emit(f"  {mapname_macro}_{kind.upper()}_DOMAIN_NONE = 0, //!< Not part of a {kind} domain")
+            for domain in rmap.get_domains(kind):
+                self.emit(f"  {mapname_macro}_{kind.upper()}_DOMAIN_{c_macro(domain)},")
+            self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="Clock and power domain of a register.",
+                                    doc="The register is inaccessible while either domain is off.")))
+        self.emit(f"struct {mapname_c}_domain_entry {{")
+        self.emit(f"  {adr_type} adr;")
+        self.emit(f"  enum {mapname_c}_clock_domain clock;")
+        self.emit(f"  enum {mapname_c}_power_domain power;")
+        self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(f"#define {mapname_macro}_DOMAIN_TABLE_LEN ({len(regs)}U) //!< Number of registers in a domain")
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(brief="Domains of all registers that are part of a clock or power domain, by address.", doc=None)))
+        self.emit(f"static const struct {mapname_c}_domain_entry {mapname_c}_domain_table[{mapname_macro}_DOMAIN_TABLE_LEN] = {{")
+        for reg in regs:
+            clock = c_macro(reg.clock_domain) if reg.clock_domain is not None else "NONE"
+            power = c_macro(reg.power_domain) if reg.power_domain is not None else "NONE"
+            self.emit(f"  {{0x{reg.adr:X}U, {mapname_macro}_CLOCK_DOMAIN_{clock}, {mapname_macro}_POWER_DOMAIN_{power}}}, // {reg.name}")
+        self.emit(f"}};")
+
+        self.emit(f"")
+        self.emit(doxy_comment(Docs(
+            brief="Look up the clock and power domain of a register, given its address.",
+            doc="Returns 0, or -1 if the register is not part of any domain (with 'out' left unchanged).")))
+        self.emit(f"static inline int {mapname_c}_domain_lookup({adr_type} adr, struct {mapname_c}_domain_entry *out) {{")
+        self.emit(f"  for (unsigned i = 0; i < {mapname_macro}_DOMAIN_TABLE_LEN; i++) {{")
+        self.emit(f"    if ({mapname_c}_domain_table[i].adr == adr) {{")
+        self.emit(f"      *out = {mapname_c}_domain_table[i];")
+        self.emit(f"      return 0;")
+        self.emit(f"    }}")
+        self.emit(f"  }}")
+        self.emit(f"  return -1;")
+        self.emit(f"}}")
+
 
 def parse_args(args: List[str]):
 
@@ -328,12 +1433,12 @@ def parse_args(args: List[str]):
     return parser.parse_args(args)
 
 
-def doxy_comment(docs: Docs, prefix: str = "", note: str | None = None) -> List[str]:
+def doxy_comment(docs: Docs, prefix: str = "", note: str | None = None, warning: str | None = None) -> List[str]:
     brief = docs.brief
     doc = docs.doc
 
     have_brief = brief is not None
-    have_doc = doc is not None
+    have_doc = doc is not None or warning is not None
     have_note = note is not None
 
     match (have_brief, have_note, have_doc):
@@ -350,6 +1455,8 @@ def doxy_comment(docs: Docs, prefix: str = "", note: str | None = None) -> List[
                 out.append(f"{prefix} * @brief {brief}")
             if note is not None:
                 out.append(f"{prefix} * @note {note}")
+            if warning is not None:
+                out.append(f"{prefix} * @warning {warning}")
             if doc is not None:
                 for line in doc.splitlines():
                     out.append(f"{prefix} * {line}")
@@ -357,6 +1464,10 @@ def doxy_comment(docs: Docs, prefix: str = "", note: str | None = None) -> List[
             return out
 
 
+def c_float(value: float) -> str:
+    return f"{float(value)!r}f"
+
+
 def c_macro(s: str) -> str:
     return c_sanitize(s).upper()
 
@@ -376,7 +1487,7 @@ def register_content_to_generate(template: Register, opts) -> bool:
             return True
 
     if opts.register_functions:
-        if len(template.fields) != 0:
+        if len(template.fields) != 0 or template.unlock is not None:
             # Will generate register functions.
             return True
 
@@ -402,7 +1513,112 @@ def name_register_enum(rmap: RegisterMap, block: RegisterBlock, template: Regist
 def name_register_struct(rmap: RegisterMap, block: RegisterBlock, template: Register) -> str:
     mapname_c = c_code(rmap.map_name)
     regname_c = c_code(block.name + template.name)
-    return f"{mapname_c}_{regname_c}"
+    # Registers with separate read and write layouts get a struct per view,
+    # registers with variants one per variant:
+    view_suffix = {None: "", 'read': "_r", 'write': "_w"}[template.layout_view]
+    if template.variant is not None:
+        view_suffix = f"_{c_code(template.variant)}"
+    return f"{mapname_c}_{regname_c}{view_suffix}"
+
+
+def name_block_instance_struct(rmap: RegisterMap, block: RegisterBlock) -> str:
+    return f"{c_code(rmap.map_name)}_{c_code(block.name)}_instance"
+
+
+def field_member_name(field: Field) -> str:
+    # Name of the struct member of a field (or of the field array it is part of):
+    return field.name if field.array is None else field.array.name
+
+
+def field_member(field: Field) -> str:
+    if field.array is None:
+        return c_code(field.name)
+    return f"{c_code(field.array.name)}[{field.array.index}]"
+
+
+def c_doc_ref_renderer(rmap: RegisterMap, opts) -> DocRefRenderer:
+    # Documentation cross-references name the generated address macro or
+    # struct member, which doxygen links automatically:
+    def render(register: str, field: str | None) -> str:
+        found = rmap.template_by_name(register)
+        if found is None or not opts.registers:
+            return plain_doc_ref(register, field)
+        block, template = found
+        if field is None:
+            if rmap.register_by_name(register) is None:
+                return plain_doc_ref(register, field)  # Generic block register name, no address.
+            return f"{c_macro(rmap.map_name)}_REG_{c_macro(register)}"
+        view = next(view for view in template.get_layout_views() if field in view.fields)
+        if view.fields[field].view:
+            return plain_doc_ref(register, field)
+        return f"{name_register_struct(rmap, block, view)}::{c_code(field_member_name(view.fields[field]))}"
+    return render
+
+
+def field_docs_with_enum(rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field, opts) -> Docs:
+    if field.enum is None:
+        return field.docs
+
+    if field.enum.is_shared:
+        enum_name = name_shared_enum(rmap, field.enum)
+    else:
+        enum_name = name_register_enum(rmap, block, template, field.enum, opts)
+
+    lines = []
+    if field.docs.doc is not None:
+        lines.extend(field.docs.doc.splitlines())
+    lines.append(f"Accepts (enum {enum_name}):")
+    for entry in field.enum.entries.values():
+        line = f"  - {c_macro(enum_name)}_{c_macro(entry.name)}"
+        if entry.docs.brief is not None:
+            line += f": {entry.docs.brief}"
+        lines.append(line)
+
+    return Docs(brief=field.docs.brief, doc="\n".join(lines))
+
+
+def read_side_effect_warning(template: Register) -> str | None:
+    effects = template.get_read_side_effects()
+    if len(effects) == 0:
+        return None
+    return f"Reading this register has side effects ({', '.join(effects)}). Never cache or read it speculatively."
+
+
+def c_packable(template: Register) -> bool:
+    # Packed register values are C integers, wider registers only get their address:
+    return template.bitwidth <= 64
+
+
+def read_view(template: Register) -> Register:
+    # The register struct a read value is unpacked into:
+    return template.get_layout_views()[0]
+
+
+def write_view(template: Register) -> Register:
+    # The register struct a written value is packed from:
+    return next((view for view in template.get_layout_views() if view.layout_view == 'write'), template)
+
+
+def verify_writable(template: Register) -> bool:
+    # Registers with any writable field (or field of unknown access) get a write_verified function.
+    return any(len(field.access) == 0 or field.is_writable() for field in template.get_layout_fields().values())
+
+
+def verify_mask(template: Register) -> int:
+    # Bits that read back as written: Fields that are readable (or of unknown access), without side effects,
+    # and not changed by hardware (volatile).
+    mask = 0
+    for field in template.get_layout_fields().values():
+        if (len(field.access) == 0 or field.is_readable()) and not field.has_side_effect():
+            mask |= field.bits.get_bitmask()
+    return mask & ~template.get_volatile_mask()
+
+
+def enum_covers_field(field: Field) -> bool:
+    if field.enum is None:
+        return False
+    values = set(entry.value for entry in field.enum.entries.values())
+    return len(values) == 2 ** len(field.bits.bitlist)
 
 
 def register_struct_member_type(rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field, opts) -> str:
@@ -413,3 +1629,23 @@ def register_struct_member_type(rmap: RegisterMap, block: RegisterBlock, templat
             return "enum " + name_shared_enum(rmap, field.enum)
         else:
             return "enum " + name_register_enum(rmap, block, template, field.enum, opts)
+
+
+def c_byte_swap(expr: str, field: Field) -> str:
+    # Byte-swapped fields store their most significant byte in their lowest bits:
+    if field.byte_order != 'big':
+        return expr
+    width = field.bits.total_width()
+    c_type = c_fitting_unsigned_type(width)
+    parts = [f"(((({c_type}) ({expr}) >> {8 * i}U) & 0xFFU) << {width - 8 * (i + 1)}U)" for i in range(width // 8)]
+    return f"({c_type}) ({' | '.join(parts)})"
+
+
+def field_from_raw(rmap: RegisterMap, block: RegisterBlock, template: Register, field: Field, raw: str, opts) -> str:
+    # Conversion of a raw (unpositioned) field value to the struct member type:
+    if field.enum is not None and field.enum.get_catch_all() is not None and not enum_covers_field(field):
+        if field.enum.is_shared:
+            return f"{name_shared_enum(rmap, field.enum)}_from_raw({raw})"
+        else:
+            return f"{name_register_enum(rmap, block, template, field.enum, opts)}_from_raw({raw})"
+    return f"({register_struct_member_type(rmap, block, template, field, opts)}) ({raw})"
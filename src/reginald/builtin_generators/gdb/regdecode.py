@@ -0,0 +1,121 @@
+import argparse
+from math import ceil
+from typing import List
+
+from reginald.datamodel import RegisterMap
+from reginald.generator import OutputGenerator
+
+
+class Generator(OutputGenerator):
+    def description(self):
+        return "GDB python script with register decode commands for memory-mapped register maps."
+
+    def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
+        opts = parse_args(args)
+
+        out = []
+
+        # Generate header:
+        out.append(f"# {rmap.map_name} register decode commands for GDB.")
+        out.append(f"# Generated from {input_file} using reginald.")
+        out.append(f"# Load with 'source {output_file}' inside a GDB session. Provides:")
+        out.append(f"#   reg-list                 List all registers.")
+        out.append(f"#   reg-decode NAME [VALUE]  Decode a register, reading it from target memory")
+        out.append(f"#                            if no value is given.")
+        out.append(f"")
+        out.append(f"import gdb")
+        out.append(f"")
+        out.append(f"BYTE_ORDER = {opts.byte_order!r}")
+        out.append(f"")
+
+        # Register table: Name -> (Byte address, Size in bytes, [(Field name, Bit list, {Value: Enum entry}, Byte-swapped)]).
+        out.append(f"REGISTERS = {{")
+        for reg in sorted(rmap.physical_registers.values(), key=lambda r: r.adr):
+            out.append(f"    {reg.name!r}: ({rmap.byte_address(reg.adr):#x}, {ceil(reg.bitwidth / 8)}, [")
+            for field in reg.fields.values():
+                enum = {}
+                if field.enum is not None:
+                    enum = {entry.value: entry.name for entry in field.enum.entries.values()}
+                out.append(f"        ({field.name!r}, {field.bits.get_bitlist()!r}, {enum!r}, {field.byte_order == 'big'!r}),")
+            out.append(f"    ]),")
+        out.append(f"}}")
+        out.append(f"")
+        out.append(f"")
+
+        out.append(GDB_COMMANDS)
+
+        with open(output_file, 'w') as outfile:
+            outfile.write("\n".join(out))
+
+
+GDB_COMMANDS = '''\
+def extract_field(value, bitlist, swapped):
+    result = 0
+    for i, bit in enumerate(bitlist):
+        result |= ((value >> bit) & 1) << i
+    if swapped:
+        result = int.from_bytes(result.to_bytes(len(bitlist) // 8, 'little'), 'big')
+    return result
+
+
+def lookup_register(name):
+    for reg_name, reg in REGISTERS.items():
+        if reg_name.lower() == name.lower():
+            return reg_name, reg
+    raise gdb.GdbError(f"Unknown register '{name}'")
+
+
+class RegList(gdb.Command):
+    """List all registers."""
+
+    def __init__(self):
+        super().__init__("reg-list", gdb.COMMAND_DATA)
+
+    def invoke(self, arg, from_tty):
+        for name, (adr, size, fields) in REGISTERS.items():
+            print(f"0x{adr:08X}  {name}  ({size} byte(s))")
+
+
+class RegDecode(gdb.Command):
+    """Decode a register: reg-decode NAME [VALUE]"""
+
+    def __init__(self):
+        super().__init__("reg-decode", gdb.COMMAND_DATA)
+
+    def complete(self, text, word):
+        return [name for name in REGISTERS if name.lower().startswith(text.lower())]
+
+    def invoke(self, arg, from_tty):
+        argv = gdb.string_to_argv(arg)
+        if len(argv) not in [1, 2]:
+            raise gdb.GdbError("Usage: reg-decode NAME [VALUE]")
+
+        name, (adr, size, fields) = lookup_register(argv[0])
+        if len(argv) == 2:
+            value = int(gdb.parse_and_eval(argv[1]))
+        else:
+            memory = gdb.selected_inferior().read_memory(adr, size)
+            value = int.from_bytes(bytes(memory), BYTE_ORDER)
+
+        print(f"{name} @ 0x{adr:X} = 0x{value:0{size * 2}X}")
+        for field_name, bitlist, enum, swapped in fields:
+            field_value = extract_field(value, bitlist, swapped)
+            if len(enum) > 0:
+                entry = enum.get(field_value, "?")
+                print(f"  {field_name:24} = 0x{field_value:X} ({entry})")
+            else:
+                print(f"  {field_name:24} = 0x{field_value:X}")
+
+
+RegList()
+RegDecode()
+'''
+
+
+def parse_args(args: List[str]):
+    parser = argparse.ArgumentParser(
+        prog="gdb.regdecode",
+        description="GDB python script generator, providing register decode commands.")
+    parser.add_argument('--byte-order', choices=['little', 'big'], default='little',
+                        help="byte order of registers in target memory (default: little)")
+    return parser.parse_args(args)
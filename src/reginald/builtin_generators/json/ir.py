@@ -0,0 +1,272 @@
+import json
+from math import ceil
+from typing import Any, Dict, List, Optional
+
+from reginald.bits import Bits
+from reginald.byte_transforms import ByteOrder
+from reginald.datamodel import (Docs, Field, RegEnum, Register, RegisterMap,
+                                SplitValue)
+from reginald.generator import OutputGenerator
+
+# Fully resolved register map, for external generators. Everything a builtin
+# generator computes from a listing is included, so that external tools do
+# not need to re-implement any bit manipulation:
+#
+#   ir_version             Version of this format. Incremented on any
+#                          incompatible change (removed or renamed keys,
+#                          changed meaning). New keys may be added without
+#                          an increment.
+#   map_name, fingerprint, docs {brief, doc}
+#   address_unit_bits      Bits per address, all addresses ('adr', block
+#                          instances, strides) are in these units.
+#                          All docs may contain (validated) cross-references
+#                          '[[REG]]' or '[[REG.FIELD]]', left for the consumer
+#                          to render.
+#   enums                  Shared enums: name -> {docs, entries: [{name, value, docs, catch_all}],
+#                                   reserved: [[first, last]]}
+#   blocks                 name -> {docs, instances: {name: adr}, templates: [name],
+#                                   array_stride (or null), group (or null)}
+#   transactions           name -> {docs, registers: [name]}
+#   split_values           name -> {docs, width, parts: [{register, field, lsb, msb}]},
+#                          with a null field for parts that span a complete register
+#   constants              name -> {docs, value}
+#                          Parts most significant first, lsb/msb are the
+#                          bits of the part within the combined value.
+#   registers              All physical registers, ordered by address:
+#     name, adr, bitwidth, bytewidth, reset_val (or null), docs
+#     byte_adr                       Address of the register in bytes.
+#     block, instance, template      Where the register came from.
+#     aliases                        Former (deprecated) names of the register.
+#     security {secure, privileged}  Required TrustZone security state and
+#                                    privilege level, each null if unspecified.
+#     array {name, index, count, stride} (or null)
+#                                    Template is element 'index' of an array
+#                                    of block registers.
+#     readable, writable
+#     read_side_effects              Of the register and its fields, any of
+#                                    'clear', 'pop', 'latch'. Never cache or
+#                                    speculatively read such registers.
+#     always_write {mask, value, dont_care} (or null)
+#                                    dont_care: Always write bits that are
+#                                    undefined when read.
+#     clock_domain, power_domain     Domains the register lives in (each null if
+#                                    not given). It is inaccessible while they
+#                                    are off.
+#     unlock {key_register, keys} (or null)
+#                                    Keys to write to the key register, in
+#                                    order, before writing the register.
+#     volatile_mask                  Bits updated by hardware (of volatile fields,
+#                                    or all if the register is volatile). Never
+#                                    cache them, always re-read.
+#     recommended_val                Reset value (0 if unknown) with all field
+#                                    defaults applied, or null without defaults.
+#     unused_mask                    Bits not covered by any field or always_write.
+#     examples [{val, fields: {name: value}, docs}]
+#     fields [...]:
+#       name, lsb, msb, width, mask (positioned), docs
+#       aliases                      Former (deprecated) names of the field.
+#       access                       List of 'r', 'w', 'w1c', 'w1s', 'w1t' (write
+#                                    1 to clear/set/toggle), 'rc' (read clears).
+#       enum                         Name of a shared enum, an inline
+#                                    {name, entries: [...]}, or null.
+#       view                         Field is an alternative view of bits of
+#                                    other fields, and not part of the layout.
+#       fixed_point {scale, offset, signed} (or null)
+#                                    physical = raw * scale + offset, raw is
+#                                    two's complement if signed.
+#       float_format                 'f16' or 'f32' for IEEE-754 floats, or null.
+#       unit                         Physical unit (e.g. 'mV'), or null.
+#       min, max                     Valid range of the raw value (inclusive),
+#                                    each null if unconstrained.
+#       array {name, index, count, stride} (or null)
+#                                    Field is element 'index' of a field array.
+#       internal                     Field is not part of the public driver API.
+#       read_side_effect             'clear', 'pop', 'latch', or null.
+#       self_clearing                Trigger bit, cleared by hardware after a 1
+#                                    is written.
+#       volatile                     Updated by hardware, never cache.
+#       byte_order                   'little', or 'big' if the field's most
+#                                    significant byte is in its lowest bits.
+#                                    Byte transforms apply to the byte-swapped
+#                                    value of 'big' fields.
+#       default                      Recommended raw value, if it differs from
+#                                    the reset value, or null.
+#       byte_transforms {little: [...], big: [...]}
+#                                    Per byte of the packed register:
+#                                    {byte, mask, shift}, with
+#                                      packed[byte] |= (value << shift) & mask
+#                                      value |= (packed[byte] & mask) >> shift
+#                                    where negative shifts shift the other way.
+#     write_fields [...] (or null)   Fields of written values, for registers
+#                                    with separate read and write layouts
+#                                    ('fields' then describes read values).
+#     variant_tag (or null)          Name of the field whose value selects one
+#                                    of the variants.
+#     variants                       name -> {tag, docs, fields: [...]},
+#                                    alternative layouts of the bits outside
+#                                    of 'fields', selected by tag.
+
+IR_VERSION = 1
+
+
+class Generator(OutputGenerator):
+    def description(self):
+        return "JSON intermediate representation of the fully resolved map (masks, widths, byte transforms), for external generators."
+
+    def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
+        _ = input_file
+        _ = args
+
+        with open(output_file, 'w') as outfile:
+            json.dump(map_ir(rmap), outfile, indent=2, ensure_ascii=False)
+            outfile.write("\n")
+
+
+def map_ir(rmap: RegisterMap) -> Dict[str, Any]:
+    registers = []
+    for block in rmap.register_blocks.values():
+        for template in block.register_templates.values():
+            for instance_name, instance_adr in block.instances.items():
+                reg = template.get_populated_template(instance_name, instance_adr)
+                registers.append(register_ir(reg) | {
+                    'byte_adr': rmap.byte_address(reg.adr),
+                    'block': block.name,
+                    'instance': instance_name,
+                    'template': template.name,
+                    'aliases': block.get_alias_names(template, instance_name),
+                    'security': block.get_security(template, instance_name).model_dump(),
+                    'array': None if template.array is None else template.array.model_dump(),
+                })
+    registers.sort(key=lambda r: r['adr'])
+
+    return {
+        'ir_version': IR_VERSION,
+        'map_name': rmap.map_name,
+        'fingerprint': rmap.fingerprint,
+        'address_unit_bits': rmap.address_unit_bits,
+        'docs': docs_ir(rmap.docs),
+        'enums': {name: enum_ir(enum) for name, enum in rmap.enums.items()},
+        'blocks': {name: {
+            'docs': docs_ir(block.docs),
+            'instances': block.instances,
+            'templates': list(block.register_templates.keys()),
+            'array_stride': block.array_stride,
+            'group': block.group,
+        } for name, block in rmap.register_blocks.items()},
+        'transactions': {name: {
+            'docs': docs_ir(transaction.docs),
+            'registers': transaction.registers,
+        } for name, transaction in rmap.transactions.items()},
+        'split_values': {name: split_value_ir(rmap, split_value) for name, split_value in rmap.split_values.items()},
+        'constants': {name: {'docs': docs_ir(c.docs), 'value': c.value} for name, c in rmap.constants.items()},
+        'registers': registers,
+    }
+
+
+def split_value_ir(rmap: RegisterMap, split_value: SplitValue) -> Dict[str, Any]:
+    layout = rmap.split_value_layout(split_value)
+    return {
+        'docs': docs_ir(split_value.docs),
+        'width': sum(bits.total_width() for _, _, bits, _ in layout),
+        'parts': [{
+            'register': reg.name,
+            'field': field.name if field is not None else None,
+            'lsb': lsb,
+            'msb': lsb + bits.total_width() - 1,
+        } for reg, field, bits, lsb in layout],
+    }
+
+
+def register_ir(reg: Register) -> Dict[str, Any]:
+    bytewidth = ceil(reg.bitwidth / 8)
+
+    always_write = None
+    if reg.always_write is not None:
+        always_write = {'mask': reg.always_write.bits.get_bitmask(), 'value': reg.always_write.value,
+                        'dont_care': reg.always_write.dont_care}
+
+    return {
+        'name': reg.name,
+        'adr': reg.adr,
+        'bitwidth': reg.bitwidth,
+        'bytewidth': bytewidth,
+        'reset_val': reg.reset_val,
+        'docs': docs_ir(reg.docs),
+        'readable': reg.is_readable(),
+        'writable': reg.is_writable(),
+        'read_side_effects': reg.get_read_side_effects(),
+        'always_write': always_write,
+        'clock_domain': reg.clock_domain,
+        'power_domain': reg.power_domain,
+        'unlock': {'key_register': reg.unlock.key_register, 'keys': reg.unlock.keys} if reg.unlock is not None else None,
+        'volatile_mask': reg.get_volatile_mask(),
+        'recommended_val': reg.get_recommended_val() if reg.has_recommended_val() else None,
+        'unused_mask': reg.get_unused_bits(include_always_write=False).get_bitmask(),
+        'examples': [{'val': ex.val, 'fields': ex.fields, 'docs': docs_ir(ex.docs)} for ex in reg.examples],
+        'fields': [field_ir(field, bytewidth) for field in reg.fields.values()],
+        'write_fields': ([field_ir(field, bytewidth) for field in reg.write_fields.values()]
+                         if reg.write_fields is not None else None),
+        'variant_tag': reg.variant_tag,
+        'variants': {name: {
+            'tag': variant.tag,
+            'docs': docs_ir(variant.docs),
+            'fields': [field_ir(field, bytewidth) for field in variant.fields.values()],
+        } for name, variant in reg.variants.items()},
+    }
+
+
+def field_ir(field: Field, bytewidth: int) -> Dict[str, Any]:
+    bits = field.bits
+    enum = None  # type: Optional[Any]
+    if field.enum is not None:
+        enum = field.enum.name if field.enum.is_shared else {'name': field.enum.name} | enum_ir(field.enum)
+
+    fixed_point = None
+    if field.fixed_point is not None:
+        fixed_point = field.fixed_point.model_dump()
+
+    return {
+        'name': field.name,
+        'aliases': field.aliases,
+        'lsb': bits.lsb_position(),
+        'msb': bits.msb_position(),
+        'width': bits.total_width(),
+        'mask': bits.get_bitmask(),
+        'access': [mode.to_str() for mode in field.access],
+        'docs': docs_ir(field.docs),
+        'enum': enum,
+        'view': field.view,
+        'fixed_point': fixed_point,
+        'float_format': field.float_format,
+        'unit': field.unit,
+        'min': field.min,
+        'max': field.max,
+        'array': None if field.array is None else field.array.model_dump(),
+        'internal': field.internal,
+        'read_side_effect': field.read_side_effect,
+        'self_clearing': field.self_clearing,
+        'volatile': field.volatile,
+        'byte_order': field.byte_order,
+        'default': field.default,
+        'byte_transforms': {
+            'little': byte_transforms_ir(bits, ByteOrder.LITTLE, bytewidth),
+            'big': byte_transforms_ir(bits, ByteOrder.BIG, bytewidth),
+        },
+    }
+
+
+def byte_transforms_ir(bits: Bits, byte_order: ByteOrder, bytewidth: int) -> List[Dict[str, int]]:
+    return [{'byte': t.byte, 'mask': t.mask, 'shift': t.shift} for t in bits.get_byte_transforms(byte_order, bytewidth)]
+
+
+def enum_ir(enum: RegEnum) -> Dict[str, Any]:
+    return {
+        'docs': docs_ir(enum.docs),
+        'entries': [{'name': e.name, 'value': e.value, 'docs': docs_ir(e.docs), 'catch_all': e.catch_all}
+                    for e in enum.entries.values()],
+        'reserved': [[first, last] for first, last in enum.reserved],
+    }
+
+
+def docs_ir(docs: Docs) -> Dict[str, Optional[str]]:
+    return {'brief': docs.brief, 'doc': docs.doc}
@@ -0,0 +1,92 @@
+import argparse
+import json
+from typing import List
+
+from reginald.datamodel import RegisterMap
+from reginald.error import ReginaldException
+from reginald.generator import OutputGenerator
+
+
+class Generator(OutputGenerator):
+    def description(self):
+        return "JSON register map statistics (counts, documentation coverage) for dashboards and badges."
+
+    def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
+        _ = input_file
+        opts = parse_args(args)
+
+        stats = map_stats(rmap)
+
+        if opts.previous is not None:
+            previous = load_stats(opts.previous)
+            stats['diff'] = {
+                'registers': stats['registers'] - previous.get('registers', 0),
+                'fields': stats['fields'] - previous.get('fields', 0),
+                'undocumented_bits': stats['undocumented_bits'] - previous.get('undocumented_bits', 0),
+                'doc_coverage_percent': round(stats['doc_coverage_percent'] - previous.get('doc_coverage_percent', 0.0), 1),
+                'fingerprint_changed': stats['fingerprint'] != previous.get('fingerprint'),
+            }
+
+        if opts.badge:
+            # shields.io endpoint format:
+            coverage = stats['doc_coverage_percent']
+            output = {
+                'schemaVersion': 1,
+                'label': "register docs",
+                'message': f"{coverage:.0f}%",
+                'color': "green" if coverage >= 90 else "yellow" if coverage >= 60 else "red",
+            }
+        else:
+            output = stats
+
+        with open(output_file, 'w') as outfile:
+            json.dump(output, outfile, indent=2)
+            outfile.write("\n")
+
+
+def map_stats(rmap: RegisterMap) -> dict:
+    regs = rmap.physical_registers.values()
+    fields = [field for reg in regs for field in reg.get_all_fields().values()]
+
+    documented_regs = len([reg for reg in regs if not reg.docs.empty()])
+    documented_fields = len([field for field in fields if not field.docs.empty()])
+    documentable = len(regs) + len(fields)
+    coverage = 100.0 * (documented_regs + documented_fields) / documentable if documentable > 0 else 100.0
+
+    return {
+        'map_name': rmap.map_name,
+        'fingerprint': f"0x{rmap.fingerprint:08X}",
+        'register_blocks': len(rmap.register_blocks),
+        'registers': len(regs),
+        'fields': len(fields),
+        'shared_enums': len(rmap.enums),
+        'total_bits': sum(reg.bitwidth for reg in regs),
+        'undocumented_bits': sum(len(reg.get_unused_bits(include_always_write=False).bitlist) for reg in regs),
+        'documented_registers': documented_regs,
+        'documented_fields': documented_fields,
+        'doc_coverage_percent': round(coverage, 1),
+    }
+
+
+def load_stats(file_name: str) -> dict:
+    try:
+        with open(file_name) as f:
+            data = json.load(f)
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except json.JSONDecodeError as e:
+        raise ReginaldException(f"{file_name}: Invalid JSON: {e}")
+    if not isinstance(data, dict):
+        raise ReginaldException(f"{file_name}: Expected a JSON object")
+    return data
+
+
+def parse_args(args: List[str]):
+    parser = argparse.ArgumentParser(
+        prog="json.stats",
+        description="JSON register map statistics generator.")
+    parser.add_argument('--previous',
+                        help="statistics file of an earlier version of the map, to include a diff against it")
+    parser.add_argument('--badge', action='store_true',
+                        help="emit a shields.io endpoint badge showing documentation coverage instead")
+    return parser.parse_args(args)
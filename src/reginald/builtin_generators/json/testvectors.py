@@ -0,0 +1,116 @@
+import argparse
+import csv
+import json
+from math import ceil
+from os import path
+from typing import Any, Dict, List, Optional
+
+from reginald.datamodel import Register, RegisterMap
+from reginald.generator import OutputGenerator
+
+# Stimulus/expected-response vectors for hardware verification benches,
+# derived from the listing:
+#
+#   1. 'reset' vectors: Read every register with a reset value, and compare
+#      the readable bits against it. These come first, before any write.
+#   2. 'example' vectors: Write every example of a writable register, read it
+#      back, and compare the readable bits against the written value.
+#
+# Readable bits are those of fields that are readable (or of unknown access).
+# Fields with side effects (write-1-to-clear/set/toggle, read-clears) are not
+# compared in example vectors, as they do not read back as written. Reset
+# vectors are read once, so read-clears fields are compared there.
+# Values are given as bytes in target byte order, with a mask of the bytes'
+# bits to compare. In CSV, byte lists are space-separated hex.
+
+
+class Generator(OutputGenerator):
+    def description(self):
+        return "Test vectors (register address, write bytes, expected read-back) from reset values and examples, as JSON or CSV."
+
+    def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
+        _ = input_file
+        opts = parse_args(args)
+
+        output_format = opts.format
+        if output_format is None:
+            output_format = 'csv' if path.splitext(output_file)[1].lower() == '.csv' else 'json'
+
+        vectors = map_test_vectors(rmap, opts.byte_order)
+
+        with open(output_file, 'w', newline='') as outfile:
+            if output_format == 'csv':
+                writer = csv.writer(outfile)
+                writer.writerow(['step', 'register', 'adr', 'kind', 'write', 'expect', 'mask'])
+                for vector in vectors:
+                    writer.writerow([vector['step'], vector['register'], f"0x{vector['adr']:X}", vector['kind'],
+                                     hex_bytes(vector['write']), hex_bytes(vector['expect']), hex_bytes(vector['mask'])])
+            else:
+                json.dump({
+                    'map_name': rmap.map_name,
+                    'fingerprint': f"0x{rmap.fingerprint:08X}",
+                    'byte_order': opts.byte_order,
+                    'vectors': vectors,
+                }, outfile, indent=2)
+                outfile.write("\n")
+
+
+def map_test_vectors(rmap: RegisterMap, byte_order: str) -> List[Dict[str, Any]]:
+    regs = sorted(rmap.physical_registers.values(), key=lambda r: r.adr)
+    vectors = []  # type: List[Dict[str, Any]]
+
+    def add(reg: Register, kind: str, write: Optional[int], expect: int, mask: int):
+        bytewidth = ceil(reg.bitwidth / 8)
+        vectors.append({
+            'step': len(vectors),
+            'register': reg.name,
+            'adr': reg.adr,
+            'kind': kind,
+            'write': None if write is None else list(write.to_bytes(bytewidth, byte_order)),
+            'expect': list((expect & mask).to_bytes(bytewidth, byte_order)),
+            'mask': list(mask.to_bytes(bytewidth, byte_order)),
+        })
+
+    for reg in regs:
+        if reg.reset_val is not None and readable_mask(reg) != 0:
+            add(reg, 'reset', None, reg.reset_val, readable_mask(reg))
+
+    for reg in regs:
+        mask = readable_mask(reg, include_side_effects=False)
+        if not writable(reg) or mask == 0:
+            continue
+        for example in reg.examples:
+            add(reg, 'example', example.val, example.val, mask)
+
+    return vectors
+
+
+def readable_mask(reg: Register, include_side_effects: bool = True) -> int:
+    mask = 0
+    for field in reg.get_layout_fields().values():
+        if not include_side_effects and field.has_side_effect():
+            continue
+        if len(field.access) == 0 or field.is_readable():
+            mask |= field.bits.get_bitmask()
+    return mask
+
+
+def writable(reg: Register) -> bool:
+    return any(len(field.access) == 0 or field.is_writable() for field in reg.get_layout_fields().values())
+
+
+def hex_bytes(data: Optional[List[int]]) -> str:
+    if data is None:
+        return ""
+    return " ".join(f"{b:02X}" for b in data)
+
+
+def parse_args(args: List[str]):
+    parser = argparse.ArgumentParser(
+        prog="json.testvectors",
+        description="Test vector generator for hardware verification benches.")
+    parser.add_argument('--format', choices=['json', 'csv'], default=None,
+                        help="output format (default: csv for .csv output files, json otherwise)")
+    parser.add_argument('--byte-order', choices=['little', 'big'], default='little',
+                        help="byte order of registers in target memory (default: little)")
+    return parser.parse_args(args)
@@ -0,0 +1,84 @@
+import argparse
+from math import ceil
+from os import path
+from typing import Dict, List, Tuple
+
+from tabulate import tabulate
+
+from reginald.datamodel import Register, RegisterMap
+from reginald.generator import OutputGenerator
+from reginald.input.errors_yaml import yaml_key_marks
+
+
+class Generator(OutputGenerator):
+    def description(self):
+        return "Markdown debug table showing where every bit of every register layout comes from."
+
+    def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
+        opts = parse_args(args)
+        lines = yaml_key_lines(input_file)
+        source = path.basename(input_file)
+
+        out = []
+        out.append(f"# {rmap.map_name} Bit Provenance")
+        out.append(f"")
+        out.append(f"Byte order: {opts.byte_order}-endian. Byte 0 is the first byte in memory/on the bus.")
+        out.append(f"")
+
+        for block in rmap.register_blocks.values():
+            for template_name, template in block.register_templates.items():
+                if template_name == "":
+                    key_path = ('registers', block.name)  # type: Tuple[str, ...]
+                else:
+                    key_path = ('registers', block.name, 'registers', template_name)
+
+                out.append(f"## {block.name + template_name}:")
+                out.append(f"")
+                out.append(tabulate(provenance_rows(template, opts.byte_order, key_path, lines, source),
+                                    headers=["Byte", "Byte Bit", "Register Bit", "Content", "Source"], tablefmt="pipe"))
+                out.append(f"")
+
+        with open(output_file, 'w') as outfile:
+            outfile.write("\n".join(out))
+
+
+def provenance_rows(reg: Register, byte_order: str, key_path: Tuple[str, ...],
+                    lines: Dict[Tuple[str, ...], int], source: str) -> List[List[str]]:
+    bytewidth = ceil(reg.bitwidth / 8)
+
+    rows = []
+    for bit in reversed(range(reg.bitwidth)):
+        byte = bit // 8 if byte_order == 'little' else bytewidth - 1 - bit // 8
+
+        field_name = reg.get_fieldname_at(bit)
+        if field_name is not None:
+            field = reg.fields[field_name]
+            field_bit = sorted(field.bits.get_bitlist()).index(bit)
+            content = f"{field_name}[{field_bit}]"
+            line = lines.get(key_path + ('fields', field_name))
+        elif reg.is_bit_always_write(bit):
+            assert reg.always_write is not None
+            content = f"always write {(reg.always_write.value >> bit) & 1}"
+            if (reg.always_write.dont_care >> bit) & 1:
+                content += ", don't care on read"
+            line = lines.get(key_path + ('always_write',))
+        else:
+            content = "(unused)"
+            line = None
+
+        rows.append([str(byte), str(bit % 8), str(bit), content, f"{source}:{line}" if line is not None else ""])
+    return rows
+
+
+def yaml_key_lines(file_name: str) -> Dict[Tuple[str, ...], int]:
+    # Line number (1-based) of every mapping key in a YAML listing. Empty for other inputs.
+    return {key_path: line for key_path, (line, _) in yaml_key_marks(file_name).items()}
+
+
+def parse_args(args: List[str]):
+    parser = argparse.ArgumentParser(
+        prog="md.bitprovenance",
+        description="Markdown bit provenance debug generator.")
+    parser.add_argument('--byte-order', choices=['little', 'big'], default='little',
+                        help="byte order used to number the bytes of registers wider than one byte (default: little)")
+    return parser.parse_args(args)
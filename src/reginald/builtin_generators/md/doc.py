@@ -1,10 +1,14 @@
-from typing import List
+import argparse
+import json
+import re
+from typing import Any, Dict, List, Optional, Tuple
 
 from tabulate import tabulate
 
-from reginald.datamodel import RegisterMap, Bits
+from reginald.datamodel import (RegisterMap, Bits, Docs, Register,
+                                SecurityAttributes, plain_doc_ref)
 from reginald.generator import OutputGenerator
-from reginald.utils import str_list
+from reginald.utils import str_list, str_oneline
 
 
 class Generator(OutputGenerator):
@@ -15,9 +19,13 @@ class Generator(OutputGenerator):
         out = []
 
         _ = input_file
-        _ = args
+        opts = parse_args(args)
+        rmap = rmap.with_rendered_doc_refs(md_doc_ref_renderer(rmap))
 
         registers = []
+        group_of = {}  # type: Dict[str, Optional[str]]
+        aliases_of = {}  # type: Dict[str, List[str]]
+        security_of = {}  # type: Dict[str, SecurityAttributes]
         for block in rmap.register_blocks.values():
             for template_name, template in block.register_templates.items():
                 for instance_name, instance_adr in block.instances.items():
@@ -25,13 +33,23 @@ class Generator(OutputGenerator):
                     register_adr = instance_adr + template.adr
 
                     registers.append((register_adr, register_name, template))
+                    group_of[register_name] = block.group
+                    aliases_of[register_name] = block.get_alias_names(template, instance_name)
+                    security_of[register_name] = block.get_security(template, instance_name)
 
-        registers.sort(key=lambda x: x[0])
+        # Grouped registers get a chapter per group, ungrouped registers follow:
+        group_order = {group: idx for idx, group in enumerate(rmap.groups)}
+        registers.sort(key=lambda x: (group_order.get(group_of[x[1]], len(group_order)), x[0]))
 
         # Generate header:
         out.append(f"# {rmap.map_name} Register Map")
         out.extend(rmap.docs.as_multi_line(prefix=""))
         out.append("")
+        out.append(f"Fingerprint: 0x{rmap.fingerprint:08X}")
+        out.append("")
+        if rmap.address_unit_bits != 8:
+            out.append(f"Addresses are in units of {rmap.address_unit_bits} bits.")
+            out.append("")
 
         # Generate overview table:
         out.append(f"## Overview:")
@@ -40,96 +58,244 @@ class Generator(OutputGenerator):
 
         for reg_adr, reg_name, template in registers:
 
-            fields = str_list(template.fields.keys())
-            rows.append([hex(reg_adr), reg_name, fields])
+            fields = str_list(template.get_all_fields().keys())
+            if len(rmap.groups) > 0:
+                rows.append([hex(reg_adr), reg_name, group_of[reg_name] or "", fields])
+            else:
+                rows.append([hex(reg_adr), reg_name, fields])
         out.append("")
-        out.append(tabulate(rows, headers=["Address", "Register", "Fields"], tablefmt="pipe"))
+        if len(rmap.groups) > 0:
+            out.append(tabulate(rows, headers=["Address", "Register", "Group", "Fields"], tablefmt="pipe"))
+        else:
+            out.append(tabulate(rows, headers=["Address", "Register", "Fields"], tablefmt="pipe"))
         out.append("")
 
+        # Generate constants (if any):
+        if len(rmap.constants) > 0:
+            out.append(f"## Constants:")
+            out.append("")
+            rows = [[c.name, f"0x{c.value:X}", str_oneline(c.docs.brief) if c.docs.brief is not None else ""]
+                    for c in rmap.constants.values()]
+            out.append(tabulate(rows, headers=["Constant", "Value", "Description"], tablefmt="pipe"))
+            out.append("")
+
+        # Generate transaction overview (if any):
+        if len(rmap.transactions) > 0:
+            out.append(f"## Transactions:")
+            out.append("")
+            out.append(f"Registers of a transaction have to be written together, in the given order.")
+            out.append("")
+            for transaction in rmap.transactions.values():
+                out.append(f" - {transaction.name}: {' -> '.join(transaction.registers)}")
+                out.extend(transaction.docs.as_two_line(prefix="   - "))
+            out.append("")
+
+        # Generate split value overview (if any):
+        if len(rmap.split_values) > 0:
+            out.append(f"## Split Values:")
+            out.append("")
+            out.append(f"Values that are split across several registers (or fields of them), most significant part first.")
+            out.append("")
+            for split_value in rmap.split_values.values():
+                layout = rmap.split_value_layout(split_value)
+                out.append(f"### {split_value.name}:")
+                out.extend(split_value.docs.as_two_line(prefix=" - "))
+                out.append("")
+                bitrow = ["Bits:"]
+                part_row = ["Part:"]
+                for part, (reg, field, bits, lsb) in zip(split_value.parts, layout):
+                    msb = lsb + bits.total_width() - 1
+                    bitrow.append(f"{msb}-{lsb}" if msb != lsb else f"{lsb}")
+                    part_row.append(f"{part.to_str()} [{bits.get_bitrange()}]")
+                out.append(tabulate([bitrow, part_row], headers="firstrow",
+                                    tablefmt="pipe", numalign="center", stralign="center"))
+                out.append("")
+
         # Generate register section:
 
-        out.append(f"## Registers:")
+        current_group = None
+        if len(rmap.groups) == 0:
+            out.append(f"## Registers:")
         for reg_adr, reg_name, template in registers:
+            # Group chapter:
+            if len(rmap.groups) > 0 and (reg_name == registers[0][1] or group_of[reg_name] != current_group):
+                current_group = group_of[reg_name]
+                out.append(f"## {current_group} Registers:" if current_group is not None else f"## Other Registers:")
+
             # Register name:
             out.append(f"### {reg_name}:")
 
             # Register info:
             out.extend(template.docs.as_two_line(prefix=" - "))
-            out.append(f" - Address: 0x{reg_adr:X}")
+            if rmap.address_unit_bits != 8:
+                out.append(f" - Address: 0x{reg_adr:X} (byte address 0x{rmap.byte_address(reg_adr):X})")
+            else:
+                out.append(f" - Address: 0x{reg_adr:X}")
+            if len(aliases_of[reg_name]) > 0:
+                out.append(f" - Formerly: {', '.join(aliases_of[reg_name])} (deprecated)")
             if template.reset_val is not None:
                 out.append(f" - Reset Val: 0x{template.reset_val:X}")
-
-            # Register bitfields table:
-
-            # Collect all bitranges that make up this register - field or not:
-            register_bitranges = []
-            for field in template.fields.values():
-                for range in field.get_bitranges():
-                    register_bitranges.append(range)
-            register_bitranges.extend(template.get_unused_bits(include_always_write=True).get_bitranges())
-
-            # Sort bitranges:
-            register_bitranges = sorted(register_bitranges, key=lambda x: x.lsb_position, reverse=True)
-
-            bitrow = ["Bits:"]
-            field_row = ["Field:"]
-            access_row = ["Access:"]
-
-            for bitrange in register_bitranges:
-                # Retrieve field that coresponds to this range (if any):
-                field_name = template.get_fieldname_at(bitrange.lsb_position)
-
-                bitrow.append(str(bitrange))
-
-                if field_name is not None:
-                    field = template.fields[field_name]
-
-                    field_row.append(field_name)
-                    if field.access is not None:
-                        access_row.append(field.access_str())
+            if template.has_recommended_val():
+                out.append(f" - Recommended Val: 0x{template.get_recommended_val():X}")
+            if template.array is not None:
+                out.append(f" - Element {template.array.index} of register array {template.array.name} "
+                           f"({template.array.count} registers, stride 0x{template.array.stride:X})")
+            if len(template.get_read_side_effects()) > 0:
+                out.append(f" - **Reading has side effects**: {', '.join(template.get_read_side_effects())}")
+            if template.volatile:
+                out.append(f" - **Volatile**: Updated by hardware, never cache")
+            if not security_of[reg_name].empty():
+                out.append(f" - Access restricted to: {security_of[reg_name].to_str()}")
+            if template.clock_domain is not None:
+                out.append(f" - Clock domain: {template.clock_domain}")
+            if template.power_domain is not None:
+                out.append(f" - Power domain: {template.power_domain}")
+            if template.unlock is not None:
+                keys = ', '.join(f"0x{key:X}" for key in template.unlock.keys)
+                key_reg = template.unlock.key_register
+                out.append(f" - **Locked**: Write {keys} to [{key_reg}](#{md_anchor(key_reg + ':')}), in order, before writing")
+            for transaction in rmap.transactions_of(reg_name):
+                out.append(f" - Part of transaction {transaction.name}: Write as {' -> '.join(transaction.registers)}")
+            for split_value in rmap.split_values_of(reg_name):
+                parts = ' : '.join(p.to_str() for p in split_value.parts)
+                out.append(f" - Part of split value [{split_value.name}](#{md_anchor(split_value.name + ':')}): {parts}")
+
+            # Register bitfields table and field info, for both layouts of registers with
+            # separate read and write layouts, and for every variant:
+            for view in template.get_layout_views():
+                if view.layout_view is not None:
+                    out.append("")
+                    out.append(f"**{view.layout_view.capitalize()} layout**:")
+                if view.variant is not None:
+                    variant = template.variants[view.variant]
+                    out.append("")
+                    out.append(f"**Variant {variant.name}** ({template.variant_tag} = 0x{variant.tag:X}):")
+                    if not variant.docs.empty():
+                        out.append("")
+                        out.extend(variant.docs.as_two_line(prefix=" - "))
+
+                # Collect all bitranges that make up this register - field or not:
+                register_bitranges = []
+                for field in view.get_layout_fields().values():
+                    for range in field.get_bitranges():
+                        register_bitranges.append(range)
+                register_bitranges.extend(view.get_unused_bitranges())
+
+                # Sort bitranges:
+                register_bitranges = sorted(register_bitranges, key=lambda x: x.lsb_position, reverse=True)
+
+                bitrow = ["Bits:"]
+                field_row = ["Field:"]
+                access_row = ["Access:"]
+
+                for bitrange in register_bitranges:
+                    # Retrieve field that coresponds to this range (if any):
+                    field_name = view.get_fieldname_at(bitrange.lsb_position)
+
+                    bitrow.append(str(bitrange))
+
+                    if field_name is not None:
+                        field = view.fields[field_name]
+
+                        field_row.append(field_name)
+                        if field.access is not None:
+                            access_row.append(field.access_str())
+                        else:
+                            access_row.append("?")
+
+                    elif view.is_bit_always_write(bitrange.lsb_position):
+                        access_row.append("")
+                        val = view.get_always_write_value(Bits.from_bitrange(bitrange))
+                        field = f"Always write 0x{val:x}"
+                        assert view.always_write is not None
+                        if view.always_write.dont_care & bitrange.get_bitmask() != 0:
+                            field += " (don't care on read)"
+                        field_row.append(field)
                     else:
                         access_row.append("?")
+                        field_row.append("?")
 
-                elif template.is_bit_always_write(bitrange.lsb_position):
-                    access_row.append("")
-                    val = template.get_always_write_value(Bits.from_bitrange(bitrange))
-                    field = f"Always write 0x{val:x}"
-                    field_row.append(field)
-                else:
-                    access_row.append("?")
-                    field_row.append("?")
-
-            out.append("")
-            out.append(tabulate([bitrow, field_row, access_row], headers="firstrow",
-                                tablefmt="pipe", numalign="center", stralign="center"))
-            out.append("")
+                out.append("")
+                out.append(tabulate([bitrow, field_row, access_row], headers="firstrow",
+                                    tablefmt="pipe", numalign="center", stralign="center"))
+                out.append("")
 
-            # Field info:
-            out.append("")
-            out.append(f"*Bitfields*:")
+                # Field info:
+                out.append("")
+                out.append(f"*Bitfields*:")
 
-            for field_name, field in template.fields.items():
+                for field_name, field in view.fields.items():
 
-                # Access (if any):
-                if len(field.access) > 0:
-                    access_str = f" [{field.access_str()}]"
-                else:
-                    access_str = ""
+                    # Access (if any):
+                    if len(field.access) > 0:
+                        access_str = f" [{field.access_str()}]"
+                    else:
+                        access_str = ""
 
+                    out.append("")
+                    if field.view:
+                        out.append(f"  - {field_name}{access_str} (view of bits {', '.join(str(r) for r in field.get_bitranges())}):")
+                    else:
+                        out.append(f"  - {field_name}{access_str}:")
+
+                    # Documentation (if any):
+                    out.extend(field.docs.as_two_line(prefix="    - "))
+                    if len(field.aliases) > 0:
+                        out.append(f"    - Formerly: {', '.join(field.aliases)} (deprecated)")
+                    if field.read_side_effect is not None:
+                        out.append(f"    - **Reading has side effects**: {field.read_side_effect}")
+                    if field.self_clearing:
+                        out.append(f"    - **Self-clearing**: Write 1 to trigger, cleared by hardware")
+                    if field.byte_order == 'big':
+                        out.append(f"    - Byte order: Big-endian (most significant byte in the lowest bits of the field)")
+                    if field.volatile and not template.volatile:
+                        out.append(f"    - **Volatile**: Updated by hardware, never cache")
+                    unit = f" [{field.unit}]" if field.unit is not None else ""
+                    if field.fixed_point is not None:
+                        signed = " (raw is signed)" if field.fixed_point.signed else ""
+                        out.append(f"    - Physical value{unit}: raw * {field.fixed_point.scale!r} + {field.fixed_point.offset!r}{signed}")
+                    elif field.float_format is not None:
+                        out.append(f"    - Physical value{unit}: {field.float_format} float")
+                    elif field.unit is not None:
+                        out.append(f"    - Unit: {field.unit}")
+                    if field.has_range():
+                        out.append(f"    - Valid range (raw): {field.range_str()}")
+                    if field.default is not None:
+                        entry_name = field.lookup_enum_entry_name(field.default)
+                        default_name = f"{entry_name} (0x{field.default:X})" if entry_name is not None else f"0x{field.default:X}"
+                        out.append(f"    - Recommended: {default_name}")
+
+                    # Accepted values (through local or global enum):
+                    if field.enum is not None:
+                        if field.enum.is_shared:
+                            enum_brief = f": {field.enum.docs.brief}" if field.enum.docs.brief is not None else ""
+                            out.append(f"    - Accepts (shared enum {field.enum.name}{enum_brief}):")
+                        else:
+                            out.append(f"    - Accepts:")
+                        for entry in field.enum.entries.values():
+                            catch_all = " - *all other values*" if entry.catch_all else ""
+                            if entry.docs.brief is not None:
+                                out.append(f"      - {entry.name}: 0x{entry.value:X}{catch_all} ({entry.docs.brief})")
+                            else:
+                                out.append(f"      - {entry.name}: 0x{entry.value:X}{catch_all}")
+                        if len(field.enum.reserved) > 0:
+                            out.append(f"      - *Reserved*: {field.enum.reserved_str()}")
+
+            # Examples (if any):
+            if len(template.examples) > 0:
                 out.append("")
-                out.append(f"  - {field_name}{access_str}:")
-
-                # Documentation (if any):
-                out.extend(field.docs.as_two_line(prefix="    - "))
-
-                # Accepted values (through local or global enum):
-                if field.enum is not None:
-                    out.append(f"    - Accepts:")
-                    for entry in field.enum.entries.values():
-                        if entry.docs.brief is not None:
-                            out.append(f"      - {entry.name}: 0x{entry.value:X} ({entry.docs.brief})")
+                out.append(f"*Examples*:")
+                out.append("")
+                for example in template.examples:
+                    field_strs = []
+                    for field_name, field_val in example.fields.items():
+                        entry_name = template.fields[field_name].lookup_enum_entry_name(field_val)
+                        if entry_name is not None:
+                            field_strs.append(f"{field_name}={entry_name} (0x{field_val:X})")
                         else:
-                            out.append(f"      - {entry.name}: 0x{entry.value:X}")
+                            field_strs.append(f"{field_name}=0x{field_val:X}")
+                    out.append(f"  - 0x{example.val:X}: {str_list(field_strs)}")
+                    out.extend(example.docs.as_two_line(prefix="    - "))
 
             # horizontal rule:
             out.append("")
@@ -137,3 +303,72 @@ class Generator(OutputGenerator):
 
         with open(output_file, 'w') as outfile:
             outfile.write("\n".join(out))
+
+        if opts.search_index is not None:
+            with open(opts.search_index, 'w') as outfile:
+                json.dump(search_index(rmap, registers), outfile, separators=(',', ':'), ensure_ascii=False)
+                outfile.write("\n")
+
+
+# Search index: Every register, field, and enum entry, with the address of
+# its register, its documentation as one line, and the anchor of its
+# register's heading in the generated markdown:
+#
+#   {"map_name": ..., "fingerprint": ..., "entries": [
+#     {"kind": "register"|"field"|"enum", "name": ..., "register": ...,
+#      "adr": ..., "text": ..., "anchor": ...}, ...]}
+
+
+def search_index(rmap: RegisterMap, registers: List[Tuple[int, str, Register]]) -> Dict[str, Any]:
+    entries = []  # type: List[Dict[str, Any]]
+
+    def add(kind: str, name: str, reg_name: str, reg_adr: int, docs: Docs):
+        entries.append({
+            'kind': kind,
+            'name': name,
+            'register': reg_name,
+            'adr': reg_adr,
+            'text': " ".join(str_oneline(text) for text in [docs.brief, docs.doc] if text is not None),
+            'anchor': md_anchor(f"{reg_name}:"),
+        })
+
+    for reg_adr, reg_name, template in registers:
+        add('register', reg_name, reg_name, reg_adr, template.docs)
+        for field in template.get_all_fields().values():
+            add('field', f"{reg_name}.{field.name}", reg_name, reg_adr, field.docs)
+            if field.enum is not None:
+                for entry in field.enum.entries.values():
+                    add('enum', f"{reg_name}.{field.name}.{entry.name}", reg_name, reg_adr, entry.docs)
+
+    return {
+        'map_name': rmap.map_name,
+        'fingerprint': f"0x{rmap.fingerprint:08X}",
+        'entries': entries,
+    }
+
+
+def md_doc_ref_renderer(rmap: RegisterMap):
+    # Documentation cross-references link to the register heading (the first
+    # instance for generic block register names):
+    def render(register: str, field: Optional[str]) -> str:
+        found = rmap.template_by_name(register)
+        heading = register
+        if found is not None and rmap.register_by_name(register) is None:
+            block, template = found
+            heading = next(iter(block.instances.keys())) + template.name
+        return f"[{plain_doc_ref(register, field)}](#{md_anchor(heading + ':')})"
+    return render
+
+
+def md_anchor(heading: str) -> str:
+    # GitHub-style heading anchor:
+    return re.sub(r"[^\w\- ]", "", heading.strip().lower()).replace(" ", "-")
+
+
+def parse_args(args: List[str]):
+    parser = argparse.ArgumentParser(
+        prog="md.doc",
+        description="Markdown register documentation generator.")
+    parser.add_argument('--search-index', metavar='FILE',
+                        help="also write a compact JSON search index of all registers, fields, and enum entries to FILE")
+    return parser.parse_args(args)
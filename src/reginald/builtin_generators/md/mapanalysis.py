@@ -0,0 +1,100 @@
+from math import ceil
+from typing import List, Tuple
+
+from tabulate import tabulate
+
+from reginald.datamodel import Register, RegisterBlock, RegisterMap
+from reginald.generator import OutputGenerator
+
+
+class Generator(OutputGenerator):
+    def description(self):
+        return "Markdown register map address analysis (alignment, gaps, overlaps)."
+
+    def generate(self, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
+        _ = input_file
+        _ = args
+
+        out = []
+
+        # Generate header:
+        out.append(f"# {rmap.map_name} Address Analysis")
+        out.append(f"")
+        if rmap.address_unit_bits == 8:
+            out.append(f"Note: Addresses are assumed to be byte addresses.")
+        else:
+            out.append(f"Note: Addresses are in units of {rmap.address_unit_bits} bits.")
+        out.append(f"")
+
+        # Unaligned registers:
+        rows = []
+        for adr, name, reg in physical_registers(rmap):
+            if adr % register_size(rmap, reg) != 0:
+                rows.append([f"0x{adr:X}", name, reg.bitwidth, register_size(rmap, reg)])
+        out.append(f"## Unaligned registers:")
+        out.append(f"")
+        out.extend(table_or_none(rows, ["Address", "Register", "Bitwidth", "Required Alignment"]))
+
+        # Overlapping registers:
+        rows = []
+        regs = physical_registers(rmap)
+        for i, (adr_a, name_a, reg_a) in enumerate(regs):
+            for adr_b, name_b, _ in regs[i+1:]:
+                if adr_b >= adr_a + register_size(rmap, reg_a):
+                    break
+                rows.append([f"0x{adr_a:X}", name_a, f"0x{adr_b:X}", name_b])
+        out.append(f"## Overlapping registers:")
+        out.append(f"")
+        out.extend(table_or_none(rows, ["Address", "Register", "Overlaps At", "With Register"]))
+
+        # Gaps inside register blocks:
+        rows = []
+        for block in rmap.register_blocks.values():
+            templates = sorted(block.register_templates.values(), key=lambda t: t.adr)
+            for this, following in zip(templates, templates[1:]):
+                end = this.adr + register_size(rmap, this)
+                if following.adr > end:
+                    rows.append([block.name, f"0x{end:X}", f"0x{following.adr-1:X}", following.adr - end])
+        out.append(f"## Gaps inside register blocks:")
+        out.append(f"")
+        out.extend(table_or_none(rows, ["Block", "Gap Start Offset", "Gap End Offset", "Size"]))
+
+        # Instances that are closer together than the block is long:
+        rows = []
+        for block in rmap.register_blocks.values():
+            span = block_span(rmap, block)
+            instances = sorted(block.instances.items(), key=lambda i: i[1])
+            for (name, adr), (next_name, next_adr) in zip(instances, instances[1:]):
+                if next_adr - adr < span:
+                    rows.append([block.name, name, next_name, f"0x{next_adr-adr:X}", f"0x{span:X}"])
+        out.append(f"## Block instances closer together than block span:")
+        out.append(f"")
+        out.extend(table_or_none(rows, ["Block", "Instance", "Next Instance", "Stride", "Block Span"]))
+
+        with open(output_file, 'w') as outfile:
+            outfile.write("\n".join(out))
+
+
+def register_size(rmap: RegisterMap, reg: Register) -> int:
+    # Size of register in address units.
+    return ceil(reg.bitwidth / rmap.address_unit_bits)
+
+
+def block_span(rmap: RegisterMap, block: RegisterBlock) -> int:
+    return max(t.adr + register_size(rmap, t) for t in block.register_templates.values())
+
+
+def physical_registers(rmap: RegisterMap) -> List[Tuple[int, str, Register]]:
+    registers = []
+    for block in rmap.register_blocks.values():
+        for template_name, template in block.register_templates.items():
+            for instance_name, instance_adr in block.instances.items():
+                registers.append((instance_adr + template.adr, instance_name + template_name, template))
+    registers.sort(key=lambda x: x[0])
+    return registers
+
+
+def table_or_none(rows: List[List], headers: List[str]) -> List[str]:
+    if len(rows) == 0:
+        return ["None.", ""]
+    return [tabulate(rows, headers=headers, tablefmt="pipe"), ""]
@@ -1,4 +1,4 @@
-from typing import Dict, List, Union
+from typing import Dict, List, Optional, Union
 
 import yaml
 from pydantic import ValidationError, NonNegativeInt
@@ -6,7 +6,8 @@ from pydantic.dataclasses import dataclass
 from tabulate import tabulate
 from yaml import SafeLoader
 
-from reginald.datamodel import RegisterMap, Bits
+from reginald.datamodel import RegisterMap, Bits, plain_doc_ref
+from reginald.decode import decode_register
 from reginald.error import ReginaldException
 from reginald.generator import OutputGenerator
 
@@ -14,6 +15,7 @@ from reginald.generator import OutputGenerator
 @dataclass
 class YamlBinaryDump:
     binary: Dict[NonNegativeInt, Union[List[NonNegativeInt], NonNegativeInt]]
+    fingerprint: Optional[NonNegativeInt] = None
 
     @classmethod
     def from_yaml_file(cls, file_name: str):
@@ -55,6 +57,7 @@ class Generator(OutputGenerator):
 
         _ = input_file
         _ = args
+        rmap = rmap.with_rendered_doc_refs(plain_doc_ref)
 
         registers = []
         for block in rmap.register_blocks.values():
@@ -79,6 +82,13 @@ class Generator(OutputGenerator):
         # Generate header:
         out.append(f"# {rmap.map_name} Register Dump Analysis")
         out.append(f"")
+        if dump_yaml.fingerprint is not None:
+            if dump_yaml.fingerprint == rmap.fingerprint:
+                out.append(f"Fingerprint: 0x{rmap.fingerprint:08X} (matches register map)")
+            else:
+                out.append(f"Fingerprint: *ERROR*: Dump was taken with register map 0x{dump_yaml.fingerprint:08X}, "
+                           f"but this register map is 0x{rmap.fingerprint:08X}!")
+            out.append(f"")
         out.append(f"")
 
         for adr in adrs:
@@ -101,7 +111,7 @@ class Generator(OutputGenerator):
                 for field in reg_template.fields.values():
                     for range in field.get_bitranges():
                         register_bitranges.append(range)
-                register_bitranges.extend(reg_template.get_unused_bits(include_always_write=True).get_bitranges())
+                register_bitranges.extend(reg_template.get_unused_bitranges())
 
                 # Sort bitranges:
                 register_bitranges = sorted(register_bitranges, key=lambda x: x.lsb_position, reverse=True)
@@ -139,7 +149,9 @@ class Generator(OutputGenerator):
                         val = reg_template.get_always_write_value(Bits.from_bitrange(bitrange))
                         field = f"Always write 0x{val:x}"
                         field_row.append(field)
-                        if val == field_val:
+                        assert reg_template.always_write is not None
+                        check_mask = reg_template.always_write.get_check_mask() >> bitrange.lsb_position
+                        if val & check_mask == field_val & check_mask:
                             decode_row.append(f"OK")
                         else:
                             decode_row.append(f"ERROR")
@@ -155,16 +167,23 @@ class Generator(OutputGenerator):
                 # Field info:
                 out.append(f"*Bitfields*:")
 
-                for field_name, field in reg_template.fields.items():
-                    field_val = field.bits.extract_this_field_from(dump[adr])
-                    out.append(f"   - {field_name}: 0x{field_val:X}")
+                decoded = decode_register(reg_template, dump[adr])
+                fields = list(reg_template.fields.values())
+                if decoded.variant is not None:
+                    out.append(f"   - Variant: {decoded.variant}")
+                    fields.extend(reg_template.variants[decoded.variant].fields.values())
+                for field, decoded_field in zip(fields, decoded.fields):
+                    out.append(f"   - {field.name}: 0x{decoded_field.raw:X}")
                     out.extend(field.docs.as_two_line(prefix="     - "))
 
                     if field.enum is not None:
-                        enum_entryname = field.lookup_enum_entry_name(field_val)
+                        enum_entryname = decoded_field.enum_entry
                         if enum_entryname is not None:
                             entry = field.enum.entries[enum_entryname]
-                            out.append(f"       - *SELECTED*: {enum_entryname}")
+                            if field.enum.is_shared:
+                                out.append(f"       - *SELECTED*: {enum_entryname} (shared enum {field.enum.name})")
+                            else:
+                                out.append(f"       - *SELECTED*: {enum_entryname}")
                             out.extend(entry.docs.as_two_line(prefix="         - "))
                         else:
                             decode_row.append(
@@ -15,6 +15,18 @@
 #ifndef {{ c_macro(output_file) }}_
 #define {{ c_macro(output_file) }}_
 
+#define {{ c_macro(rmap.map_name) }}__FINGERPRINT ({{ "0x%08X" % rmap.fingerprint }}U) // Register map fingerprint.
+{% if rmap.address_unit_bits != 8 %}
+#define {{ c_macro(rmap.map_name) }}__ADDRESS_UNIT_BITS ({{ rmap.address_unit_bits }}U) // Bits per address, all register addresses are in these units.
+{% endif %}
+{% if rmap.constants|length > 0 %}
+
+{{ c_header("Constants") }}
+{%   for constant in rmap.constants.values() %}
+#define {{ c_macro(rmap.map_name) }}__CONST_{{ c_macro(constant.name) }} ({{ hex(constant.value) }}{{ "U" if constant.value < 2**32 else "ULL" }}) // Constant{{ brief_doc(constant.docs, ' "','"') }}.
+{%   endfor %}
+{% endif %}
+
 {% for block in rmap.register_blocks.values() %}
 {%   for template in block.register_templates.values() %}
 
@@ -27,25 +39,72 @@
 
 {%     for instance_name, instance_start in block.instances.items() %}
 #define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(instance_name+template.name) }} ({{ hex(instance_start+template.adr) }}U) // Register address{{ brief_doc(template.docs, ' "','"') }}.
+{%       if rmap.address_unit_bits != 8 %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(instance_name+template.name) }}__BYTE_ADR ({{ hex(rmap.byte_address(instance_start+template.adr)) }}U) // Register byte address.
+{%       endif %}
+{%       set security = block.get_security(template, instance_name) %}
+{%       if security.secure is not none %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(instance_name+template.name) }}__SECURE ({{ 1 if security.secure else 0 }}U) // 1 if only accessible from the secure state.
+{%       endif %}
+{%       if security.privileged is not none %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(instance_name+template.name) }}__PRIVILEGED ({{ 1 if security.privileged else 0 }}U) // 1 if only accessible from privileged code.
+{%       endif %}
+{%       for alias in block.get_alias_names(template, instance_name) %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(alias) }} {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(instance_name+template.name) }} // Deprecated, use {{ instance_name+template.name }}.
+{%       endfor %}
 {%     endfor -%}
+{%     if block.array_stride is not none %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__ADR(i) ({{ hex(block.instances.values()|min + template.adr) }}U + (i) * {{ hex(block.array_stride) }}U) // Address of array register i.
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__COUNT ({{ block.instances|length }}U) // Number of array registers.
+{%     endif -%}
+{%     if template.array is not none and template.array.index == 0 %}
+{%       for instance_name, instance_start in block.instances.items() %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(instance_name+template.array.name) }}__ADR(i) ({{ hex(instance_start+template.adr) }}U + (i) * {{ hex(template.array.stride) }}U) // Address of array register i.
+{%       endfor %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(block.name+template.array.name) }}__OFFSET(i) ({{ hex(template.adr) }}U + (i) * {{ hex(template.array.stride) }}U) // Offset of array register i from start of {{ block.name }} block.
+#define {{ c_macro(rmap.map_name) }}__REG_{{ c_macro(block.name+template.array.name) }}__COUNT ({{ template.array.count }}U) // Number of array registers.
+{%     endif -%}
 {%     if block.instances|length > 1 and block.register_templates|length > 1%}
 #define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__OFFSET ({{ hex(template.adr) }}U) // Offset of {{ block.name + template.name }} register from start of {{ block.name }} block.
 {%     endif -%}
-{%     if template.reset_val is not none %}
+{#     Values of registers wider than the widest C integer have no literal, only their address is given: #}
+{%     if template.bitwidth <= 64 %}
+{%       if template.reset_val is not none %}
 #define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__RESET ({{ hex(template.reset_val) }}U) // Reset value.
-{%     endif -%}
-{%     if template.always_write is not none %}
+{%       endif -%}
+{%       if template.has_recommended_val() %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__RECOMMENDED ({{ hex(template.get_recommended_val()) }}U) // Recommended value (reset value with field defaults).
+{%       endif -%}
+{%       if template.always_write is not none %}
 #define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__ALWAYS_WRITE_MASK ({{ hex(template.always_write.bits.get_bitmask()) }}U) // Always-write bit mask.
 #define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__ALWAYS_WRITE_VAL ({{ hex(template.always_write.value) }}U) //  Always-write value.
-{%     endif -%}
-{%     for field in template.fields.values() %}
+{%         if template.always_write.dont_care != 0 %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__ALWAYS_WRITE_DONT_CARE ({{ hex(template.always_write.dont_care) }}U) // Always-write bits that are undefined when read.
+{%         endif %}
+{%       endif -%}
+{%       for field in template.get_all_fields().values() %}
 #define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__FIELD_{{ c_macro(field.name) }} ({{ hex(field.bits.get_bitmask()) }}U) // Field mask{{ brief_doc(field.docs, ' "','"') }}.
-{%       if field.enum is not none %}
-{%         for entry in field.enum.entries.values() %}
+{%         for alias in field.aliases %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__FIELD_{{ c_macro(alias) }} {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__FIELD_{{ c_macro(field.name) }} // Deprecated, use {{ field.name }}.
+{%         endfor %}
+{%         if field.enum is not none %}
+{%           for entry in field.enum.entries.values() %}
 #define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__FIELD_{{ c_macro(field.name) }}__CONST_{{c_macro(entry.name)}} ({{ hex(entry.value) }}U) // Constant{{ brief_doc(entry.docs, ' "','"') }}.
+{%           endfor -%}
+{%         endif -%}
+{%       endfor -%}
+{%       for variant in template.variants.values() %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__VARIANT_{{ c_macro(variant.name) }} ({{ hex(variant.tag) }}U) // {{ template.variant_tag }} value that selects variant{{ brief_doc(variant.docs, ' "','"') }}.
+{%         for field in variant.fields.values() %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__VARIANT_{{ c_macro(variant.name) }}__FIELD_{{ c_macro(field.name) }} ({{ hex(field.bits.get_bitmask()) }}U) // Field mask{{ brief_doc(field.docs, ' "','"') }}.
+{%           if field.enum is not none %}
+{%             for entry in field.enum.entries.values() %}
+#define {{ c_macro(rmap.map_name) }}__REG_{{ generic_reg_name }}__VARIANT_{{ c_macro(variant.name) }}__FIELD_{{ c_macro(field.name) }}__CONST_{{c_macro(entry.name)}} ({{ hex(entry.value) }}U) // Constant{{ brief_doc(entry.docs, ' "','"') }}.
+{%             endfor -%}
+{%           endif -%}
 {%         endfor -%}
-{%       endif -%}
-{%     endfor -%}
+{%       endfor -%}
+{%     endif -%}
 {%   endfor -%}
 {%   if block.instances|length > 1 and block.register_templates|length > 1%}
 
@@ -0,0 +1,92 @@
+from enum import Enum
+from typing import Dict, List, Optional
+
+from pydantic import NonNegativeInt, PositiveInt
+from pydantic.dataclasses import dataclass
+
+# Byte transforms describe how a field is packed into (and unpacked from) the
+# individual bytes of a register value, for generators that work on byte
+# arrays instead of integers (e.g. for registers that are wider than the
+# widest integer type of the target language):
+#
+#   >>> mask_to_byte_transforms(0x0FF0)
+#   [ByteTransform(byte=0, mask=240, shift=4), ByteTransform(byte=1, mask=15, shift=-4)]
+#
+# A field value of 0xAB is packed into byte 0 as (0xAB << 4) & 0xF0 = 0xB0 and
+# into byte 1 as (0xAB >> 4) & 0x0F = 0x0A. Bytes are numbered in memory order
+# of the register value: For little-endian registers byte 0 holds bits 7-0, for
+# big-endian registers it holds the most significant byte, which requires the
+# width of the register in bytes.
+#
+# Both directions are checked against Bits.get_bitmask() and
+# Bits.extract_this_field_from() by scripts/check_byte_transforms.py.
+
+
+class ByteOrder(Enum):
+    LITTLE = 0
+    BIG = 1
+
+
+@dataclass
+class ByteTransform:
+    # Describes how the part of a field that is located in a single byte of the
+    # packed register is derived from the field value:
+    #
+    #   packed[byte] |= shift_left(value, shift) & mask
+    #   value |= shift_left(packed[byte] & mask, -shift)
+    #
+    # where 'value' is the field value (without the field's position applied).
+    byte: NonNegativeInt
+    mask: NonNegativeInt
+    shift: int
+
+    def pack(self, value: NonNegativeInt) -> NonNegativeInt:
+        return shift_left(value, self.shift) & self.mask
+
+    def unpack(self, byte_value: NonNegativeInt) -> NonNegativeInt:
+        return shift_left(byte_value & self.mask, -self.shift)
+
+
+def shift_left(val: NonNegativeInt, amt: int) -> NonNegativeInt:
+    if amt >= 0:
+        return val << amt
+    else:
+        return val >> (-amt)
+
+
+def mask_to_byte_masks(mask: NonNegativeInt) -> Dict[NonNegativeInt, NonNegativeInt]:
+    # Split a mask at byte boundaries. Returns the (byte-local) mask of every
+    # byte that contains at least one bit of the mask, keyed by byte index.
+    result = {}
+    byte = 0
+    while mask != 0:
+        if mask & 0xFF:
+            result[byte] = mask & 0xFF
+        mask >>= 8
+        byte += 1
+    return result
+
+
+def mask_to_byte_transforms(mask: NonNegativeInt, byte_order: Optional[ByteOrder] = None,
+                            bytewidth: Optional[PositiveInt] = None) -> List[ByteTransform]:
+    # Note: For big-endian registers, the total width of the register in bytes
+    # is required to number the bytes.
+    if byte_order is None:
+        byte_order = ByteOrder.LITTLE
+
+    if mask == 0:
+        return []
+
+    lsb = (mask & -mask).bit_length() - 1
+    transforms = [ByteTransform(byte=byte, mask=byte_mask, shift=lsb - 8 * byte)
+                  for byte, byte_mask in mask_to_byte_masks(mask).items()]
+
+    if byte_order == ByteOrder.BIG:
+        if bytewidth is None:
+            raise ValueError("Big-endian byte transforms require the register bytewidth")
+        if max(t.byte for t in transforms) >= bytewidth:
+            raise ValueError("Mask does not fit into register")
+        for t in transforms:
+            t.byte = bytewidth - 1 - t.byte
+
+    return transforms
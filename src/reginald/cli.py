@@ -1,29 +1,68 @@
 import argparse
 from dataclasses import dataclass
-from typing import List, Tuple
+from typing import Dict, List, Optional, Tuple
 
+import reginald.builtin_commands.convert
+import reginald.builtin_commands.doctor
+import reginald.builtin_commands.lint
+import reginald.builtin_commands.merge
+import reginald.builtin_commands.migrate
+import reginald.builtin_commands.replay
+import reginald.builtin_commands.schema
+import reginald.builtin_commands.verify
 import reginald.builtin_generators.c.funcpack
 import reginald.builtin_generators.c.macromap
 import reginald.builtin_generators.external_jinja2_template
+import reginald.builtin_generators.gdb.regdecode
+import reginald.builtin_generators.json.ir
+import reginald.builtin_generators.json.stats
+import reginald.builtin_generators.json.testvectors
+import reginald.builtin_generators.md.bitprovenance
 import reginald.builtin_generators.md.doc
+import reginald.builtin_generators.md.mapanalysis
 import reginald.builtin_generators.md.regdumpanalysis
+from reginald.command import CLICommand
 from reginald.error import ReginaldException
 from reginald.generator import OutputGenerator
+from reginald.input.load_listing import INPUT_FORMATS
 
 builtin_generators = {
     'c.macromap': reginald.builtin_generators.c.macromap.Generator(),
     'c.funcpack': reginald.builtin_generators.c.funcpack.Generator(),
     'md.regdumpanalysis': reginald.builtin_generators.md.regdumpanalysis.Generator(),
     'md.doc': reginald.builtin_generators.md.doc.Generator(),
+    'md.mapanalysis': reginald.builtin_generators.md.mapanalysis.Generator(),
+    'md.bitprovenance': reginald.builtin_generators.md.bitprovenance.Generator(),
+    'gdb.regdecode': reginald.builtin_generators.gdb.regdecode.Generator(),
+    'json.stats': reginald.builtin_generators.json.stats.Generator(),
+    'json.ir': reginald.builtin_generators.json.ir.Generator(),
+    'json.testvectors': reginald.builtin_generators.json.testvectors.Generator(),
     'jinja2': reginald.builtin_generators.external_jinja2_template.Generator()
 }
 
+builtin_commands = {
+    'convert': reginald.builtin_commands.convert.Command(),
+    'doctor': reginald.builtin_commands.doctor.Command(),
+    'lint': reginald.builtin_commands.lint.Command(),
+    'merge': reginald.builtin_commands.merge.Command(),
+    'migrate': reginald.builtin_commands.migrate.Command(),
+    'replay': reginald.builtin_commands.replay.Command(),
+    'schema': reginald.builtin_commands.schema.Command(),
+    'verify': reginald.builtin_commands.verify.Command(),
+}
+
 
 @dataclass
 class CLI:
     input_file: str
     output_file: str
     generator_args: List[str]
+    doc_lang: Optional[str]
+    usage_manifest: Optional[str]
+    input_format: Optional[str]
+    params: Dict[str, int]
+    overlays: List[str]
+    permissive: bool
 
 
 def parse_args() -> Tuple[CLI, OutputGenerator]:
@@ -34,14 +73,33 @@ def parse_args() -> Tuple[CLI, OutputGenerator]:
 
     builtin_choices_text = "\n".join(builtin_choices_text)
 
+    builtin_commands_text = []
+    for name, command in builtin_commands.items():
+        builtin_commands_text.append(f"   {name}: {command.description()}")
+
+    builtin_commands_text = "\n".join(builtin_commands_text)
+
     parser = argparse.ArgumentParser(prog="Reginald",
                                      description='Register map utility.\nPhilipp Schilk, 2022-2023',
-                                     epilog="builtin generators: \n" + builtin_choices_text,
+                                     epilog="builtin generators: \n" + builtin_choices_text +
+                                     "\n\nbuiltin commands (reginald <command> --help): \n" + builtin_commands_text,
                                      formatter_class=argparse.RawDescriptionHelpFormatter)
 
     parser.add_argument('--version', action='version', version='reginald ' + reginald.__version__)
+    parser.add_argument('--input-format', choices=list(INPUT_FORMATS.keys()),
+                        help="format of the input file. If not given, it is inferred from the file extension (default: yaml)")
+    parser.add_argument('--doc-lang',
+                        help="language to select from multi-language doc/brief strings")
+    parser.add_argument('--usage-manifest',
+                        help="yaml file listing the registers in use. Only these are passed to the generator")
+    parser.add_argument('--param', action='append', default=[], type=parse_param, metavar='NAME=VALUE',
+                        help="override a listing parameter. Can be given multiple times")
+    parser.add_argument('--overlay', action='append', default=[],
+                        help="yaml/json patch applied to the listing before generation. Can be given multiple times")
+    parser.add_argument('--permissive', action='store_true',
+                        help="warn about unknown keys in yaml/json listings instead of failing")
     parser.add_argument('input_file',
-                        help="input register description yaml")
+                        help="input register description (yaml listing, or other supported format)")
     parser.add_argument('output_file',
                         help=f"name of file to be generated")
     parser.add_argument('output_generator',
@@ -59,4 +117,26 @@ def parse_args() -> Tuple[CLI, OutputGenerator]:
 
     return CLI(input_file=args.input_file,
                output_file=args.output_file,
-               generator_args=args.generator_args), generator
+               generator_args=args.generator_args,
+               doc_lang=args.doc_lang,
+               usage_manifest=args.usage_manifest,
+               input_format=args.input_format,
+               params=dict(args.param),
+               overlays=args.overlay,
+               permissive=args.permissive), generator
+
+
+def parse_param(s: str) -> Tuple[str, int]:
+    name, sep, value = s.partition('=')
+    try:
+        if sep == "" or name == "":
+            raise ValueError()
+        return name, int(value, 0)
+    except ValueError:
+        raise argparse.ArgumentTypeError(f"invalid parameter '{s}', expected NAME=VALUE with an integer value")
+
+
+def parse_command(args: List[str]) -> Optional[Tuple[CLICommand, List[str]]]:
+    if len(args) > 0 and args[0] in builtin_commands:
+        return builtin_commands[args[0]], args[1:]
+    return None
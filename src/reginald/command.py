@@ -0,0 +1,12 @@
+from abc import ABC, abstractmethod
+from typing import List
+
+
+class CLICommand(ABC):
+    @abstractmethod
+    def run(self, args: List[str]):
+        raise NotImplementedError
+
+    @abstractmethod
+    def description(self) -> str:
+        raise NotImplementedError
@@ -1,17 +1,42 @@
 import functools
+import json
+import re
+import zlib
 from copy import deepcopy
 from enum import Enum
-from typing import Dict, List, Optional, Self
+from typing import Any, Callable, Dict, List, Literal, Optional, Self, Tuple
 
 from pydantic import BaseModel, NonNegativeInt, PositiveInt
 
-from reginald.bits import BitRange, Bits
+from reginald.bits import BitRange, Bits, byte_swap
 from reginald.utils import str_oneline
 
 
+# What reading a register/field does besides returning its value: Clear it,
+# pop an entry off a FIFO, or latch other values (e.g. the upper half of a
+# counter). Such registers must never be read speculatively or cached.
+ReadSideEffect = Literal['clear', 'pop', 'latch']
+
+# Cross-references in documentation: '[[REG]]' or '[[REG.FIELD]]', where REG is
+# a physical register or the generic name of a block register. Generators
+# render them with a function of (register, field or None):
+DOC_REF_RE = re.compile(r"\[\[([A-Za-z_][A-Za-z0-9_]*)(?:\.([A-Za-z_][A-Za-z0-9_]*))?\]\]")
+DocRefRenderer = Callable[[str, Optional[str]], str]
+
+
+def plain_doc_ref(register: str, field: Optional[str]) -> str:
+    return register if field is None else f"{register}.{field}"
+
+
 class AccessMode(Enum):
     READ = 0
     WRITE = 1
+    # Writing a 1 clears/sets/toggles a bit, writing a 0 has no effect:
+    WRITE_1_CLEAR = 2
+    WRITE_1_SET = 3
+    WRITE_1_TOGGLE = 4
+    # Reading returns the value and clears the field:
+    READ_CLEAR = 5
 
     def to_str(self) -> str:
         match self:
@@ -19,9 +44,34 @@ class AccessMode(Enum):
                 return "r"
             case AccessMode.WRITE:
                 return "w"
+            case AccessMode.WRITE_1_CLEAR:
+                return "w1c"
+            case AccessMode.WRITE_1_SET:
+                return "w1s"
+            case AccessMode.WRITE_1_TOGGLE:
+                return "w1t"
+            case AccessMode.READ_CLEAR:
+                return "rc"
             case _:
                 raise ValueError()
 
+    @staticmethod
+    def from_str(s: str) -> Optional['AccessMode']:
+        for mode in AccessMode:
+            if mode.to_str() == s.lower():
+                return mode
+        return None
+
+    def is_read(self) -> bool:
+        return self in [AccessMode.READ, AccessMode.READ_CLEAR]
+
+    def is_write(self) -> bool:
+        return not self.is_read()
+
+    def has_side_effect(self) -> bool:
+        # The field does not simply hold what was last written:
+        return self not in [AccessMode.READ, AccessMode.WRITE]
+
 
 class Docs(BaseModel):
     brief: Optional[str]
@@ -47,11 +97,24 @@ class Docs(BaseModel):
     def empty(self) -> bool:
         return (self.brief is None) and (self.doc is None)
 
+    def refs(self) -> List[Tuple[str, Optional[str]]]:
+        return [(match.group(1), match.group(2))
+                for text in [self.brief, self.doc] if text is not None
+                for match in DOC_REF_RE.finditer(text)]
+
+    def render_refs(self, render: DocRefRenderer) -> 'Docs':
+        def sub(text: Optional[str]) -> Optional[str]:
+            if text is None:
+                return None
+            return DOC_REF_RE.sub(lambda match: render(match.group(1), match.group(2)), text)
+        return Docs(brief=sub(self.brief), doc=sub(self.doc))
+
 
 class RegEnumEntry(BaseModel):
     name: str
     value: NonNegativeInt
     docs: Docs
+    catch_all: bool = False
 
 
 class RegEnum(BaseModel):
@@ -59,11 +122,45 @@ class RegEnum(BaseModel):
     is_shared: bool
     docs: Docs
     entries: Dict[str, RegEnumEntry]
+    # Inclusive (first, last) value ranges that are reserved:
+    reserved: List[Tuple[NonNegativeInt, NonNegativeInt]] = []
+
+    def get_catch_all(self) -> Optional[RegEnumEntry]:
+        # Entry that stands for all values without an entry of their own:
+        return next((entry for entry in self.entries.values() if entry.catch_all), None)
+
+    def is_reserved(self, val: NonNegativeInt) -> bool:
+        return any(first <= val <= last for first, last in self.reserved)
+
+    def reserved_str(self) -> str:
+        return ", ".join(f"0x{first:X}" if first == last else f"0x{first:X}-0x{last:X}" for first, last in self.reserved)
 
 
 class AlwaysWrite(BaseModel):
     bits: Bits
     value: NonNegativeInt
+    # Bits (of 'bits') that are undefined when read, and must not be checked:
+    dont_care: NonNegativeInt = 0
+
+    def get_check_mask(self) -> NonNegativeInt:
+        return self.bits.get_bitmask() & ~self.dont_care
+
+
+class FixedPoint(BaseModel):
+    # Physical value = raw value * scale + offset, where the raw value is
+    # interpreted as two's complement if signed.
+    scale: float
+    offset: float
+    signed: bool
+
+
+class FieldArray(BaseModel):
+    # Field arrays ('count' + 'stride') are expanded into one field per
+    # element. Element i starts i * stride bits after element 0.
+    name: str
+    index: NonNegativeInt
+    count: PositiveInt
+    stride: PositiveInt
 
 
 class Field(BaseModel):
@@ -72,6 +169,31 @@ class Field(BaseModel):
     access: List[AccessMode]
     docs: Docs
     enum: Optional[RegEnum] = None
+    # Views expose bits of other fields under a different name/type, and are
+    # not part of the register layout:
+    view: bool = False
+    fixed_point: Optional[FixedPoint] = None
+    # IEEE-754 binary16/binary32 value:
+    float_format: Optional[Literal['f16', 'f32']] = None
+    # Physical unit of the (fixed-point/float converted) value, e.g. 'mV':
+    unit: Optional[str] = None
+    # Valid range of the raw field value (inclusive):
+    min: Optional[NonNegativeInt] = None
+    max: Optional[NonNegativeInt] = None
+    array: Optional[FieldArray] = None
+    # Calibration/test fields that are not part of the public driver API:
+    internal: bool = False
+    read_side_effect: Optional[ReadSideEffect] = None
+    # Trigger bit, cleared by hardware after a 1 is written:
+    self_clearing: bool = False
+    # Updated by hardware, so it must always be re-read instead of cached:
+    volatile: bool = False
+    # Byte-swapped ('big') fields store their most significant byte in their lowest bits:
+    byte_order: Literal['little', 'big'] = 'little'
+    # Former names, still generated but deprecated:
+    aliases: List[str] = []
+    # Recommended raw value, if it differs from the reset value:
+    default: Optional[NonNegativeInt] = None
 
     def get_bitrange(self) -> BitRange:
         return self.bits.get_bitrange()
@@ -79,10 +201,44 @@ class Field(BaseModel):
     def get_bitranges(self) -> List[BitRange]:
         return self.bits.get_bitranges()
 
+    def extract_from(self, reg_val: NonNegativeInt) -> NonNegativeInt:
+        # Field value from a packed register value:
+        val = self.bits.extract_this_field_from(reg_val)
+        if self.byte_order == 'big':
+            return byte_swap(val, self.bits.total_width() // 8)
+        return val
+
+    def insert_into(self, reg_val: NonNegativeInt, val: NonNegativeInt) -> NonNegativeInt:
+        # Packed register value with this field replaced by 'val':
+        if self.byte_order == 'big':
+            val = byte_swap(val, self.bits.total_width() // 8)
+        mask = self.bits.get_bitmask()
+        return (reg_val & ~mask) | ((val << self.bits.lsb_position()) & mask)
+
     def access_str(self) -> str:
         modes = [mode.to_str() for mode in self.access]
         return "/".join(modes)
 
+    def is_readable(self) -> bool:
+        return any(mode.is_read() for mode in self.access)
+
+    def is_writable(self) -> bool:
+        return any(mode.is_write() for mode in self.access)
+
+    def has_side_effect(self) -> bool:
+        return self.self_clearing or any(mode.has_side_effect() for mode in self.access)
+
+    def has_range(self) -> bool:
+        return self.min is not None or self.max is not None
+
+    def is_in_range(self, val: NonNegativeInt) -> bool:
+        return (self.min is None or val >= self.min) and (self.max is None or val <= self.max)
+
+    def range_str(self) -> str:
+        min_val = self.min if self.min is not None else 0
+        max_val = self.max if self.max is not None else self.bits.get_unpositioned_bits().get_bitmask()
+        return f"{min_val} to {max_val}"
+
     def lookup_enum_entry_name(self, val: NonNegativeInt) -> Optional[str]:
         if self.enum is None:
             return None
@@ -94,6 +250,60 @@ class Field(BaseModel):
         return None
 
 
+class RegisterExample(BaseModel):
+    val: NonNegativeInt
+    fields: Dict[str, NonNegativeInt]
+    docs: Docs
+
+
+class RegisterArray(BaseModel):
+    # Arrays of block registers ('count' + 'stride') are expanded into one
+    # template per element. Element i is at the offset of element 0 + i * stride.
+    name: str
+    index: NonNegativeInt
+    count: PositiveInt
+    stride: PositiveInt
+
+
+class UnlockSequence(BaseModel):
+    # Keys that have to be written to the (physical) key register, in order,
+    # before the register accepts a write.
+    key_register: str
+    keys: List[NonNegativeInt]
+
+
+class SecurityAttributes(BaseModel):
+    # TrustZone security state and privilege level required for access, None
+    # if not specified:
+    secure: Optional[bool] = None
+    privileged: Optional[bool] = None
+
+    def override(self, other: 'SecurityAttributes') -> 'SecurityAttributes':
+        # Attributes of 'other' take precedence, where specified:
+        return SecurityAttributes(secure=other.secure if other.secure is not None else self.secure,
+                                  privileged=other.privileged if other.privileged is not None else self.privileged)
+
+    def empty(self) -> bool:
+        return self.secure is None and self.privileged is None
+
+    def to_str(self) -> str:
+        attrs = []
+        if self.secure is not None:
+            attrs.append("secure" if self.secure else "non-secure")
+        if self.privileged is not None:
+            attrs.append("privileged" if self.privileged else "unprivileged")
+        return ", ".join(attrs)
+
+
+class RegisterVariant(BaseModel):
+    # Alternative layout of the bits outside of a register's fields, selected
+    # by the value 'tag' of the register's tag field:
+    name: str
+    tag: NonNegativeInt
+    fields: Dict[str, Field]
+    docs: Docs
+
+
 class Register(BaseModel):
     name: str
     fields: Dict[str, Field]
@@ -103,12 +313,114 @@ class Register(BaseModel):
     always_write: Optional[AlwaysWrite]
     reset_val: Optional[NonNegativeInt]
     docs: Docs
+    examples: List[RegisterExample] = []
+    read_side_effect: Optional[ReadSideEffect] = None
+    # All bits are updated by hardware (fields can also be volatile on their own):
+    volatile: bool = False
+    unlock: Optional[UnlockSequence] = None
+    array: Optional[RegisterArray] = None
+    # Former names, still generated but deprecated. Complete register names
+    # for single registers, template names for registers of a block:
+    aliases: List[str] = []
+    security: SecurityAttributes = SecurityAttributes()
+    # Clock and power domain, the register is inaccessible while they are off:
+    clock_domain: Optional[str] = None
+    power_domain: Optional[str] = None
+    # Registers with separate read and write layouts keep the read layout in
+    # 'fields', and the write layout here:
+    write_fields: Optional[Dict[str, Field]] = None
+    # Registers with variants hold alternative layouts of the bits outside of
+    # 'fields', selected by the value of the 'variant_tag' field:
+    variant_tag: Optional[str] = None
+    variants: Dict[str, RegisterVariant] = {}
+    # Set on the views returned by get_layout_views():
+    layout_view: Optional[Literal['read', 'write']] = None
+    variant: Optional[str] = None
+
+    def get_layout_views(self) -> List['Register']:
+        # The register itself (followed by a view of each of its variants), or
+        # its read and write view (each with only the fields of that layout):
+        if self.write_fields is None:
+            return [self] + [self.get_variant_view(name) for name in self.variants]
+        return [self.model_copy(update={'write_fields': None, 'layout_view': 'read'}),
+                self.model_copy(update={'fields': self.write_fields, 'write_fields': None, 'layout_view': 'write',
+                                        'reset_val': None, 'examples': []})]
+
+    def get_variant_view(self, name: str) -> 'Register':
+        # The register with the fields of one of its variants. The tag field
+        # becomes always_write bits, holding the tag of the variant:
+        assert self.variant_tag is not None
+        variant = self.variants[name]
+        tag_field = self.fields[self.variant_tag]
+        tag_mask = tag_field.bits.get_bitmask()
+        tag_val = variant.tag << tag_field.bits.lsb_position()
+        if self.always_write is not None:
+            always_write = AlwaysWrite(bits=Bits.from_mask(self.always_write.bits.get_bitmask() | tag_mask),
+                                       value=self.always_write.value | tag_val, dont_care=self.always_write.dont_care)
+        else:
+            always_write = AlwaysWrite(bits=Bits.from_mask(tag_mask), value=tag_val, dont_care=0)
+        fields = {n: f for n, f in self.fields.items() if n != self.variant_tag} | variant.fields
+        docs = variant.docs if not variant.docs.empty() else self.docs
+        return self.model_copy(update={'fields': fields, 'always_write': always_write, 'variant': name, 'docs': docs,
+                                       'examples': []})
+
+    def lookup_variant(self, reg_val: NonNegativeInt) -> Optional[RegisterVariant]:
+        # The variant selected by the tag in a packed register value, if any:
+        if self.variant_tag is None:
+            return None
+        tag = self.fields[self.variant_tag].extract_from(reg_val)
+        return next((variant for variant in self.variants.values() if variant.tag == tag), None)
+
+    def get_all_fields(self) -> Dict[str, Field]:
+        # Fields of both layouts of registers with separate read and write layouts:
+        return self.fields | (self.write_fields or {})
+
+    def get_write_layout_fields(self) -> Dict[str, Field]:
+        # Fields that make up a written value:
+        if self.write_fields is None:
+            return self.get_layout_fields()
+        return {name: field for name, field in self.write_fields.items() if not field.view}
+
+    def get_read_side_effects(self) -> List[ReadSideEffect]:
+        # Of the register itself and all its fields (read-clears fields clear):
+        effects = [self.read_side_effect]  # type: List[Optional[ReadSideEffect]]
+        for field in self.get_layout_fields().values():
+            effects.append(field.read_side_effect)
+            if AccessMode.READ_CLEAR in field.access:
+                effects.append('clear')
+        return list(dict.fromkeys(effect for effect in effects if effect is not None))
+
+    def get_volatile_mask(self) -> NonNegativeInt:
+        # Bits that may change without being written, and cannot be cached:
+        if self.volatile:
+            return (1 << self.bitwidth) - 1
+        mask = 0
+        for field in self.get_layout_fields().values():
+            if field.volatile:
+                mask |= field.bits.get_bitmask()
+        return mask
+
+    def is_volatile(self) -> bool:
+        return self.get_volatile_mask() != 0
+
+    def has_recommended_val(self) -> bool:
+        return any(field.default is not None for field in self.get_write_layout_fields().values())
+
+    def get_recommended_val(self) -> NonNegativeInt:
+        # Reset value (0 if unknown, or if it is the one of a separate read layout) with all field defaults applied:
+        val = self.reset_val if self.reset_val is not None and self.write_fields is None else 0
+        for field in self.get_write_layout_fields().values():
+            if field.default is not None:
+                val = field.insert_into(val, field.default)
+        if self.always_write is not None:
+            val = (val & ~self.always_write.bits.get_bitmask()) | self.always_write.value
+        return val
 
     def get_unused_bits(self, include_always_write: bool) -> Bits:
 
         bits = list(range(self.bitwidth))
 
-        for field in self.fields.values():
+        for field in self.get_layout_fields().values():
             for bit in field.bits.bitlist:
                 bits.remove(bit)
 
@@ -119,8 +431,15 @@ class Register(BaseModel):
 
         return Bits(bitlist=bits)
 
+    def get_unused_bitranges(self) -> List[BitRange]:
+        # Ranges of the bits outside of all fields, where a range is either
+        # completely always write bits or none at all:
+        unused = self.get_unused_bits(include_always_write=True).bitlist
+        always_write = Bits(bitlist=[bit for bit in unused if self.is_bit_always_write(bit)])
+        return self.get_unused_bits(include_always_write=False).get_bitranges() + always_write.get_bitranges()
+
     def get_fieldname_at(self, bit: NonNegativeInt) -> Optional[str]:
-        for field in self.fields.values():
+        for field in self.get_layout_fields().values():
             if bit in field.bits.bitlist:
                 return field.name
         return None
@@ -152,8 +471,35 @@ class Register(BaseModel):
         reg.name = name
         return reg
 
+    def is_writable(self) -> bool:
+        fields = self.fields if self.write_fields is None else self.write_fields
+        return any(field.is_writable() for field in fields.values())
+
+    def is_readable(self) -> bool:
+        return any(field.is_readable() for field in self.fields.values())
+
+    def get_access_mask(self, mode: AccessMode) -> int:
+        mask = 0
+        variant_fields = [field for variant in self.variants.values() for field in variant.fields.values() if not field.view]
+        for field in list((self.get_layout_fields() | self.get_write_layout_fields()).values()) + variant_fields:
+            if mode in field.access:
+                mask |= field.bits.get_bitmask()
+        return mask
+
+    def get_layout_fields(self) -> Dict[str, Field]:
+        return {name: field for name, field in self.fields.items() if not field.view}
+
+    def get_view_fields(self) -> Dict[str, Field]:
+        return {name: field for name, field in self.fields.items() if field.view}
+
     def get_local_enums(self) -> List[RegEnum]:
-        return [field.enum for field in self.fields.values() if isinstance(field.enum, RegEnum) and not field.enum.is_shared]
+        # Elements of a field array share their enum:
+        enums = {}  # type: Dict[str, RegEnum]
+        variant_fields = [field for variant in self.variants.values() for field in variant.fields.values()]
+        for field in list(self.get_all_fields().values()) + variant_fields:
+            if isinstance(field.enum, RegEnum) and not field.enum.is_shared:
+                enums.setdefault(field.enum.name, field.enum)
+        return list(enums.values())
 
 
 class RegisterBlock(BaseModel):
@@ -161,13 +507,70 @@ class RegisterBlock(BaseModel):
     instances: Dict[str, NonNegativeInt]
     docs: Docs
     register_templates: Dict[str, Register]
+    # Register arrays ('count' + 'stride'): Instance i is at the address of
+    # the first instance + i * stride.
+    array_stride: Optional[PositiveInt] = None
+    # Functional group (e.g. 'PMU', 'ADC') for organizing large maps:
+    group: Optional[str] = None
+    # Security of all instances, and of single instances:
+    security: SecurityAttributes = SecurityAttributes()
+    instance_security: Dict[str, SecurityAttributes] = {}
+
+    def get_security(self, template: Register, instance_name: str) -> SecurityAttributes:
+        # Of one physical register, the register overrides its instance, which overrides the block:
+        instance = self.instance_security.get(instance_name, SecurityAttributes())
+        return self.security.override(instance).override(template.security)
+
+    def get_alias_names(self, template: Register, instance_name: str) -> List[str]:
+        # Former names of one physical register:
+        if template.name == "":
+            return template.aliases
+        return [instance_name + alias for alias in template.aliases]
+
+
+class RegisterTransaction(BaseModel):
+    # Registers that have to be written together, in the given order.
+    name: str
+    registers: List[str]
+    docs: Docs
+
+
+class SplitValuePart(BaseModel):
+    reg_name: str
+    # None for a part that spans the complete register:
+    field_name: Optional[str] = None
+
+    def to_str(self) -> str:
+        return self.reg_name if self.field_name is None else f"{self.reg_name}.{self.field_name}"
+
+
+class SplitValue(BaseModel):
+    # A value split across fields of several registers (e.g. a 16-bit
+    # reading in a high and a low byte register). Parts are given most
+    # significant first.
+    name: str
+    parts: List[SplitValuePart]
+    docs: Docs
+
+
+class Constant(BaseModel):
+    # A value that belongs with the register map, but is not a register
+    # (device IDs, unlock values, command opcodes...).
+    name: str
+    value: NonNegativeInt
+    docs: Docs
 
 
 class RegisterMap(BaseModel):
     map_name: str
     docs: Docs
+    # Bits per address, all addresses are in these units:
+    address_unit_bits: PositiveInt = 8
     register_blocks: Dict[str, RegisterBlock]
     enums: Dict[str, RegEnum]
+    transactions: Dict[str, RegisterTransaction] = {}
+    split_values: Dict[str, SplitValue] = {}
+    constants: Dict[str, Constant] = {}
 
     @functools.cached_property
     def physical_registers(self) -> Dict[NonNegativeInt, Register]:
@@ -180,6 +583,108 @@ class RegisterMap(BaseModel):
 
         return regs
 
+    @functools.cached_property
+    def groups(self) -> List[str]:
+        # All register groups, in order of their lowest address:
+        first_adr = {}  # type: Dict[str, NonNegativeInt]
+        for block in self.register_blocks.values():
+            adrs = [instance_adr + template.adr for instance_adr in block.instances.values()
+                    for template in block.register_templates.values()]
+            if block.group is None or len(adrs) == 0:
+                continue
+            adr = min(adrs)
+            first_adr[block.group] = min(adr, first_adr.get(block.group, adr))
+        return sorted(first_adr.keys(), key=lambda group: first_adr[group])
+
+    def byte_address(self, adr: NonNegativeInt) -> NonNegativeInt:
+        return adr * self.address_unit_bits // 8
+
+    def get_domains(self, kind: str) -> List[str]:
+        # All 'clock' or 'power' domains, in order of their lowest register address:
+        domains = [getattr(reg, f"{kind}_domain") for _, reg in sorted(self.physical_registers.items())]
+        return list(dict.fromkeys(domain for domain in domains if domain is not None))
+
+    def template_by_name(self, register_name: str) -> Optional[Tuple[RegisterBlock, Register]]:
+        # Block and template of a physical register, or of the generic name of a block register:
+        for block in self.register_blocks.values():
+            for template in block.register_templates.values():
+                names = [block.name + template.name] + [instance_name + template.name for instance_name in block.instances.keys()]
+                if register_name in names:
+                    return block, template
+        return None
+
+    def documented(self) -> List[Tuple[str, Any]]:
+        # Everything that carries documentation (has 'docs'), with a breadcrumb:
+        result = [("map", self)]  # type: List[Tuple[str, Any]]
+
+        def add_enum(enum: RegEnum, bt: str):
+            result.append((bt, enum))
+            result.extend((bt + f" -> {entry.name}", entry) for entry in enum.entries.values())
+
+        for enum in self.enums.values():
+            add_enum(enum, f"enums -> {enum.name}")
+        for block in self.register_blocks.values():
+            bt = f"registers -> {block.name}"
+            result.append((bt, block))
+            for template in block.register_templates.values():
+                tbt = bt + f" -> {template.name}" if template.name != "" else bt
+                result.append((tbt, template))
+                for field in template.get_all_fields().values():
+                    result.append((tbt + f" -> {field.name}", field))
+                    if field.enum is not None:
+                        add_enum(field.enum, tbt + f" -> {field.name} -> enum")
+                for variant in template.variants.values():
+                    vbt = tbt + f" -> variants -> {variant.name}"
+                    result.append((vbt, variant))
+                    for field in variant.fields.values():
+                        result.append((vbt + f" -> {field.name}", field))
+                        if field.enum is not None:
+                            add_enum(field.enum, vbt + f" -> {field.name} -> enum")
+                result.extend((tbt + f" -> examples -> {idx}", example) for idx, example in enumerate(template.examples))
+        result.extend((f"transactions -> {t.name}", t) for t in self.transactions.values())
+        result.extend((f"split_values -> {v.name}", v) for v in self.split_values.values())
+        result.extend((f"constants -> {c.name}", c) for c in self.constants.values())
+        return result
+
+    def with_rendered_doc_refs(self, render: DocRefRenderer) -> 'RegisterMap':
+        # Copy of the map with all documentation cross-references replaced:
+        _ = self.fingerprint
+        rmap = deepcopy(self)
+        for _, thing in rmap.documented():
+            thing.docs = thing.docs.render_refs(render)
+        rmap.__dict__.pop('physical_registers', None)
+        return rmap
+
+    def register_by_name(self, register_name: str) -> Optional[Register]:
+        return next((reg for reg in self.physical_registers.values() if reg.name == register_name), None)
+
+    def transactions_of(self, register_name: str) -> List[RegisterTransaction]:
+        return [t for t in self.transactions.values() if register_name in t.registers]
+
+    def split_values_of(self, register_name: str) -> List[SplitValue]:
+        return [s for s in self.split_values.values() if any(p.reg_name == register_name for p in s.parts)]
+
+    def split_value_layout(self, split_value: SplitValue) -> List[Tuple[Register, Optional[Field], Bits, NonNegativeInt]]:
+        # Register, field (None for complete registers), bits within the
+        # register, and lsb position within the combined value of every part
+        # (most significant first). Expects a validated map.
+        regs = {reg.name: reg for reg in self.physical_registers.values()}
+        parts = []
+        for part in split_value.parts:
+            reg = regs[part.reg_name]
+            if part.field_name is None:
+                parts.append((reg, None, Bits.from_position(0, reg.bitwidth)))
+            else:
+                field = reg.fields[part.field_name]
+                parts.append((reg, field, field.bits))
+
+        layout = []
+        lsb = sum(bits.total_width() for _, _, bits in parts)
+        for reg, field, bits in parts:
+            lsb -= bits.total_width()
+            layout.append((reg, field, bits, lsb))
+        return layout
+
     @functools.cached_property
     def addresses(self) -> List[NonNegativeInt]:
         return list(self.physical_registers.keys())
@@ -188,6 +693,40 @@ class RegisterMap(BaseModel):
     def max_address(self) -> NonNegativeInt:
         return max(self.addresses)
 
+    @functools.cached_property
+    def fingerprint(self) -> NonNegativeInt:
+        # Stable 32-bit hash of everything that defines the binary layout of
+        # the map (addresses, widths, fields, enum values...), but not of its
+        # documentation.
+        def field_entry(field: Field) -> List[Any]:
+            enum = None
+            if field.enum is not None:
+                enum = {entry.name: entry.value for entry in field.enum.entries.values()}
+            return ([field.name, sorted(field.bits.bitlist), field.access_str(), enum] + (['view'] if field.view else []) +
+                    (['big'] if field.byte_order == 'big' else []))
+
+        regs = []
+        for adr, reg in sorted(self.physical_registers.items()):
+            fields = [field_entry(field) for field in reg.fields.values()]
+
+            always_write = None
+            if reg.always_write is not None:
+                always_write = [reg.always_write.bits.get_bitmask(), reg.always_write.value]
+
+            write_fields = []
+            if reg.write_fields is not None:
+                write_fields = [[field_entry(field) for field in reg.write_fields.values()]]
+
+            variants = []
+            if reg.variant_tag is not None:
+                variants = [[reg.variant_tag, [[variant.name, variant.tag, [field_entry(field) for field in variant.fields.values()]]
+                                               for variant in reg.variants.values()]]]
+
+            regs.append([adr, reg.name, reg.bitwidth, reg.reset_val, always_write, fields] + write_fields + variants)
+
+        address_unit = [self.address_unit_bits] if self.address_unit_bits != 8 else []
+        return zlib.crc32(json.dumps([self.map_name, regs] + address_unit).encode())
+
     @functools.cached_property
     def max_register_bitwidth(self) -> NonNegativeInt:
         return max([reg.bitwidth for block in self.register_blocks.values() for reg in block.register_templates.values()])
@@ -0,0 +1,106 @@
+import struct
+from dataclasses import dataclass, field
+from math import ceil
+from typing import List, Literal, Optional
+
+from reginald.datamodel import Field, Register, RegisterMap
+from reginald.error import ReginaldException
+
+# Decoding of packed register values into a structured tree, for host-side
+# tools that embed decoding instead of running the command line tool:
+#
+#   import reginald
+#   rmap = reginald.load("chip.yaml")
+#   for reg in reginald.decode_bytes(rmap, dump, adr=0x10):
+#       print(reg.name, [(f.name, f.enum_entry or f.raw) for f in reg.fields])
+#
+# Decoding never fails on unexpected values: Values without an enum entry or
+# outside of a field's range are reported through 'valid'.
+
+
+@dataclass
+class DecodedField:
+    name: str
+    raw: int
+    # Name of the matching enum entry, if the field is an enum:
+    enum_entry: Optional[str] = None
+    # Fixed-point or float value, in 'unit':
+    physical: Optional[float] = None
+    unit: Optional[str] = None
+    # False if the value has no enum entry or is outside of the valid range:
+    valid: bool = True
+
+
+@dataclass
+class DecodedRegister:
+    name: str
+    adr: int
+    val: int
+    fields: List[DecodedField] = field(default_factory=list)
+    # Variant selected by the tag field (fields then include the ones of the
+    # variant), None for registers without variants or an unknown tag:
+    variant: Optional[str] = None
+    # Whether the always write bits hold their required value (None if the
+    # register has none):
+    always_write_ok: Optional[bool] = None
+
+
+def decode_field(f: Field, reg_val: int) -> DecodedField:
+    raw = f.extract_from(reg_val)
+    result = DecodedField(name=f.name, raw=raw, unit=f.unit)
+
+    if f.enum is not None:
+        result.enum_entry = f.lookup_enum_entry_name(raw)
+        if result.enum_entry is None:
+            catch_all = f.enum.get_catch_all()
+            result.enum_entry = catch_all.name if catch_all is not None else None
+        result.valid = result.enum_entry is not None
+
+    if f.fixed_point is not None:
+        width = f.bits.total_width()
+        signed_raw = raw - (1 << width) if f.fixed_point.signed and raw >> (width - 1) else raw
+        result.physical = signed_raw * f.fixed_point.scale + f.fixed_point.offset
+    elif f.float_format is not None:
+        fmt = {'f16': '<e', 'f32': '<f'}[f.float_format]
+        result.physical = struct.unpack(fmt, raw.to_bytes(struct.calcsize(fmt), 'little'))[0]
+
+    if not f.is_in_range(raw):
+        result.valid = False
+
+    return result
+
+
+def decode_register(reg: Register, val: int) -> DecodedRegister:
+    result = DecodedRegister(name=reg.name, adr=reg.adr, val=val)
+    result.fields = [decode_field(f, val) for f in reg.fields.values()]
+
+    variant = reg.lookup_variant(val)
+    if variant is not None:
+        result.variant = variant.name
+        result.fields.extend(decode_field(f, val) for f in variant.fields.values())
+
+    if reg.always_write is not None:
+        check_mask = reg.always_write.get_check_mask()
+        result.always_write_ok = (val & check_mask) == (reg.always_write.value & check_mask)
+
+    return result
+
+
+def decode_bytes(rmap: RegisterMap, data: bytes, adr: int = 0,
+                 byte_order: Literal['little', 'big'] = 'little') -> List[DecodedRegister]:
+    # Decodes all registers that lie completely inside 'data', which starts at
+    # (register map) address 'adr'. Ordered by address.
+    if byte_order not in ['little', 'big']:
+        raise ReginaldException(f"Unknown byte order '{byte_order}'!")
+
+    start = rmap.byte_address(adr)
+    result = []
+    for reg_adr, reg in sorted(rmap.physical_registers.items()):
+        offset = rmap.byte_address(reg_adr) - start
+        size = ceil(reg.bitwidth / 8)
+        if offset < 0 or offset + size > len(data):
+            continue
+        val = int.from_bytes(data[offset:offset + size], byte_order)
+        result.append(decode_register(reg, val))
+
+    return result
@@ -1,2 +1,13 @@
+import warnings
+
+
 class ReginaldException(Exception):
     pass
+
+
+class ReginaldWarning(UserWarning):
+    pass
+
+
+def warn(msg: str):
+    warnings.warn(msg, ReginaldWarning, stacklevel=2)
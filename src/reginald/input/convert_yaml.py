@@ -1,49 +1,86 @@
+import math
 import re
-from typing import Dict, List, Optional
+from typing import Dict, List, Optional, Tuple, Union
 
-from pydantic import PositiveInt
+from pydantic import NonNegativeInt, PositiveInt
 
 from reginald.bits import Bits
-from reginald.datamodel import (AccessMode, AlwaysWrite, Docs, Field, RegEnum,
-                                RegEnumEntry, Register, RegisterBlock,
-                                RegisterMap)
+from reginald.datamodel import (AccessMode, AlwaysWrite, Constant, Docs, Field,
+                                FieldArray, FixedPoint, RegEnum, RegEnumEntry,
+                                Register, RegisterArray, RegisterBlock,
+                                RegisterExample, RegisterMap,
+                                RegisterTransaction, RegisterVariant,
+                                SecurityAttributes,
+                                SplitValue, SplitValuePart, UnlockSequence)
 from reginald.error import ReginaldException
 from reginald.input.parse_yaml import (YAML_Access, YAML_AlwaysWrite,
-                                       YAML_Bits, YAML_Field,
-                                       YAML_RegEnumEntry, YAML_Register,
-                                       YAML_RegisterBlock, YAML_RegisterMap)
+                                       YAML_Bits, YAML_Doc, YAML_Field,
+                                       YAML_FixedPoint, YAML_InstanceArray,
+                                       YAML_Layout, YAML_RegEnumEntry,
+                                       YAML_Register, YAML_RegisterBlock,
+                                       YAML_RegisterExample, YAML_RegisterMap,
+                                       YAML_RegisterVariant, YAML_Security,
+                                       YAML_Unlock)
+from reginald.input.validate_map import MAX_REGISTER_BITWIDTH
+
+
+ARRAY_PLACEHOLDER = "{n}"
 
 
 class YAMLConverter:
-    def __init__(self, yaml: YAML_RegisterMap):
+    def __init__(self, yaml: YAML_RegisterMap, doc_lang: Optional[str] = None):
         self.yaml = yaml
+        self.doc_lang = doc_lang
 
     def convert(self) -> RegisterMap:
         bt = f"{self.yaml.map_name}"
         self.rmap = RegisterMap(
             map_name=self.yaml.map_name,
             docs=self._convert_docs(self.yaml, bt),
+            address_unit_bits=self.yaml.address_unit_bits,
             enums={},
             register_blocks={})
 
         # Order is critical: register conversion requires enums to be converted.
         self.rmap.enums = self._convert_enums(bt)
         self.rmap.register_blocks = self._convert_registers(bt)
+        self.rmap.transactions = self._convert_transactions(bt)
+        self.rmap.split_values = self._convert_split_values(bt)
+        self.rmap.constants = self._convert_constants(bt)
 
         return self.rmap
 
     def _convert_docs(self, thing, bt: str) -> Docs:
-        if thing.brief is not None:
-            if len(thing.brief.strip()) == 0:
+        brief = self._select_doc_lang(thing.brief, bt + " -> brief")
+        doc = self._select_doc_lang(thing.doc, bt + " -> doc")
+
+        if brief is not None:
+            if len(brief.strip()) == 0:
                 raise ReginaldException(f"{bt} -> brief: brief does not contain text!")
-            if len(thing.brief.splitlines()) > 1:
+            if len(brief.splitlines()) > 1:
                 raise ReginaldException(f"{bt} -> brief: brief may not contain more than one line!")
 
-        if thing.doc is not None:
-            if len(thing.doc.strip()) == 0:
+        if doc is not None:
+            if len(doc.strip()) == 0:
                 raise ReginaldException(f"{bt} -> doc: doc does not contain text!")
 
-        return Docs(brief=thing.brief, doc=thing.doc)
+        return Docs(brief=brief, doc=doc)
+
+    def _select_doc_lang(self, doc: Optional[YAML_Doc], bt: str) -> Optional[str]:
+        if doc is None or isinstance(doc, str):
+            return doc
+
+        if len(doc) == 0:
+            raise ReginaldException(f"{bt}: No language given!")
+
+        if self.doc_lang is None:
+            # No language selected: Use the first one listed.
+            return next(iter(doc.values()))
+
+        if self.doc_lang not in doc:
+            raise ReginaldException(f"{bt}: No documentation in language '{self.doc_lang}'!")
+
+        return doc[self.doc_lang]
 
     def _convert_bitwidth(self, bitwidth: Optional[PositiveInt], bt: str) -> PositiveInt:
         bt = bt + f" -> bitwidth"
@@ -67,6 +104,7 @@ class YAMLConverter:
 
             result[enum_name] = RegEnum(
                 name=enum_name,
+                reserved=self._convert_enum_reserved(enum.reserved, bt),
                 is_shared=True,
                 docs=docs,
                 entries=entries)
@@ -76,7 +114,27 @@ class YAMLConverter:
     def _convert_enum_entry(self, entry_name: str, entry: YAML_RegEnumEntry, bt: str) -> RegEnumEntry:
         bt = bt + f" -> {entry_name}"
         docs = self._convert_docs(entry, bt)
-        return RegEnumEntry(name=entry_name, value=entry.val, docs=docs)
+        return RegEnumEntry(name=entry_name, value=entry.val, docs=docs, catch_all=entry.catch_all)
+
+    def _convert_enum_reserved(self, reserved: List[Union[NonNegativeInt, str]], bt: str) -> List[Tuple[NonNegativeInt, NonNegativeInt]]:
+        bt = bt + " -> reserved"
+        result = []
+        for entry in reserved:
+            if isinstance(entry, int):
+                result.append((entry, entry))
+                continue
+            match = re.fullmatch(r"\s*(\w+)\s*(?:-\s*(\w+)\s*)?", entry)
+            try:
+                if match is None:
+                    raise ValueError()
+                first = int(match.group(1), 0)
+                last = int(match.group(2), 0) if match.group(2) is not None else first
+            except ValueError:
+                raise ReginaldException(f"{bt}: Invalid range '{entry}', expected a value or FIRST-LAST!")
+            if first < 0 or last < first:
+                raise ReginaldException(f"{bt}: Invalid range '{entry}'!")
+            result.append((first, last))
+        return result
 
     def _convert_always_write(self, always_write: Optional[YAML_AlwaysWrite], bt: str) -> Optional[AlwaysWrite]:
         bt = bt + f" -> always_write"
@@ -86,8 +144,33 @@ class YAMLConverter:
 
         bits = Bits.from_mask(always_write.mask)
         value = always_write.val
-
-        return AlwaysWrite(bits=bits, value=value)
+        if always_write.dont_care & ~always_write.mask != 0:
+            raise ReginaldException(f"{bt}: dont_care (0x{always_write.dont_care:X}) has to be a subset of mask!")
+
+        return AlwaysWrite(bits=bits, value=value, dont_care=always_write.dont_care)
+
+    def _fill_reserved(self, r: YAML_Register, bitwidth: int, fields: Dict[str, Field], variants: Dict[str, RegisterVariant],
+                       always_write: Optional[AlwaysWrite]) -> Optional[AlwaysWrite]:
+        # Reserved bits (not part of any field or always_write) become always_write
+        # bits with the fill value, so that packing writes them as the datasheet requires:
+        fill = r.reserved_fill if r.reserved_fill is not None else self.yaml.reserved_fill
+        if fill is None or bitwidth > MAX_REGISTER_BITWIDTH:
+            return always_write  # Too wide registers are left for validation to report.
+
+        used = set(always_write.bits.bitlist if always_write is not None else [])
+        variant_fields = [field for variant in variants.values() for field in variant.fields.values()]
+        for field in list(fields.values()) + variant_fields:
+            if not field.view:
+                used.update(field.bits.bitlist)
+        reserved = [bit for bit in range(bitwidth) if bit not in used]
+        if len(reserved) == 0:
+            return always_write
+
+        reserved_mask = Bits.from_bitlist(reserved).get_bitmask()
+        mask = reserved_mask | (always_write.bits.get_bitmask() if always_write is not None else 0)
+        value = (always_write.value if always_write is not None else 0) | (reserved_mask if fill == 1 else 0)
+        dont_care = always_write.dont_care if always_write is not None else 0
+        return AlwaysWrite(bits=Bits.from_mask(mask), value=value, dont_care=dont_care)
 
     def _convert_bits(self, bits: YAML_Bits, bt: str, allow_zero: bool) -> Bits:
         bt = bt + f" -> bits"
@@ -125,6 +208,88 @@ class YAMLConverter:
 
         return Bits(bitlist=bitlist)
 
+    def _convert_layouts(self, r: YAML_Register, bt: str, access: List[AccessMode], siblings: Dict[str, YAML_Register],
+                         stack: List[str]) -> Tuple[Dict[str, Field], Optional[Dict[str, Field]]]:
+        # Fields of a register, or the fields of its read and write layout:
+        if r.layout_read is None and r.layout_write is None:
+            return self._convert_register_fields(r, bt, access, siblings, stack), None
+        if r.layout_read is None or r.layout_write is None:
+            raise ReginaldException(f"{bt}: Separate layouts need both a layout_read and a layout_write!")
+        if len(r.fields) > 0 or r.extends is not None:
+            raise ReginaldException(f"{bt}: Registers with separate read and write layouts cannot have fields or extend another register!")
+
+        views = []
+        for key, layout, default_access in [('layout_read', r.layout_read, [AccessMode.READ]),
+                                            ('layout_write', r.layout_write, [AccessMode.WRITE])]:
+            # Resolved like 'extends', fields without access get the one of their view:
+            fields = self._convert_register_fields(YAML_Layout(extends=layout), bt + f" -> {key}", access, siblings, stack)
+            views.append({name: field if len(field.access) > 0 else field.model_copy(update={'access': default_access})
+                          for name, field in fields.items()})
+        return views[0], views[1]
+
+    def _convert_variants(self, r: YAML_Register, fields: Dict[str, Field], bt: str, access: List[AccessMode],
+                          siblings: Dict[str, YAML_Register], stack: List[str]) -> Dict[str, RegisterVariant]:
+        if r.variant_tag is None:
+            if len(r.variants) > 0:
+                raise ReginaldException(f"{bt}: Registers with variants need a variant_tag!")
+            return {}
+        if len(r.variants) == 0:
+            raise ReginaldException(f"{bt}: Register has a variant_tag, but no variants!")
+        if r.layout_read is not None:
+            raise ReginaldException(f"{bt}: Registers with separate read and write layouts cannot have variants!")
+        if r.variant_tag not in fields:
+            raise ReginaldException(f"{bt} -> variant_tag: Register has no field {r.variant_tag}!")
+        tag_field = fields[r.variant_tag]
+
+        result = {}
+        for name, v in r.variants.items():
+            vbt = bt + f" -> variants -> {name}"
+            if isinstance(v.tag, str):
+                # Enum entry name:
+                if tag_field.enum is None or v.tag not in tag_field.enum.entries:
+                    raise ReginaldException(f"{vbt} -> tag: Field {tag_field.name} has no enum entry {v.tag}!")
+                tag = tag_field.enum.entries[v.tag].value
+            else:
+                tag = v.tag
+            variant_access = self._convert_access(v.access, vbt) if v.access is not None else access
+            variant_fields = self._convert_register_fields(v, vbt, variant_access, siblings, stack)
+            result[name] = RegisterVariant(name=name, tag=tag, fields=variant_fields, docs=self._convert_docs(v, vbt))
+        return result
+
+    def _convert_register_fields(self, r: Union[YAML_Register, YAML_Layout, YAML_RegisterVariant], bt: str, access: List[AccessMode],
+                                 siblings: Dict[str, YAML_Register], stack: List[str]) -> Dict[str, Field]:
+        # Fields of a register, including the ones inherited through 'extends':
+        fields = self._convert_fields(r.fields, bt, access)
+        if r.extends is None:
+            return fields
+
+        bt = bt + f" -> extends"
+        base_name = r.extends
+        if base_name in stack:
+            raise ReginaldException(f"{bt}: Cyclic extension: {' -> '.join(stack + [base_name])}")
+        if base_name in siblings:
+            base = siblings[base_name]
+        elif isinstance(self.yaml.registers.get(base_name), YAML_Register):
+            base = self.yaml.registers[base_name]
+            siblings = {}
+        elif base_name in self.yaml.layouts:
+            base = self.yaml.layouts[base_name]
+            siblings = {}
+        else:
+            raise ReginaldException(f"{bt}: Unknown register {base_name}!")
+
+        base_access = self._convert_access(base.access, bt)
+        base_fields = self._convert_register_fields(base, bt + f" -> {base_name}", base_access, siblings, stack + [base_name])
+
+        # Overridden fields are replaced, all others must not share bits with the extension:
+        result = {name: field for name, field in base_fields.items() if name not in fields}
+        for field in fields.values():
+            for base_field in result.values():
+                if not field.view and not base_field.view and set(field.bits.bitlist) & set(base_field.bits.bitlist):
+                    raise ReginaldException(f"{bt}: Field {field.name} overlaps with field {base_field.name} inherited from {base_name}!")
+        result.update(fields)
+        return result
+
     def _convert_fields(self, fields: Dict[str, YAML_Field], bt_orig: str, default_access: List[AccessMode]) -> Dict[str, Field]:
         result = {}
 
@@ -136,14 +301,125 @@ class YAMLConverter:
             if len(access) == 0:
                 access = default_access
             docs = self._convert_docs(field, bt)
-            enum = self._convert_field_enum(field_name, field, bt)
+            fixed_point = self._convert_fixed_point(field.fixed_point, bt)
+
+            if field.count is None and field.stride is None:
+                enum = self._convert_field_enum(field_name, field, bt)
+                result[field_name] = Field(
+                    name=field_name,
+                    bits=bits,
+                    docs=docs,
+                    access=access,
+                    enum=enum,
+                    view=field.view,
+                    fixed_point=fixed_point,
+                    float_format=field.float_format,
+                    unit=field.unit,
+                    min=field.min,
+                    max=field.max,
+                    internal=field.internal,
+                    read_side_effect=field.read_side_effect,
+                    self_clearing=field.self_clearing,
+                    volatile=field.volatile,
+                    byte_order=field.byte_order,
+                    aliases=field.aliases,
+                    default=self._convert_field_default(field.default, enum, bt))
+                continue
+
+            # Field array: 'MODE{n}' with count 8 becomes fields 'MODE0' to
+            # 'MODE7'. Without a '{n}' placeholder, the index is appended.
+            if field.count is None or field.stride is None:
+                raise ReginaldException(f"{bt}: Field arrays need both a count and a stride!")
+            if len(bits.get_bitranges()) != 1:
+                raise ReginaldException(f"{bt}: Bits of the first element of a field array have to be contiguous!")
+            if field.view:
+                raise ReginaldException(f"{bt}: Views cannot be field arrays!")
+            if len(field.aliases) > 0:
+                raise ReginaldException(f"{bt}: Field arrays cannot have aliases!")
+            if field.byte_order != 'little':
+                raise ReginaldException(f"{bt}: Field arrays cannot be byte-swapped!")
+
+            array_name = field_name.replace(ARRAY_PLACEHOLDER, "")
+            enum = self._convert_field_enum(array_name, field, bt)
+            default = self._convert_field_default(field.default, enum, bt)
+            for i in range(field.count):
+                if ARRAY_PLACEHOLDER in field_name:
+                    element_name = field_name.replace(ARRAY_PLACEHOLDER, str(i))
+                else:
+                    element_name = f"{field_name}{i}"
+                if element_name in fields or element_name in result:
+                    raise ReginaldException(f"{bt}: Array element {element_name} collides with another field!")
+                result[element_name] = Field(
+                    name=element_name,
+                    bits=bits.bitwise_lshift(i * field.stride),
+                    docs=docs,
+                    access=access,
+                    enum=enum,
+                    fixed_point=fixed_point,
+                    float_format=field.float_format,
+                    unit=field.unit,
+                    min=field.min,
+                    max=field.max,
+                    internal=field.internal,
+                    read_side_effect=field.read_side_effect,
+                    self_clearing=field.self_clearing,
+                    volatile=field.volatile,
+                    default=default,
+                    array=FieldArray(name=array_name, index=i, count=field.count, stride=field.stride))
 
-            result[field_name] = Field(
-                name=field_name,
-                bits=bits,
-                docs=docs,
-                access=access,
-                enum=enum)
+        return result
+
+    def _convert_field_default(self, default: Optional[Union[bool, int, str]], enum: Optional[RegEnum], bt: str) -> Optional[int]:
+        if default is None:
+            return None
+        if isinstance(default, str):
+            # Enum entry name:
+            if enum is None or default not in enum.entries:
+                raise ReginaldException(f"{bt} -> default: Field has no enum entry {default}!")
+            return enum.entries[default].value
+        return int(default)
+
+    def _convert_fixed_point(self, fixed_point: Optional[YAML_FixedPoint], bt: str) -> Optional[FixedPoint]:
+        bt = bt + f" -> fixed_point"
+
+        if fixed_point is None:
+            return None
+
+        if (fixed_point.frac_bits is None) == (fixed_point.scale is None):
+            raise ReginaldException(f"{bt}: Specify exactly one of frac_bits or scale!")
+
+        if fixed_point.frac_bits is not None:
+            scale = 2.0 ** -fixed_point.frac_bits
+        else:
+            scale = fixed_point.scale
+
+        if scale == 0 or not math.isfinite(scale) or not math.isfinite(fixed_point.offset):
+            raise ReginaldException(f"{bt}: Scale has to be non-zero, and scale and offset finite!")
+
+        return FixedPoint(scale=scale, offset=fixed_point.offset, signed=fixed_point.signed)
+
+    def _convert_examples(self, examples: List[YAML_RegisterExample], fields: Dict[str, Field], bt_orig: str) -> List[RegisterExample]:
+        result = []
+
+        for idx, example in enumerate(examples):
+            bt = bt_orig + f" -> examples -> {idx}"
+            docs = self._convert_docs(example, bt)
+
+            field_vals = {}
+            for field_name, val in example.fields.items():
+                if field_name not in fields:
+                    raise ReginaldException(f"{bt}: Register has no field {field_name}!")
+                field = fields[field_name]
+
+                if isinstance(val, str):
+                    # Enum entry name:
+                    if field.enum is None or val not in field.enum.entries:
+                        raise ReginaldException(f"{bt} -> {field_name}: Field has no enum entry {val}!")
+                    field_vals[field_name] = field.enum.entries[val].value
+                else:
+                    field_vals[field_name] = int(val)
+
+            result.append(RegisterExample(val=example.val, fields=field_vals, docs=docs))
 
         return result
 
@@ -178,18 +454,49 @@ class YAMLConverter:
         result = []
 
         for access_mode in access:
-            match access_mode.lower():
-                case "r":
-                    result.append(AccessMode.READ)
-                case "w":
-                    result.append(AccessMode.WRITE)
-                case _:
-                    raise ReginaldException(f"{bt}: Unknown access mode {access_mode}.")
+            mode = AccessMode.from_str(access_mode)
+            if mode is None:
+                raise ReginaldException(f"{bt}: Unknown access mode {access_mode}.")
+            result.append(mode)
+
+        if len([mode for mode in result if mode.is_read()]) > 1 or len([mode for mode in result if mode.is_write()]) > 1:
+            raise ReginaldException(f"{bt}: At most one read and one write access mode can be given.")
 
         return result
 
+    def _convert_transactions(self, bt_orig: str) -> Dict[str, RegisterTransaction]:
+        transactions = {}
+        for name, t in self.yaml.transactions.items():
+            bt = bt_orig + f" -> transactions -> {name}"
+            transactions[name] = RegisterTransaction(name=name, registers=t.registers, docs=self._convert_docs(t, bt))
+        return transactions
+
+    def _convert_split_values(self, bt_orig: str) -> Dict[str, SplitValue]:
+        split_values = {}
+        for name, v in self.yaml.split_values.items():
+            bt = bt_orig + f" -> split_values -> {name}"
+            parts = []
+            for part in v.parts:
+                if part.count(".") > 1 or part.startswith(".") or part.endswith("."):
+                    raise ReginaldException(f"{bt}: Invalid part '{part}', expected REGISTER or REGISTER.FIELD!")
+                register, _, field = part.partition(".")
+                parts.append(SplitValuePart(reg_name=register, field_name=field if field != "" else None))
+            split_values[name] = SplitValue(name=name, parts=parts, docs=self._convert_docs(v, bt))
+        return split_values
+
+    def _convert_constants(self, bt_orig: str) -> Dict[str, Constant]:
+        constants = {}
+        for name, c in self.yaml.constants.items():
+            bt = bt_orig + f" -> constants -> {name}"
+            constants[name] = Constant(name=name, value=c.val, docs=self._convert_docs(c, bt))
+        return constants
+
     def _convert_registers(self, bt: str) -> Dict[str, RegisterBlock]:
         result = {}
+        for name in self.yaml.layouts:
+            if name in self.yaml.registers:
+                raise ReginaldException(f"{bt} -> layouts -> {name}: A register with the same name exists!")
+
         bt = bt + f" -> registers"
         for name, r in self.yaml.registers.items():
             if isinstance(r, YAML_Register):
@@ -199,19 +506,30 @@ class YAMLConverter:
         return result
 
     def _convert_register(self, name: str, r: YAML_Register, bt: str) -> RegisterBlock:
-        bt = bt + f" -> name"
+        bt = bt + f" -> {name}"
         adr = r.adr
         bitwidth = self._convert_bitwidth(r.bitwidth, bt)
         docs = self._convert_docs(r, bt)
         reset_val = r.reset_val
         always_write = self._convert_always_write(r.always_write, bt)
         access = self._convert_access(r.access, bt)
-        fields = self._convert_fields(r.fields, bt, access)
+        fields, write_fields = self._convert_layouts(r, bt, access, {}, [name])
+        variants = self._convert_variants(r, fields, bt, access, {}, [name])
+        always_write = self._fill_reserved(r, bitwidth, write_fields if write_fields is not None else fields, variants, always_write)
+        examples = self._convert_examples(r.examples, fields, bt)
+
+        instances = {name: adr}
+        if r.count is not None or r.stride is not None:
+            if len(r.aliases) > 0:
+                raise ReginaldException(f"{bt}: Register arrays cannot have aliases!")
+            name, instances = self._convert_array(name, r, bt)
 
         return RegisterBlock(
             name=name,
             docs=docs,
-            instances={name: adr},
+            instances=instances,
+            array_stride=r.stride,
+            group=r.group,
             register_templates={"": Register(
                 name="",
                 fields=fields,
@@ -220,10 +538,36 @@ class YAMLConverter:
                 always_write=always_write,
                 reset_val=reset_val,
                 docs=docs,
-                is_block_template=True
+                is_block_template=True,
+                examples=examples,
+                read_side_effect=r.read_side_effect,
+                volatile=r.volatile,
+                unlock=self._convert_unlock(r.unlock),
+                aliases=r.aliases,
+                security=self._convert_security(r.security),
+                clock_domain=r.clock_domain,
+                power_domain=r.power_domain,
+                write_fields=write_fields,
+                variant_tag=r.variant_tag,
+                variants=variants,
             )}
         )
 
+    def _convert_unlock(self, u: Optional[YAML_Unlock]) -> Optional[UnlockSequence]:
+        if u is None:
+            return None
+        return UnlockSequence(key_register=u.key_register, keys=u.keys)
+
+    def _convert_security(self, s: Optional[YAML_Security]) -> SecurityAttributes:
+        if s is None:
+            return SecurityAttributes()
+        return SecurityAttributes(secure=s.secure, privileged=s.privileged)
+
+    def _convert_array(self, name: str, r: YAML_Register, bt: str) -> Tuple[str, Dict[str, NonNegativeInt]]:
+        if r.count is None or r.stride is None:
+            raise ReginaldException(f"{bt}: Register arrays need both a count and a stride!")
+        return array_instances(name, r.adr, r.count, r.stride)
+
     def _convert_register_block(self, name: str, b: YAML_RegisterBlock, bt_orig: str) -> RegisterBlock:
         bt_orig = bt_orig + f" -> {name}"
         docs = self._convert_docs(b, bt_orig)
@@ -232,15 +576,21 @@ class YAMLConverter:
 
         for reg_name, r in b.registers.items():
             bt = bt_orig + f"-> {reg_name}"
+            if r.group is not None:
+                raise ReginaldException(f"{bt}: Registers inside a block cannot have a group, set it on the block instead!")
             adr = r.adr
             bitwidth = self._convert_bitwidth(r.bitwidth, bt)
             docs = self._convert_docs(r, bt)
             reset_val = r.reset_val
             always_write = self._convert_always_write(r.always_write, bt)
             access = self._convert_access(r.access, bt)
-            fields = self._convert_fields(r.fields, bt, access)
+            fields, write_fields = self._convert_layouts(r, bt, access, b.registers, [reg_name])
+            variants = self._convert_variants(r, fields, bt, access, b.registers, [reg_name])
+            always_write = self._fill_reserved(r, bitwidth, write_fields if write_fields is not None else fields, variants,
+                                               always_write)
+            examples = self._convert_examples(r.examples, fields, bt)
 
-            registers[reg_name] = Register(
+            template = Register(
                 name=reg_name,
                 fields=fields,
                 bitwidth=bitwidth,
@@ -248,11 +598,71 @@ class YAMLConverter:
                 adr=adr,
                 always_write=always_write,
                 reset_val=reset_val, docs=docs,
+                examples=examples,
+                read_side_effect=r.read_side_effect,
+                volatile=r.volatile,
+                unlock=self._convert_unlock(r.unlock),
+                aliases=r.aliases,
+                security=self._convert_security(r.security),
+                clock_domain=r.clock_domain if r.clock_domain is not None else b.clock_domain,
+                power_domain=r.power_domain if r.power_domain is not None else b.power_domain,
+                write_fields=write_fields,
+                variant_tag=r.variant_tag,
+                variants=variants,
             )
 
+            if r.count is None and r.stride is None:
+                registers[reg_name] = template
+                continue
+
+            # Array of block registers: 'STAT{n}' with count 8 becomes templates
+            # 'STAT0' to 'STAT7'. Without a '{n}' placeholder, the index is appended.
+            if r.count is None or r.stride is None:
+                raise ReginaldException(f"{bt}: Register arrays need both a count and a stride!")
+            if len(r.aliases) > 0:
+                raise ReginaldException(f"{bt}: Register arrays cannot have aliases!")
+            array_name = reg_name.replace(ARRAY_PLACEHOLDER, "n")
+            for i in range(r.count):
+                if ARRAY_PLACEHOLDER in reg_name:
+                    element_name = reg_name.replace(ARRAY_PLACEHOLDER, str(i))
+                else:
+                    element_name = f"{reg_name}{i}"
+                if element_name in registers or element_name in b.registers:
+                    raise ReginaldException(f"{bt}: Array element {element_name} collides with another register!")
+                registers[element_name] = template.model_copy(deep=True, update={
+                    'name': element_name,
+                    'adr': adr + i * r.stride,
+                    'array': RegisterArray(name=array_name, index=i, count=r.count, stride=r.stride),
+                })
+
+        instances = b.instances
+        array_stride = None
+        if isinstance(b.instances, YAML_InstanceArray):
+            name, instances = array_instances(name, b.instances.start, b.instances.count, b.instances.stride)
+            array_stride = b.instances.stride
+
+        for instance_name in b.instance_security:
+            if instance_name not in instances:
+                raise ReginaldException(f"{bt_orig} -> instance_security: Unknown instance {instance_name}!")
+
         return RegisterBlock(
             name=name,
             docs=docs,
-            instances=b.instances,
-            register_templates=registers
+            instances=instances,
+            array_stride=array_stride,
+            register_templates=registers,
+            group=b.group,
+            security=self._convert_security(b.security),
+            instance_security={inst: self._convert_security(s) for inst, s in b.instance_security.items()},
         )
+
+
+def array_instances(name: str, start: NonNegativeInt, count: PositiveInt, stride: PositiveInt) -> Tuple[str, Dict[str, NonNegativeInt]]:
+    # Register (or block) array: 'CH{n}_CFG' with count 16 becomes block
+    # 'CHn_CFG' with instances 'CH0_CFG' to 'CH15_CFG'. Without a '{n}'
+    # placeholder, the index is appended to the name.
+    if ARRAY_PLACEHOLDER in name:
+        instances = {name.replace(ARRAY_PLACEHOLDER, str(i)): start + i * stride for i in range(count)}
+        return name.replace(ARRAY_PLACEHOLDER, "n"), instances
+    else:
+        return name, {f"{name}{i}": start + i * stride for i in range(count)}
@@ -0,0 +1,42 @@
+import json
+from os import path
+from typing import Any, Dict, Optional
+
+import yaml
+
+from reginald.error import ReginaldException
+from reginald.input.migrate_yaml import SCHEMA_VERSION
+from reginald.input.parse_yaml import YAML_RegisterMap
+
+# Supported listing output formats: Name -> File extensions
+OUTPUT_FORMATS = {
+    'yaml': ['.yaml', '.yml'],
+    'json': ['.json'],
+}
+
+
+def listing_data(listing: YAML_RegisterMap) -> Dict[str, Any]:
+    return {'schema': SCHEMA_VERSION} | listing.model_dump(exclude_none=True, exclude_defaults=True)
+
+
+def write_listing(listing: YAML_RegisterMap, file_name: str, output_format: Optional[str] = None) -> str:
+    if output_format is None:
+        output_format = guess_output_format(file_name)
+
+    data = listing_data(listing)
+    with open(file_name, 'w') as outfile:
+        if output_format == 'json':
+            json.dump(data, outfile, indent=2, ensure_ascii=False)
+            outfile.write("\n")
+        else:
+            yaml.safe_dump(data, outfile, sort_keys=False, allow_unicode=True)
+
+    return output_format
+
+
+def guess_output_format(file_name: str) -> str:
+    ext = path.splitext(file_name)[1].lower()
+    for name, extensions in OUTPUT_FORMATS.items():
+        if ext in extensions:
+            return name
+    raise ReginaldException(f"Cannot infer output format from '{file_name}', use --output-format")
@@ -0,0 +1,134 @@
+from typing import Any, Dict, List, Tuple, Union
+
+import yaml
+from pydantic import ValidationError
+
+from reginald.error import warn
+
+# Validation errors of a listing are reported with the position in the source
+# file and the path inside the listing:
+#
+#   dummy.yaml:6:5: registers -> A -> rset_val: Extra inputs are not permitted
+#
+# In permissive mode, unknown keys are dropped (with a warning) instead.
+#
+# Pydantic reports errors for every alternative of a union. For register map
+# entries, only the errors of the alternative the entry was meant to be (a
+# block if it has 'instances', a register otherwise) are shown.
+
+KeyPath = Tuple[Union[str, int], ...]
+
+BLOCK_MODEL = 'YAML_RegisterBlock'
+REGISTER_MODEL = 'YAML_Register'
+
+
+def yaml_key_marks(file_name: str) -> Dict[KeyPath, Tuple[int, int]]:
+    # Line and column (both 1-based) of every mapping key and sequence item in a
+    # YAML file. Empty if the file cannot be read.
+    try:
+        with open(file_name) as f:
+            root = yaml.compose(f, Loader=yaml.SafeLoader)
+    except (OSError, yaml.YAMLError):
+        return {}
+
+    marks = {}  # type: Dict[KeyPath, Tuple[int, int]]
+
+    def walk(node: Any, key_path: KeyPath):
+        if isinstance(node, yaml.MappingNode):
+            for key, value in node.value:
+                if isinstance(key, yaml.ScalarNode):
+                    marks[key_path + (str(key.value),)] = (key.start_mark.line + 1, key.start_mark.column + 1)
+                    walk(value, key_path + (str(key.value),))
+        elif isinstance(node, yaml.SequenceNode):
+            for idx, item in enumerate(node.value):
+                marks[key_path + (idx,)] = (item.start_mark.line + 1, item.start_mark.column + 1)
+                walk(item, key_path + (idx,))
+
+    walk(root, ())
+    return marks
+
+
+def drop_unknown_keys(error: ValidationError, data: Any, file_name: str,
+                      marks: Dict[KeyPath, Tuple[int, int]]) -> bool:
+    # Removes all unknown keys reported in a validation error from the listing
+    # data. Returns True if any key was removed.
+    dropped = False
+    for err in error.errors():
+        if err['type'] != 'extra_forbidden':
+            continue
+        key_path = data_path(err['loc'], data)
+        if key_path is None or len(key_path) == 0:
+            continue
+
+        parent = data
+        for key in key_path[:-1]:
+            parent = parent[key]
+        if isinstance(parent, dict) and key_path[-1] in parent:
+            del parent[key_path[-1]]
+            warn(f"{file_name}:{position(key_path, marks)} {' -> '.join(str(k) for k in key_path)}: Unknown key, ignored.")
+            dropped = True
+
+    return dropped
+
+
+def format_validation_error(error: ValidationError, data: Any, file_name: str,
+                            marks: Dict[KeyPath, Tuple[int, int]]) -> str:
+    messages = []  # type: List[str]
+    for err in error.errors():
+        key_path = data_path(err['loc'], data)
+        if key_path is None:
+            continue
+        if err['type'] == 'missing':
+            key_path = key_path + (err['loc'][-1],)
+
+        breadcrumb = " -> ".join(str(k) for k in key_path)
+        msg = f"{file_name}:{position(key_path, marks)} {breadcrumb + ': ' if breadcrumb else ''}{err['msg']}"
+        if msg not in messages:
+            messages.append(msg)
+
+    if len(messages) == 0:
+        return f"{file_name}: {error}"
+    return "\n".join(messages)
+
+
+def format_yaml_error(error: yaml.YAMLError, file_name: str) -> str:
+    # Syntax errors are reported at the position of the problem, in the file it
+    # was found in (e.g. an included listing):
+    mark = getattr(error, 'problem_mark', None)
+    problem = getattr(error, 'problem', None) or str(error)
+    if mark is None:
+        return f"{file_name}: Invalid YAML: {problem}"
+    if not mark.name.startswith('<'):
+        file_name = mark.name
+    return f"{file_name}:{mark.line + 1}:{mark.column + 1}: Invalid YAML: {problem}"
+
+
+def position(key_path: KeyPath, marks: Dict[KeyPath, Tuple[int, int]]) -> str:
+    # Position of the innermost key of the path that exists in the file:
+    for length in reversed(range(len(key_path) + 1)):
+        if key_path[:length] in marks:
+            line, col = marks[key_path[:length]]
+            return f"{line}:{col}:"
+    return ""
+
+
+def data_path(loc: Tuple[Union[str, int], ...], data: Any) -> Union[KeyPath, None]:
+    # Converts a pydantic error location into a path into the listing, leaving
+    # out the union alternatives. Returns None if the error belongs to a union
+    # alternative that does not match the entry.
+    key_path = []  # type: List[Union[str, int]]
+    node = data
+    for element in loc:
+        if isinstance(node, dict) and element in (BLOCK_MODEL, REGISTER_MODEL) and tuple(key_path[:1]) == ('registers',):
+            is_block = 'instances' in node
+            if (element == BLOCK_MODEL) != is_block:
+                return None
+            continue
+        if isinstance(node, dict) and element in node:
+            node = node[element]
+            key_path.append(element)
+        elif isinstance(node, list) and isinstance(element, int) and element < len(node):
+            node = node[element]
+            key_path.append(element)
+        # Anything else names a union alternative/type, or a missing key.
+    return tuple(key_path)
@@ -0,0 +1,177 @@
+import json
+from copy import deepcopy
+from os import path
+from typing import Any, Dict, List, Tuple
+
+import yaml
+from yaml.loader import SafeLoader
+
+from reginald.error import ReginaldException
+from reginald.input.errors_yaml import format_yaml_error
+from reginald.input.migrate_yaml import migrate_listing
+
+# Listings can include other listings (paths relative to the including file):
+#
+#   includes: [common/enums.yaml, blocks/uart.yaml]
+#
+# The registers, layouts, enums, transactions, split values, and constants of
+# every included listing are merged into the including listing. All other keys
+# of an included listing are ignored, except for its 'default_register_bitwidth',
+# which is applied to its own registers, and its 'uses'. Defining the same name twice is an
+# error, as are include cycles.
+#
+# Listings can also use shared enums and layouts of library listings (paths
+# relative to the using file), e.g. fields common to all chips of a vendor:
+#
+#   uses: [vendor/common.yaml]
+#   registers:
+#     CTRL:
+#       adr: 0x10
+#       extends: SENSOR_CTRL              # A layout of the library.
+#       fields:
+#         ODR: {bits: [6-4], enum: ODR}   # A shared enum of the library.
+#
+# Unlike includes, a library only provides definitions: Its enums and layouts
+# are taken over as far as the listing (or a layout taken over) references
+# them, everything else of a library is ignored. Definitions of the listing
+# take precedence over the ones of its libraries. Libraries can include and
+# use other libraries.
+
+MERGED_KEYS = ['registers', 'layouts', 'enums', 'transactions', 'split_values', 'constants']
+LIBRARY_KEYS = ['enums', 'layouts']
+
+
+def load_listing_data(file_name: str) -> Any:
+    try:
+        with open(file_name) as f:
+            if path.splitext(file_name)[1].lower() == '.json':
+                return json.load(f)
+            return yaml.load(f, Loader=SafeLoader)
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except OSError as e:
+        raise ReginaldException(f"{file_name}: Cannot read file: {e.strerror}")
+    except UnicodeDecodeError:
+        raise ReginaldException(f"{file_name}: Not a UTF-8 text file!")
+    except json.JSONDecodeError as e:
+        raise ReginaldException(f"{file_name}: Invalid JSON: {e}")
+    except yaml.YAMLError as e:
+        raise ReginaldException(format_yaml_error(e, file_name))
+
+
+def resolve_includes(data: Dict[str, Any], file_name: str) -> Dict[str, Any]:
+    # Expects a migrated listing, returns it with all includes merged and all
+    # used library definitions taken over.
+    return _resolve_includes_and_uses(data, file_name, [path.abspath(file_name)])
+
+
+def _resolve_includes_and_uses(data: Dict[str, Any], file_name: str, uses_stack: List[str]) -> Dict[str, Any]:
+    origins = {}  # type: Dict[Tuple[str, str], str]
+    data = _resolve(data, file_name, [path.abspath(file_name)], origins)
+    return _resolve_uses(data, file_name, uses_stack)
+
+
+def _resolve(data: Dict[str, Any], file_name: str, stack: List[str], origins: Dict[Tuple[str, str], str]) -> Dict[str, Any]:
+    includes = data.get('includes', [])
+    if not isinstance(includes, list) or not all(isinstance(i, str) for i in includes):
+        raise ReginaldException(f"{file_name}: 'includes' has to be a list of file names!")
+
+    uses = data.get('uses', [])
+    if not isinstance(uses, list) or not all(isinstance(u, str) for u in uses):
+        raise ReginaldException(f"{file_name}: 'uses' has to be a list of file names!")
+
+    data = {key: val for key, val in data.items() if key not in ['includes', 'uses']}
+    data['uses'] = [path.join(path.dirname(file_name), u) for u in uses]
+    for key in MERGED_KEYS:
+        if key in data and not isinstance(data[key], dict):
+            raise ReginaldException(f"{file_name}: '{key}' has to be a map!")
+        data[key] = dict(data.get(key) or {})
+        for name in data[key]:
+            _claim(origins, key, name, file_name)
+
+    for include in includes:
+        include_file = path.join(path.dirname(file_name), include)
+        include_abs = path.abspath(include_file)
+        if include_abs in stack:
+            cycle = " -> ".join(path.relpath(f) for f in stack[stack.index(include_abs):] + [include_abs])
+            raise ReginaldException(f"{file_name}: Include cycle: {cycle}")
+
+        included = migrate_listing(load_listing_data(include_file))
+        included = _resolve(included, include_file, stack + [include_abs], origins)
+        apply_default_bitwidth(included, include_file)
+
+        for key in MERGED_KEYS:
+            data[key].update(included[key])
+        data['uses'].extend(included['uses'])
+
+    return data
+
+
+def _resolve_uses(data: Dict[str, Any], file_name: str, stack: List[str]) -> Dict[str, Any]:
+    library = {key: {} for key in LIBRARY_KEYS}  # type: Dict[str, Dict[str, Any]]
+    origins = {}  # type: Dict[Tuple[str, str], str]
+    for library_file in data.pop('uses'):
+        library_abs = path.abspath(library_file)
+        if library_abs in stack:
+            cycle = " -> ".join(path.relpath(f) for f in stack[stack.index(library_abs):] + [library_abs])
+            raise ReginaldException(f"{file_name}: Uses cycle: {cycle}")
+
+        used = migrate_listing(load_listing_data(library_file))
+        used = _resolve_includes_and_uses(used, library_file, stack + [library_abs])
+        for key in LIBRARY_KEYS:
+            for name, entry in used[key].items():
+                _claim(origins, key, name, library_file)
+                library[key][name] = entry
+
+    # Take over everything referenced, including the references of taken over
+    # layouts. Malformed entries are skipped, and left for validation to report:
+    pending = []  # type: List[Tuple[Dict[str, Any], Any]]
+    for entry in data['registers'].values():
+        if isinstance(entry, dict) and 'instances' in entry:
+            siblings = entry.get('registers')
+            if isinstance(siblings, dict):
+                pending.extend((siblings, template) for template in siblings.values())
+        else:
+            pending.append(({}, entry))
+    pending.extend(({}, layout) for layout in data['layouts'].values())
+
+    while len(pending) > 0:
+        siblings, template = pending.pop()
+        if not isinstance(template, dict):
+            continue
+        for base in [template.get('extends'), template.get('layout_read'), template.get('layout_write')]:
+            if isinstance(base, str) and base in library['layouts'] and \
+                    not any(base in defined for defined in [siblings, data['registers'], data['layouts']]):
+                data['layouts'][base] = deepcopy(library['layouts'][base])
+                pending.append(({}, data['layouts'][base]))
+        fields = template.get('fields')
+        for field in (fields.values() if isinstance(fields, dict) else []):
+            enum = field.get('enum') if isinstance(field, dict) else None
+            if isinstance(enum, str) and enum in library['enums'] and enum not in data['enums']:
+                data['enums'][enum] = deepcopy(library['enums'][enum])
+        variants = template.get('variants')
+        if isinstance(variants, dict):
+            pending.extend((siblings, variant) for variant in variants.values())
+
+    return data
+
+
+def _claim(origins: Dict[Tuple[str, str], str], key: str, name: str, file_name: str):
+    # The same file may be included more than once (e.g. common enums):
+    if (key, name) in origins and path.abspath(origins[(key, name)]) != path.abspath(file_name):
+        raise ReginaldException(f"{file_name}: {key} -> {name} is already defined in {origins[(key, name)]}!")
+    origins[(key, name)] = file_name
+
+
+def apply_default_bitwidth(data: Dict[str, Any], file_name: str):
+    bitwidth = data.get('default_register_bitwidth')
+    if bitwidth is None:
+        return
+
+    for name, entry in data['registers'].items():
+        if not isinstance(entry, dict):
+            raise ReginaldException(f"{file_name}: registers -> {name} has to be a map!")
+        templates = entry.get('registers') if 'instances' in entry else {name: entry}
+        for template in (templates or {}).values():
+            if isinstance(template, dict) and 'bitwidth' not in template:
+                template['bitwidth'] = bitwidth
@@ -0,0 +1,97 @@
+import re
+from typing import List, Set
+
+from reginald.datamodel import Docs, RegEnum, RegisterMap
+
+# Consistency checks on documentation strings:
+#   - Placeholder text (TBD, TODO, ...) that survived datasheet transcription.
+#   - Backtick-quoted references (`NAME` or `REG.FIELD`) that do not resolve
+#     to a register, block, field, enum, enum entry, or constant.
+#   - Bare upper-case identifiers with an underscore (e.g. 'CHG_EN') that look
+#     like field or register names but do not exist in the map.
+
+PLACEHOLDER_RE = re.compile(r"\b(TBD|TBC|TODO|FIXME|XXX)\b|\?\?\?|lorem ipsum|to be (defined|determined|confirmed)",
+                            re.IGNORECASE)
+BACKTICK_REF_RE = re.compile(r"`([A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z_][A-Za-z0-9_]*)*)`")
+BARE_NAME_RE = re.compile(r"(?<![`\w.])([A-Z][A-Z0-9]*_[A-Z0-9_]*[A-Z0-9])(?![`\w])")
+
+
+class DocLinter:
+    def __init__(self, rmap: RegisterMap):
+        self.rmap = rmap
+        self.names = self._known_names()
+
+    def lint(self) -> List[str]:
+        findings = []
+        rmap = self.rmap
+
+        findings.extend(self._lint_docs(rmap.docs, "map"))
+
+        for enum in rmap.enums.values():
+            findings.extend(self._lint_enum(enum, f"enums -> {enum.name}"))
+
+        for constant in rmap.constants.values():
+            findings.extend(self._lint_docs(constant.docs, f"constants -> {constant.name}"))
+
+        for block in rmap.register_blocks.values():
+            bt = f"registers -> {block.name}"
+            findings.extend(self._lint_docs(block.docs, bt))
+
+            for template in block.register_templates.values():
+                tbt = bt + f" -> {template.name}" if template.name != "" else bt
+                findings.extend(self._lint_docs(template.docs, tbt))
+
+                for field in template.get_all_fields().values():
+                    fbt = tbt + f" -> {field.name}"
+                    findings.extend(self._lint_docs(field.docs, fbt))
+                    if field.enum is not None and not field.enum.is_shared:
+                        findings.extend(self._lint_enum(field.enum, fbt + " -> enum"))
+
+        # Single registers share their docs with the block that wraps them:
+        return list(dict.fromkeys(findings))
+
+    def _lint_enum(self, enum: RegEnum, bt: str) -> List[str]:
+        findings = self._lint_docs(enum.docs, bt)
+        for entry in enum.entries.values():
+            findings.extend(self._lint_docs(entry.docs, bt + f" -> {entry.name}"))
+        return findings
+
+    def _lint_docs(self, docs: Docs, bt: str) -> List[str]:
+        findings = []
+        for text in [docs.brief, docs.doc]:
+            if text is None:
+                continue
+
+            for match in PLACEHOLDER_RE.finditer(text):
+                findings.append(f"{bt}: Placeholder text '{match.group()}' in documentation.")
+
+            for match in BACKTICK_REF_RE.finditer(text):
+                unknown = [part for part in match.group(1).split('.') if part not in self.names]
+                if len(unknown) > 0:
+                    findings.append(f"{bt}: Reference `{match.group(1)}` does not resolve ('{unknown[0]}' is not defined).")
+
+            for match in BARE_NAME_RE.finditer(text):
+                if match.group(1) not in self.names:
+                    findings.append(f"{bt}: Documentation mentions '{match.group(1)}', which is not a name in the map.")
+        return findings
+
+    def _known_names(self) -> Set[str]:
+        names = set()
+        for reg in self.rmap.physical_registers.values():
+            names.add(reg.name)
+        for block in self.rmap.register_blocks.values():
+            names.add(block.name)
+            names.update(block.instances.keys())
+            for template in block.register_templates.values():
+                names.add(template.name)
+                names.add(template.name.lstrip('_'))
+                for field in template.get_all_fields().values():
+                    names.add(field.name)
+                    if field.enum is not None:
+                        names.add(field.enum.name)
+                        names.update(field.enum.entries.keys())
+        for enum in self.rmap.enums.values():
+            names.add(enum.name)
+            names.update(enum.entries.keys())
+        names.update(self.rmap.constants.keys())
+        return names
@@ -0,0 +1,84 @@
+from os import path
+from typing import Callable, Dict, List, Optional, Tuple
+
+import yaml
+
+from reginald.datamodel import RegisterMap
+from reginald.error import ReginaldException
+from reginald.input.convert_yaml import YAMLConverter
+from reginald.input.errors_yaml import format_yaml_error
+from reginald.input.overlay_yaml import apply_overlays
+from reginald.input.parse_cheader import listing_from_c_header_file
+from reginald.input.parse_csv import listing_from_csv_file
+from reginald.input.parse_ipxact import listing_from_ipxact_file
+from reginald.input.parse_opentitan import listing_from_opentitan_file
+from reginald.input.parse_svd import listing_from_svd_file
+from reginald.input.parse_systemrdl import listing_from_systemrdl_file
+from reginald.input.parse_yaml import YAML_RegisterMap
+from reginald.input.validate_map import MapValidator
+
+# Supported input formats: Name -> (File extensions, Loader)
+INPUT_FORMATS = {
+    'yaml': (['.yaml', '.yml'], YAML_RegisterMap.from_yaml_file),
+    'json': (['.json'], YAML_RegisterMap.from_json_file),
+    'svd': (['.svd'], listing_from_svd_file),
+    'ipxact': (['.xml'], listing_from_ipxact_file),
+    'systemrdl': (['.rdl'], listing_from_systemrdl_file),
+    'cheader': (['.h'], listing_from_c_header_file),
+    'csv': (['.csv'], listing_from_csv_file),
+    'opentitan': (['.hjson'], listing_from_opentitan_file),
+}  # type: Dict[str, Tuple[List[str], Callable[..., YAML_RegisterMap]]]
+
+
+# Input formats that support listing parameters (and permissive parsing):
+PARAM_INPUT_FORMATS = ['yaml', 'json']
+
+
+def load_listing(file_name: str, input_format: Optional[str] = None, params: Optional[Dict[str, int]] = None,
+                 permissive: bool = False) -> YAML_RegisterMap:
+    if input_format is None:
+        input_format = guess_input_format(file_name)
+
+    if input_format not in INPUT_FORMATS:
+        raise ReginaldException(f"Unknown input format '{input_format}'")
+
+    _, loader = INPUT_FORMATS[input_format]
+    if input_format not in PARAM_INPUT_FORMATS and params:
+        raise ReginaldException(f"Parameters are not supported for input format '{input_format}'")
+    if input_format not in PARAM_INPUT_FORMATS and permissive:
+        raise ReginaldException(f"Permissive parsing is not supported for input format '{input_format}'")
+
+    # Importers report malformed content, but not unreadable files or YAML
+    # syntax errors (e.g. of a CSV column config):
+    try:
+        if input_format in PARAM_INPUT_FORMATS:
+            return loader(file_name, params, permissive)
+        return loader(file_name)
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except OSError as e:
+        raise ReginaldException(f"{file_name}: Cannot read file: {e.strerror}")
+    except UnicodeDecodeError:
+        raise ReginaldException(f"{file_name}: Not a UTF-8 text file!")
+    except yaml.YAMLError as e:
+        raise ReginaldException(format_yaml_error(e, file_name))
+
+
+def guess_input_format(file_name: str) -> str:
+    ext = path.splitext(file_name)[1].lower()
+    for name, (extensions, _) in INPUT_FORMATS.items():
+        if ext in extensions:
+            return name
+
+    # Default to yaml:
+    return 'yaml'
+
+
+def load_register_map(file_name: str, input_format: Optional[str] = None, doc_lang: Optional[str] = None,
+                      params: Optional[Dict[str, int]] = None, overlays: Optional[List[str]] = None,
+                      permissive: bool = False) -> RegisterMap:
+    # Open, parse, patch, convert, and validate an input file:
+    listing = apply_overlays(load_listing(file_name, input_format, params, permissive), overlays or [])
+    rmap = YAMLConverter(listing, doc_lang).convert()
+    MapValidator(rmap).validate()
+    return rmap
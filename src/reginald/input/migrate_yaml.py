@@ -0,0 +1,161 @@
+import re
+from typing import Any, Callable, Dict, FrozenSet, List, Optional, Tuple, Union
+
+import yaml
+from yaml.loader import SafeLoader
+
+from reginald.error import ReginaldException
+
+# Current listing schema version:
+SCHEMA_VERSION = 2
+
+
+def migrate_bit_ranges(data: Any) -> Any:
+    # Schema 2: Bit ranges are written MSB first ("2-0"), as in datasheets and
+    # in the output of all importers. Ranges written LSB first ("0-2") are
+    # still accepted, this only brings upgraded listings into the common form.
+    # Malformed entries are left for validation to report.
+    if isinstance(data, dict):
+        for key, val in data.items():
+            if key == 'fields' and isinstance(val, dict):
+                for field in val.values():
+                    if isinstance(field, dict) and 'bits' in field:
+                        field['bits'] = msb_first(field['bits'])
+            migrate_bit_ranges(val)
+    elif isinstance(data, list):
+        for val in data:
+            migrate_bit_ranges(val)
+    return data
+
+
+def msb_first(bits: Any) -> Any:
+    if isinstance(bits, list):
+        return [msb_first(bit) for bit in bits]
+    if isinstance(bits, str):
+        match = re.fullmatch(r"([0-9]+)-([0-9]+)", bits)
+        if match is not None and int(match.group(1)) < int(match.group(2)):
+            return f"{match.group(2)}-{match.group(1)}"
+    return bits
+
+
+# Listing migrations. The function stored at key N upgrades a raw listing
+# from schema version N to schema version N+1.
+MIGRATIONS: Dict[int, Callable[[Dict[str, Any]], Dict[str, Any]]] = {
+    1: migrate_bit_ranges,
+}
+
+
+def listing_schema_version(data: Dict[str, Any]) -> int:
+    # Listings without a schema key predate schema versioning.
+    version = data.get('schema', 1)
+
+    if not isinstance(version, int) or isinstance(version, bool) or version < 1:
+        raise ReginaldException(f"schema: Invalid schema version '{version}'!")
+
+    if version > SCHEMA_VERSION:
+        raise ReginaldException(f"schema: Listing uses schema version {version}, but this version of reginald "
+                                f"only supports versions up to {SCHEMA_VERSION}!")
+
+    return version
+
+
+def migrate_listing(data: Dict[str, Any]) -> Dict[str, Any]:
+    if not isinstance(data, dict):
+        raise ReginaldException("Listing is not a map!")
+
+    version = listing_schema_version(data)
+
+    data = {key: val for key, val in data.items() if key != 'schema'}
+    for step in range(version, SCHEMA_VERSION):
+        data = MIGRATIONS[step](data)
+
+    return data
+
+
+def listing_changes(old: Any, new: Any, key_path: Tuple[Union[str, int], ...] = ()) -> List[str]:
+    # Human-readable list of differences between two raw listings, one line per
+    # added, removed, or changed key.
+    if isinstance(old, dict) and isinstance(new, dict):
+        changes = []  # type: List[str]
+        for key in old:
+            if key not in new:
+                changes.append(f"{breadcrumb(key_path + (key,))}: Removed.")
+        for key in new:
+            if key not in old:
+                changes.append(f"{breadcrumb(key_path + (key,))}: Added.")
+            else:
+                changes.extend(listing_changes(old[key], new[key], key_path + (key,)))
+        return changes
+
+    if isinstance(old, list) and isinstance(new, list) and len(old) == len(new):
+        changes = []
+        for idx, (old_item, new_item) in enumerate(zip(old, new)):
+            changes.extend(listing_changes(old_item, new_item, key_path + (idx,)))
+        return changes
+
+    if old != new:
+        return [f"{breadcrumb(key_path)}: Changed from {old!r} to {new!r}."]
+    return []
+
+
+def breadcrumb(key_path: Tuple[Union[str, int], ...]) -> str:
+    return " -> ".join(str(k) for k in key_path)
+
+
+def edit_listing_text(text: str, old: Dict[str, Any], new: Dict[str, Any]) -> Optional[str]:
+    # Applies the differences between two raw listings (a new 'schema' key, and
+    # changed values) to the text of the old one, so that comments and
+    # formatting are kept. Returns None if a difference cannot be applied that
+    # way, e.g. an added or removed key, or a changed map or list.
+    root = yaml.compose(text, Loader=SafeLoader)
+    if not isinstance(root, yaml.MappingNode) or root.flow_style:
+        return None
+
+    edits = {}  # type: Dict[int, Tuple[int, str]]
+    old = {key: val for key, val in old.items() if key != 'schema'}
+    new_schema = new.get('schema')
+    new = {key: val for key, val in new.items() if key != 'schema'}
+    if not node_edits(root, old, new, edits, skip_keys=frozenset({'schema'})):
+        return None
+
+    schema_node = next((val for key, val in root.value if key.value == 'schema'), None)
+    if new_schema is not None:
+        if schema_node is not None:
+            if not node_edits(schema_node, None, new_schema, edits):
+                return None
+        else:
+            line_start = root.start_mark.index - root.start_mark.column
+            edits[line_start] = (line_start, f"schema: {new_schema}\n")
+
+    for start, (end, replacement) in sorted(edits.items(), reverse=True):
+        text = text[:start] + replacement + text[end:]
+    return text
+
+
+def node_edits(node: yaml.Node, old: Any, new: Any, edits: Dict[int, Tuple[int, str]],
+               skip_keys: FrozenSet[str] = frozenset()) -> bool:
+    # Collects the text edits (start -> (end, replacement)) that turn the
+    # value of a node from old into new.
+    if old == new and type(old) is type(new):
+        return True
+
+    if isinstance(node, yaml.MappingNode) and isinstance(old, dict) and isinstance(new, dict):
+        if set(old.keys()) != set(new.keys()):
+            return False
+        nodes = {key.value: val for key, val in node.value if isinstance(key, yaml.ScalarNode) and key.value not in skip_keys}
+        if set(nodes.keys()) != set(str(key) for key in old.keys()):
+            return False
+        return all(node_edits(nodes[str(key)], old[key], new[key], edits) for key in old)
+
+    if isinstance(node, yaml.SequenceNode) and isinstance(old, list) and isinstance(new, list) and len(old) == len(new):
+        return all(node_edits(item, old_item, new_item, edits) for item, old_item, new_item in zip(node.value, old, new))
+
+    # Block scalars ('|', '>') would need their indentation adjusted:
+    if isinstance(node, yaml.ScalarNode) and node.style not in ['|', '>'] and isinstance(new, (str, int, float, bool)):
+        replacement = yaml.safe_dump(new, default_style=node.style if isinstance(new, str) else None, width=float('inf'))
+        if replacement.endswith("\n...\n"):
+            replacement = replacement[:-len("\n...\n")]
+        edits[node.start_mark.index] = (node.end_mark.index, replacement.rstrip("\n"))
+        return True
+
+    return False
@@ -0,0 +1,253 @@
+from os import path
+from typing import Any, Dict, Iterable, List, Set
+
+from reginald.datamodel import DOC_REF_RE
+from reginald.error import ReginaldException
+from reginald.input.include_yaml import (MERGED_KEYS, apply_default_bitwidth,
+                                         load_listing_data, resolve_includes)
+from reginald.input.migrate_yaml import migrate_listing
+from reginald.input.params_yaml import expand_params, expand_value
+
+# Listings can mount other register maps at a base address (paths relative to
+# the mounting file), e.g. a companion chip bridged into an MCU's address
+# space:
+#
+#   mount:
+#     - {map: companion.yaml, at: 0x8000}
+#     - {map: sensor.yaml, at: 0x9000, prefix: "SNS_"}
+#
+# A single mount can also be given without the list. Unlike includes, a
+# mounted map stays self-contained: Its own parameters, includes, mounts, and
+# default bitwidth apply, all of its addresses are offset by 'at', and all of
+# its register, layout, enum, transaction, split value, and constant names are
+# prefixed (by default with '<map_name>_'). The reserved fill of the mounting
+# listing also applies to mounted registers, unless the mounted map sets its
+# own. Name collisions, registers at the same address as a register of the
+# mounting listing, and maps with different address units are errors.
+
+
+def resolve_mounts(data: Dict[str, Any], file_name: str) -> Dict[str, Any]:
+    # Expects a listing with includes and parameters resolved, returns it with
+    # all mounted maps merged.
+    return _resolve(data, file_name, [path.abspath(file_name)])
+
+
+def _resolve(data: Dict[str, Any], file_name: str, stack: List[str]) -> Dict[str, Any]:
+    mounts = data.get('mount', [])
+    if isinstance(mounts, dict):
+        mounts = [mounts]
+    if not isinstance(mounts, list):
+        raise ReginaldException(f"{file_name}: 'mount' has to be a map or a list of maps!")
+
+    data = {key: val for key, val in data.items() if key != 'mount'}
+    if len(mounts) == 0:
+        return data
+
+    for key in MERGED_KEYS:
+        data[key] = dict(data.get(key) or {})
+
+    for idx, mount in enumerate(mounts):
+        bt = f"{file_name}: mount -> {idx}"
+        if not isinstance(mount, dict) or 'map' not in mount or 'at' not in mount:
+            raise ReginaldException(f"{bt}: A mount needs a 'map' and an 'at' address!")
+        unknown = set(mount.keys()) - {'map', 'at', 'prefix'}
+        if len(unknown) > 0:
+            raise ReginaldException(f"{bt}: Unknown key(s) {', '.join(sorted(unknown))}!")
+
+        at = expand_value(mount['at'], {}, bt + " -> at")
+        if not isinstance(at, int) or isinstance(at, bool) or at < 0:
+            raise ReginaldException(f"{bt} -> at: Has to be a non-negative integer!")
+
+        mount_file = path.join(path.dirname(file_name), str(mount['map']))
+        mount_abs = path.abspath(mount_file)
+        if mount_abs in stack:
+            cycle = " -> ".join(path.relpath(f) for f in stack[stack.index(mount_abs):] + [mount_abs])
+            raise ReginaldException(f"{file_name}: Mount cycle: {cycle}")
+
+        mounted = migrate_listing(load_listing_data(mount_file))
+        mounted = expand_params(resolve_includes(mounted, mount_file))
+        mounted = _resolve(mounted, mount_file, stack + [mount_abs])
+        apply_default_bitwidth(mounted, mount_file)
+        apply_reserved_fill(mounted)
+
+        host_unit = data.get('address_unit_bits', 8)
+        mounted_unit = mounted.get('address_unit_bits', 8)
+        if mounted_unit != host_unit:
+            raise ReginaldException(f"{bt}: Mounted map uses {mounted_unit}-bit addresses, "
+                                    f"but the mounting listing uses {host_unit}-bit addresses!")
+
+        prefix = mount.get('prefix', f"{mounted.get('map_name', '')}_")
+        if not isinstance(prefix, str):
+            raise ReginaldException(f"{bt} -> prefix: Has to be a string!")
+
+        mounted = prefix_listing(offset_listing(mounted, at), prefix)
+
+        host_adrs = register_addresses(data)
+        for adr, name in register_addresses(mounted).items():
+            if adr in host_adrs:
+                raise ReginaldException(f"{bt}: Mounted register {name} at 0x{adr:X} collides with register {host_adrs[adr]}!")
+
+        for key in MERGED_KEYS:
+            for name, entry in mounted[key].items():
+                if name in data[key]:
+                    raise ReginaldException(f"{bt}: Mounted {key} -> {name} is already defined!")
+                data[key][name] = entry
+
+    return data
+
+
+def apply_reserved_fill(data: Dict[str, Any]):
+    # Only registers are merged, so push the mounted map's reserved fill down
+    # to its registers:
+    fill = data.get('reserved_fill')
+    if fill is None:
+        return
+
+    for name, entry in data['registers'].items():
+        if not isinstance(entry, dict):
+            continue
+        templates = entry.get('registers') if 'instances' in entry else {name: entry}
+        for template in (templates or {}).values():
+            if isinstance(template, dict):
+                template.setdefault('reserved_fill', fill)
+
+
+def offset_listing(data: Dict[str, Any], at: int) -> Dict[str, Any]:
+    # Non-integer addresses are left for validation to report:
+    for entry in data['registers'].values():
+        if isinstance(entry, dict) and is_instance_array(entry.get('instances')):
+            if isinstance(entry['instances']['start'], int):
+                entry['instances']['start'] = entry['instances']['start'] + at
+        elif isinstance(entry, dict) and isinstance(entry.get('instances'), dict):
+            entry['instances'] = {inst: adr + at if isinstance(adr, int) else adr for inst, adr in entry['instances'].items()}
+        elif isinstance(entry, dict) and isinstance(entry.get('adr'), int):
+            entry['adr'] = entry['adr'] + at
+    return data
+
+
+def prefix_listing(data: Dict[str, Any], prefix: str) -> Dict[str, Any]:
+    enum_names = set(data['enums'].keys())  # type: Set[str]
+
+    registers = {}
+    for name, entry in data['registers'].items():
+        if not isinstance(entry, dict):
+            registers[prefix + name] = entry
+            continue
+        if is_instance_array(entry.get('instances')):
+            templates = (entry.get('registers') or {}).values()  # Instance names follow the (prefixed) block name.
+        elif isinstance(entry.get('instances'), dict):
+            entry['instances'] = {prefix + inst: adr for inst, adr in entry['instances'].items()}
+            templates = (entry.get('registers') or {}).values()
+        else:
+            templates = [entry]
+            # Aliases of single registers are complete register names:
+            if isinstance(entry.get('aliases'), list):
+                entry['aliases'] = [prefix + alias if isinstance(alias, str) else alias for alias in entry['aliases']]
+        if isinstance(entry.get('instance_security'), dict):
+            entry['instance_security'] = {prefix + inst: attrs for inst, attrs in entry['instance_security'].items()}
+        if 'instances' in entry:
+            prefix_domains(entry, prefix)
+        siblings = set(entry.get('registers') or {}) if 'instances' in entry else set()
+        prefix_references(templates, siblings, enum_names, prefix)
+        registers[prefix + name] = entry
+
+    for layout in data['layouts'].values():
+        if isinstance(layout, dict):
+            prefix_references([layout], set(), enum_names, prefix)
+
+    transactions = {}
+    for name, transaction in data['transactions'].items():
+        if isinstance(transaction, dict) and isinstance(transaction.get('registers'), list):
+            transaction['registers'] = [prefix + reg for reg in transaction['registers']]
+        transactions[prefix + name] = transaction
+
+    split_values = {}
+    for name, split_value in data['split_values'].items():
+        if isinstance(split_value, dict) and isinstance(split_value.get('parts'), list):
+            split_value['parts'] = [prefix + part if isinstance(part, str) else part for part in split_value['parts']]
+        split_values[prefix + name] = split_value
+
+    prefix_doc_refs(data, prefix)
+    data['registers'] = registers
+    data['layouts'] = {prefix + name: layout for name, layout in data['layouts'].items()}
+    data['enums'] = {prefix + name: enum for name, enum in data['enums'].items()}
+    data['transactions'] = transactions
+    data['split_values'] = split_values
+    data['constants'] = {prefix + name: constant for name, constant in data['constants'].items()}
+    return data
+
+
+def prefix_references(templates: Iterable[Any], siblings: Set[str], enum_names: Set[str], prefix: str):
+    # References to shared enums, key registers, extended (or read/write layout) top-level registers or layouts, and domains:
+    for template in templates:
+        if not isinstance(template, dict):
+            continue
+        for key in ['extends', 'layout_read', 'layout_write']:
+            if isinstance(template.get(key), str) and template[key] not in siblings:
+                template[key] = prefix + template[key]
+        for field in (template.get('fields') or {}).values():
+            if isinstance(field, dict) and isinstance(field.get('enum'), str) and field['enum'] in enum_names:
+                field['enum'] = prefix + field['enum']
+        unlock = template.get('unlock')
+        if isinstance(unlock, dict) and isinstance(unlock.get('key_register'), str):
+            unlock['key_register'] = prefix + unlock['key_register']
+        if isinstance(template.get('variants'), dict):
+            prefix_references(template['variants'].values(), siblings, enum_names, prefix)
+        prefix_domains(template, prefix)
+
+
+def prefix_domains(entry: Dict[str, Any], prefix: str):
+    # The mounted map's clock and power domains are its own:
+    for key in ['clock_domain', 'power_domain']:
+        if isinstance(entry.get(key), str):
+            entry[key] = prefix + entry[key]
+
+
+def prefix_doc_refs(data: Any, prefix: str):
+    # Documentation cross-references ('[[REG.FIELD]]') in all (per-language) docs:
+    def sub(text: str) -> str:
+        return DOC_REF_RE.sub(lambda m: f"[[{prefix}{m.group(0)[2:]}", text)
+
+    if isinstance(data, dict):
+        for key, val in data.items():
+            if key in ['doc', 'brief'] and isinstance(val, str):
+                data[key] = sub(val)
+            elif key in ['doc', 'brief'] and isinstance(val, dict) and all(isinstance(t, str) for t in val.values()):
+                data[key] = {lang: sub(t) for lang, t in val.items()}
+            else:
+                prefix_doc_refs(val, prefix)
+    elif isinstance(data, list):
+        for val in data:
+            prefix_doc_refs(val, prefix)
+
+
+def register_addresses(data: Dict[str, Any]) -> Dict[int, str]:
+    # Physical register addresses -> names, for registers with integer addresses:
+    adrs = {}  # type: Dict[int, str]
+    for name, entry in data['registers'].items():
+        if not isinstance(entry, dict):
+            continue
+        if 'instances' in entry:
+            instances = entry.get('instances') or {}
+            if is_instance_array(instances):
+                instances = instance_array_addresses(name, instances)
+            for inst, inst_adr in instances.items():
+                for template_name, template in (entry.get('registers') or {}).items():
+                    if isinstance(template, dict) and isinstance(template.get('adr'), int) and isinstance(inst_adr, int):
+                        adrs[inst_adr + template['adr']] = inst + template_name
+        elif isinstance(entry.get('adr'), int):
+            adrs[entry['adr']] = name
+    return adrs
+
+
+def is_instance_array(instances: Any) -> bool:
+    # Block instances given as {count, start, stride} instead of by name:
+    return isinstance(instances, dict) and set(instances.keys()) == {'count', 'start', 'stride'}
+
+
+def instance_array_addresses(name: str, instances: Dict[str, Any]) -> Dict[str, int]:
+    # Non-integer values are left for validation to report:
+    count, start, stride = instances['count'], instances['start'], instances['stride']
+    if not all(isinstance(val, int) for val in [count, start, stride]):
+        return {}
+    return {f"{name}[{i}]": start + i * stride for i in range(count)}
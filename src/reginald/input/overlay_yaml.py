@@ -0,0 +1,49 @@
+from typing import Any, Dict, List
+
+from pydantic import ValidationError
+
+from reginald.error import ReginaldException
+from reginald.input.errors_yaml import format_validation_error, yaml_key_marks
+from reginald.input.include_yaml import load_listing_data
+from reginald.input.migrate_yaml import migrate_listing
+from reginald.input.parse_yaml import YAML_RegisterMap
+
+# Overlays patch a listing before it is converted, to fix up (vendor) maps
+# without forking them. An overlay has the structure of a listing and is
+# merged into it, following JSON merge patch (RFC 7386) semantics:
+#   - Maps are merged recursively, adding or modifying keys.
+#   - A key set to null removes it (e.g. a register, field, or enum entry).
+#   - All other values (including lists) replace the original value.
+
+
+def apply_overlays(listing: YAML_RegisterMap, overlay_files: List[str]) -> YAML_RegisterMap:
+    if len(overlay_files) == 0:
+        return listing
+
+    data = listing.model_dump(exclude_none=True)
+    for overlay_file in overlay_files:
+        patch = migrate_listing(load_listing_data(overlay_file))
+        data = merge_patch(data, patch)
+
+        try:
+            listing = YAML_RegisterMap(**data)
+        except ValidationError as e:
+            raise ReginaldException(f"{overlay_file}: Listing is invalid after applying overlay:\n"
+                                    + format_validation_error(e, data, overlay_file, yaml_key_marks(overlay_file)))
+
+    return listing
+
+
+def merge_patch(target: Any, patch: Any) -> Any:
+    if not isinstance(patch, dict):
+        return patch
+    if not isinstance(target, dict):
+        target = {}
+
+    result = dict(target)  # type: Dict[Any, Any]
+    for key, value in patch.items():
+        if value is None:
+            result.pop(key, None)
+        else:
+            result[key] = merge_patch(result.get(key), value)
+    return result
@@ -0,0 +1,164 @@
+import ast
+import re
+from typing import Any, Dict, Optional
+
+from reginald.error import ReginaldException
+
+# Listings can declare integer parameters, which are expanded in addresses,
+# instance addresses, bitwidths, reset values, always write values, array
+# counts/strides, field ranges, enum values, and constants (of registers,
+# their variants, and layouts alike):
+#
+#   params:
+#     BASE: 0x4000
+#   registers:
+#     CTRL:
+#       adr: "${BASE} + 0x10"
+#
+# Strings in these places are evaluated as simple integer expressions (+, -,
+# *, //, <<, >>, |, &) of '${NAME}' references and literals. This also allows
+# values to be written as in the datasheet, e.g. "0b1010_1100", "0x1_0000",
+# or "1 << 12", without any parameters.
+# Parameters can be overridden from the command line (--param NAME=VALUE).
+# Included listings use the parameters of the including listing.
+
+PARAM_RE = re.compile(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}")
+# Intermediate values (and shift counts) of expressions are limited, so that a
+# typo like "1 << 0x100000000" is reported instead of exhausting the memory:
+MAX_EXPR_BITS = 4096
+EXPANDED_REGISTER_KEYS = ['adr', 'bitwidth', 'reset_val', 'count', 'stride']
+EXPANDED_FIELD_KEYS = ['min', 'max', 'count', 'stride']
+EXPANDED_ALWAYS_WRITE_KEYS = ['mask', 'val', 'dont_care']
+
+
+def expand_params(data: Dict[str, Any], overrides: Optional[Dict[str, int]] = None) -> Dict[str, Any]:
+    params = data.get('params', {})
+    if not isinstance(params, dict):
+        raise ReginaldException("params: Has to be a map of names to integers!")
+    for name, value in params.items():
+        if not isinstance(value, int) or isinstance(value, bool):
+            raise ReginaldException(f"params -> {name}: Has to be an integer!")
+
+    for name, value in (overrides or {}).items():
+        if name not in params:
+            raise ReginaldException(f"params: Cannot override {name}, it is not declared in the listing!")
+        params[name] = value
+
+    data = {key: val for key, val in data.items() if key != 'params'}
+
+    for enum_name, enum in (data.get('enums') or {}).items():
+        if isinstance(enum, dict):
+            expand_enum(enum.get('enum'), params, f"enums -> {enum_name}")
+
+    for layout_name, layout in (data.get('layouts') or {}).items():
+        if isinstance(layout, dict):
+            expand_fields(layout.get('fields'), params, f"layouts -> {layout_name}")
+
+    for constant_name, constant in (data.get('constants') or {}).items():
+        if isinstance(constant, dict) and 'val' in constant:
+            constant['val'] = expand_value(constant['val'], params, f"constants -> {constant_name} -> val")
+
+    if 'default_register_bitwidth' in data:
+        data['default_register_bitwidth'] = expand_value(data['default_register_bitwidth'], params, "default_register_bitwidth")
+
+    for name, entry in (data.get('registers') or {}).items():
+        if not isinstance(entry, dict):
+            continue
+        bt = f"registers -> {name}"
+        expand_register(entry, params, bt)
+        if isinstance(entry.get('instances'), dict):
+            entry['instances'] = {inst: expand_value(adr, params, bt + f" -> instances -> {inst}")
+                                  for inst, adr in entry['instances'].items()}
+        if isinstance(entry.get('registers'), dict):
+            for template_name, template in entry['registers'].items():
+                if isinstance(template, dict):
+                    expand_register(template, params, bt + f" -> {template_name}")
+
+    return data
+
+
+def expand_register(reg: Dict[str, Any], params: Dict[str, int], bt: str):
+    for key in EXPANDED_REGISTER_KEYS:
+        if key in reg:
+            reg[key] = expand_value(reg[key], params, bt + f" -> {key}")
+    if isinstance(reg.get('always_write'), dict):
+        for key in EXPANDED_ALWAYS_WRITE_KEYS:
+            if key in reg['always_write']:
+                reg['always_write'][key] = expand_value(reg['always_write'][key], params, bt + f" -> always_write -> {key}")
+    expand_fields(reg.get('fields'), params, bt)
+    if isinstance(reg.get('variants'), dict):
+        for variant_name, variant in reg['variants'].items():
+            if isinstance(variant, dict):
+                expand_fields(variant.get('fields'), params, bt + f" -> variants -> {variant_name}")
+
+
+def expand_fields(fields: Any, params: Dict[str, int], bt: str):
+    # Malformed fields are left for validation to report:
+    if not isinstance(fields, dict):
+        return
+    for field_name, field in fields.items():
+        if not isinstance(field, dict):
+            continue
+        for key in EXPANDED_FIELD_KEYS:
+            if key in field:
+                field[key] = expand_value(field[key], params, bt + f" -> {field_name} -> {key}")
+        expand_enum(field.get('enum'), params, bt + f" -> {field_name}")
+
+
+def expand_enum(entries: Any, params: Dict[str, int], bt: str):
+    # Only inline enums (maps of entries), not references to shared enums:
+    if not isinstance(entries, dict):
+        return
+    for entry_name, entry in entries.items():
+        if isinstance(entry, dict) and 'val' in entry:
+            entry['val'] = expand_value(entry['val'], params, bt + f" -> {entry_name} -> val")
+
+
+def expand_value(value: Any, params: Dict[str, int], bt: str) -> Any:
+    if not isinstance(value, str):
+        return value
+
+    def lookup(match: re.Match) -> str:
+        if match.group(1) not in params:
+            raise ReginaldException(f"{bt}: Unknown parameter '{match.group(1)}'!")
+        return str(params[match.group(1)])
+
+    expr = PARAM_RE.sub(lookup, value)
+    try:
+        return eval_int_expr(ast.parse(expr.strip(), mode='eval').body)
+    except (SyntaxError, ValueError):
+        if "${" in value:
+            raise ReginaldException(f"{bt}: Invalid parameter expression '{value}'!")
+        raise ReginaldException(f"{bt}: Invalid number '{value}'!")
+
+
+def eval_int_expr(node: ast.AST) -> int:
+    match node:
+        case ast.Constant(value=int() as value) if not isinstance(value, bool):
+            return value
+        case ast.BinOp(left=left, op=op, right=right):
+            a, b = eval_int_expr(left), eval_int_expr(right)
+            match op:
+                case ast.Add():
+                    return checked_int(a + b)
+                case ast.Sub():
+                    return checked_int(a - b)
+                case ast.Mult():
+                    return checked_int(a * b)
+                case ast.FloorDiv() if b != 0:
+                    return a // b
+                case ast.LShift() if 0 <= b <= MAX_EXPR_BITS:
+                    return checked_int(a << b)
+                case ast.RShift() if b >= 0:
+                    return a >> b
+                case ast.BitOr():
+                    return a | b
+                case ast.BitAnd():
+                    return a & b
+    raise ValueError()
+
+
+def checked_int(value: int) -> int:
+    if value.bit_length() > MAX_EXPR_BITS:
+        raise ValueError()
+    return value
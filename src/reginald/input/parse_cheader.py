@@ -0,0 +1,162 @@
+import re
+from os import path
+from typing import Dict, List, Optional, Tuple
+
+from reginald.bits import Bits
+from reginald.error import ReginaldException, warn
+from reginald.input.parse_yaml import (YAML_Field, YAML_Register,
+                                       YAML_RegisterMap)
+
+# Best-effort import of a register map from a C header, to bootstrap a
+# listing for chips that only come with one. Only object-like '#define's with
+# constant values are considered:
+#   - Register addresses: 'FOO_REG', 'FOO_ADDR', 'FOO_ADR', or 'FOO_REG_ADDR'
+#     define register 'FOO' at that address.
+#   - Fields: 'FOO_BAR_MSK'/'FOO_BAR_MASK'/'FOO_BAR_Msk' define the bits of
+#     field 'BAR' in register 'FOO' (the longest matching register name).
+#     A matching shift macro ('_POS', '_SHIFT', '_Pos') is checked for
+#     consistency if present. Masks may be given already shifted or
+#     right-aligned together with a shift.
+# Everything else is ignored. The result is a skeleton without access
+# modes, reset values, or documentation.
+
+DEFINE_RE = re.compile(r"^\s*#\s*define\s+([A-Za-z_][A-Za-z0-9_]*)\s+(.+?)\s*$")
+ADR_SUFFIXES = ['_REG_ADDR', '_REG', '_ADDR', '_ADR']
+MASK_SUFFIXES = ['_MSK', '_MASK', '_Msk']
+SHIFT_SUFFIXES = ['_POS', '_SHIFT', '_Pos']
+EXPR_RE = re.compile(r"^[0-9a-fA-FxX()<>|&~+\-* ]+$")
+
+
+def listing_from_c_header_file(file_name: str) -> YAML_RegisterMap:
+    try:
+        with open(file_name) as f:
+            lines = f.read().replace("\\\n", " ").splitlines()
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+
+    macros = {}  # type: Dict[str, int]
+    for line in lines:
+        match = DEFINE_RE.match(strip_c_comments(line))
+        if match is None:
+            continue
+        value = c_eval(match.group(2), macros)
+        if value is not None:
+            macros[match.group(1)] = value
+
+    return CHeaderConverter(macros, file_name).convert()
+
+
+class CHeaderConverter:
+    def __init__(self, macros: Dict[str, int], file_name: str):
+        self.macros = macros
+        self.file_name = file_name
+
+    def convert(self) -> YAML_RegisterMap:
+        bt = f"cheader -> {path.basename(self.file_name)}"
+
+        # Register addresses:
+        reg_adrs = {}  # type: Dict[str, int]
+        for name, value in self.macros.items():
+            reg_name = strip_suffix(name, ADR_SUFFIXES)
+            if reg_name is not None and reg_name not in reg_adrs:
+                reg_adrs[reg_name] = value
+
+        if len(reg_adrs) == 0:
+            raise ReginaldException(f"{bt}: No register address macros found!")
+
+        # Fields:
+        reg_fields = {name: {} for name in reg_adrs}  # type: Dict[str, Dict[str, Tuple[int, int]]]
+        for name, mask in self.macros.items():
+            stem = strip_suffix(name, MASK_SUFFIXES)
+            if stem is None:
+                continue
+            reg_name, field_name = split_field_name(stem, list(reg_adrs.keys()))
+            if reg_name is None:
+                warn(f"{bt}: Mask {name} does not belong to any register, ignoring.")
+                continue
+            reg_fields[reg_name][field_name] = self._field_bits(stem, mask, bt + f" -> {reg_name} -> {field_name}")
+
+        registers = {}
+        for reg_name, adr in reg_adrs.items():
+            fields = {}
+            msb = 7
+            for field_name, (lsb, field_msb) in reg_fields[reg_name].items():
+                fields[field_name] = YAML_Field(bits=lsb if lsb == field_msb else f"{field_msb}-{lsb}")
+                msb = max(msb, field_msb)
+
+            registers[reg_name] = YAML_Register(
+                adr=adr,
+                bitwidth=8 if msb < 8 else 16 if msb < 16 else 32 if msb < 32 else 64,
+                fields=fields,
+            )
+
+        map_name = path.splitext(path.basename(self.file_name))[0]
+        return YAML_RegisterMap(
+            map_name=map_name,
+            default_register_bitwidth=8,
+            registers=registers,
+        )
+
+    def _field_bits(self, stem: str, mask: int, bt: str) -> Tuple[int, int]:
+        shift = None
+        for suffix in SHIFT_SUFFIXES:
+            if stem + suffix in self.macros:
+                shift = self.macros[stem + suffix]
+
+        if mask == 0:
+            raise ReginaldException(f"{bt}: Mask is zero!")
+        if mask < 0 or mask >= 1 << 64:
+            raise ReginaldException(f"{bt}: Mask 0x{mask:X} does not fit into 64 bits!")
+        if shift is not None and not 0 <= shift < 64:
+            raise ReginaldException(f"{bt}: Shift ({shift}) out of range!")
+
+        # Right-aligned mask, to be used with a shift:
+        if shift is not None and mask & 1 and shift > 0:
+            mask = mask << shift
+
+        bits = Bits.from_mask(mask)
+        if len(bits.get_bitranges()) != 1:
+            raise ReginaldException(f"{bt}: Mask 0x{mask:X} is not contiguous!")
+        if shift is not None and shift != bits.lsb_position():
+            raise ReginaldException(f"{bt}: Shift ({shift}) does not match mask 0x{mask:X}!")
+
+        return bits.lsb_position(), bits.msb_position()
+
+
+def split_field_name(stem: str, reg_names: List[str]) -> Tuple[Optional[str], str]:
+    # Longest register name that is a prefix of the given name:
+    candidates = [reg for reg in reg_names if stem.startswith(reg + "_")]
+    if len(candidates) == 0:
+        return None, stem
+    reg_name = max(candidates, key=len)
+    return reg_name, stem[len(reg_name) + 1:]
+
+
+def strip_suffix(name: str, suffixes: List[str]) -> Optional[str]:
+    for suffix in suffixes:
+        if name.endswith(suffix) and len(name) > len(suffix):
+            return name[:-len(suffix)]
+    return None
+
+
+def strip_c_comments(line: str) -> str:
+    line = re.sub(r"/\*.*?\*/", " ", line)
+    return line.split("//")[0]
+
+
+def c_eval(expr: str, macros: Dict[str, int]) -> Optional[int]:
+    # Evaluates simple constant integer expressions. Returns None for anything else.
+    expr = re.sub(r"\(\s*u?int[0-9]+_t\s*\)", "", expr)
+    expr = re.sub(r"\b[A-Za-z_][A-Za-z0-9_]*\b",
+                  lambda m: str(macros[m.group()]) if m.group() in macros else m.group(), expr)
+    expr = re.sub(r"\b(0[xX][0-9a-fA-F]+|[0-9]+)[uUlL]+\b", r"\1", expr)
+    expr = re.sub(r"\bBIT\(", "(1 << ", expr)
+    if EXPR_RE.match(expr) is None:
+        return None
+    try:
+        value = eval(expr, {"__builtins__": {}}, {})
+    except Exception:
+        return None
+    if not isinstance(value, int) or value < 0:
+        return None
+    return value
@@ -0,0 +1,282 @@
+import csv
+import re
+from os import path
+from typing import Dict, List, Optional, Tuple
+
+import yaml
+from pydantic import BaseModel, ConfigDict, PositiveInt, ValidationError
+from yaml.loader import SafeLoader
+
+from reginald.error import ReginaldException, warn
+from reginald.input.parse_yaml import (YAML_Field, YAML_Register,
+                                       YAML_RegisterMap)
+
+# Imports the classic datasheet register table, exported from a spreadsheet as
+# CSV. Every row describes either a register, a field, or both:
+#   - A row with an address or register name starts a new register. Rows
+#     without either continue the previous register (merged cells in the
+#     original sheet export as empty cells).
+#   - A row with a field name adds a field to the current register.
+#   - Description, reset, and access apply to the field if the row has one,
+#     and to the register otherwise. Field resets are combined into the
+#     register reset value.
+#   - Fields named 'reserved' (or '-') are skipped.
+#
+# Columns are found by their header, using the aliases below. A column
+# mapping config next to the table ('regs.csv' -> 'regs.columns.yaml') can
+# name the columns explicitly and set other import options, e.g.:
+#
+#   map_name: ADC
+#   default_register_bitwidth: 16
+#   columns:
+#     address: "Offset"
+#     name: "Register Name"
+#     field: "Bit Field"
+
+CSV_COLUMN_ALIASES = {
+    'address': ['address', 'addr', 'adr', 'offset'],
+    'name': ['name', 'register', 'register name', 'reg'],
+    'bits': ['bits', 'bit', 'bit range', 'position'],
+    'field': ['field', 'field name', 'bitfield'],
+    'description': ['description', 'desc'],
+    'reset': ['reset', 'reset value', 'default'],
+    'access': ['access', 'type', 'r/w', 'rw'],
+    'bitwidth': ['bitwidth', 'width', 'size'],
+}
+
+CSV_REQUIRED_COLUMNS = ['address', 'name']
+
+CSV_RESERVED_FIELDS = ['reserved', 'rsvd', '-']
+
+
+class CSV_Columns(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    address: Optional[str] = None
+    name: Optional[str] = None
+    bits: Optional[str] = None
+    field: Optional[str] = None
+    description: Optional[str] = None
+    reset: Optional[str] = None
+    access: Optional[str] = None
+    bitwidth: Optional[str] = None
+
+
+class CSV_Config(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    map_name: Optional[str] = None
+    default_register_bitwidth: PositiveInt = 8
+    delimiter: str = ","
+    skip_rows: int = 0
+    columns: CSV_Columns = CSV_Columns()
+
+    @classmethod
+    def from_yaml_file(cls, file_name: str):
+        try:
+            with open(file_name) as f:
+                data = yaml.load(f, Loader=SafeLoader)
+                return CSV_Config(**data)
+        except FileNotFoundError:
+            raise ReginaldException(f"File {file_name} not found")
+        except ValidationError as e:
+            raise ReginaldException(str(e))
+
+
+def listing_from_csv_file(file_name: str) -> YAML_RegisterMap:
+    config_file = path.splitext(file_name)[0] + ".columns.yaml"
+    config = CSV_Config.from_yaml_file(config_file) if path.exists(config_file) else CSV_Config()
+
+    try:
+        with open(file_name, newline='', encoding='utf-8-sig') as f:
+            rows = list(csv.reader(f, delimiter=config.delimiter))
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except csv.Error as e:
+        raise ReginaldException(f"{file_name}: Invalid CSV: {e}")
+
+    return CSVConverter(rows[config.skip_rows:], config, file_name).convert()
+
+
+class CSVConverter:
+    def __init__(self, rows: List[List[str]], config: CSV_Config, file_name: str):
+        self.rows = rows
+        self.config = config
+        self.file_name = file_name
+
+    def convert(self) -> YAML_RegisterMap:
+        bt = f"csv -> {path.basename(self.file_name)}"
+        if len(self.rows) == 0:
+            raise ReginaldException(f"{bt}: Table is empty!")
+
+        columns = self._find_columns(self.rows[0], bt)
+
+        registers = {}  # type: Dict[str, YAML_Register]
+        field_resets = {}  # type: Dict[str, Dict[str, Tuple[int, int]]]
+        current = None  # type: Optional[str]
+
+        # Header is line 1 (after skipped rows), first data row line 2:
+        for line, row in enumerate(self.rows[1:], start=self.config.skip_rows + 2):
+            cells = {col: row[idx].strip() if idx < len(row) else "" for col, idx in columns.items()}
+            if all(v == "" for v in cells.values()):
+                continue
+            rbt = bt + f" -> line {line}"
+
+            adr_text = cells.get('address', "")
+            name = cells.get('name', "")
+            if adr_text != "" or name != "":
+                if adr_text == "" or name == "":
+                    raise ReginaldException(f"{rbt}: Register needs both an address and a name!")
+                current = csv_identifier(name, rbt)
+                adr = csv_int(adr_text, rbt + " -> address")
+                if current in registers:
+                    if registers[current].adr != adr:
+                        raise ReginaldException(f"{rbt}: Register {current} declared twice with different addresses!")
+                else:
+                    bitwidth_text = cells.get('bitwidth', "")
+                    registers[current] = YAML_Register(
+                        adr=adr,
+                        bitwidth=csv_int(bitwidth_text, rbt + " -> bitwidth") if bitwidth_text != "" else None,
+                    )
+                    field_resets[current] = {}
+            elif current is None:
+                raise ReginaldException(f"{rbt}: Field row before the first register!")
+
+            reg = registers[current]
+            field_name = cells.get('field', "")
+            brief = csv_brief(cells.get('description', ""))
+            access = csv_access(cells.get('access', ""), rbt)
+            reset_text = cells.get('reset', "")
+
+            if field_name == "":
+                # Register row:
+                if brief is not None:
+                    reg.brief = brief
+                if access is not None:
+                    reg.access = access
+                if reset_text != "":
+                    reg.reset_val = csv_int(reset_text, rbt + " -> reset")
+                continue
+
+            if field_name.lower() in CSV_RESERVED_FIELDS:
+                continue
+
+            fbt = rbt + f" -> {current} -> {field_name}"
+            field_name = csv_identifier(field_name, fbt)
+            if field_name in reg.fields:
+                raise ReginaldException(f"{fbt}: Field declared twice!")
+
+            bits_text = cells.get('bits', "")
+            if bits_text == "":
+                raise ReginaldException(f"{fbt}: Field has no bits!")
+            lsb, msb = csv_bits(bits_text, fbt)
+
+            reg.fields[field_name] = YAML_Field(
+                bits=lsb if lsb == msb else f"{msb}-{lsb}",
+                access=access,
+                brief=brief,
+            )
+            if reset_text != "":
+                field_resets[current][field_name] = (lsb, csv_int(reset_text, fbt + " -> reset"))
+
+        if len(registers) == 0:
+            raise ReginaldException(f"{bt}: No registers found!")
+
+        for reg_name, resets in field_resets.items():
+            if len(resets) == 0:
+                continue
+            reg = registers[reg_name]
+            if reg.reset_val is not None:
+                warn(f"{bt} -> {reg_name}: Register has both a register and field reset values, using the register reset value.")
+                continue
+            reg.reset_val = 0
+            for lsb, val in resets.values():
+                reg.reset_val |= val << lsb
+
+        map_name = self.config.map_name or path.splitext(path.basename(self.file_name))[0]
+        return YAML_RegisterMap(
+            map_name=map_name,
+            default_register_bitwidth=self.config.default_register_bitwidth,
+            registers=registers,
+        )
+
+    def _find_columns(self, header: List[str], bt: str) -> Dict[str, int]:
+        names = [h.strip().lower() for h in header]
+        configured = self.config.columns.model_dump()
+
+        columns = {}  # type: Dict[str, int]
+        for col, aliases in CSV_COLUMN_ALIASES.items():
+            if configured[col] is not None:
+                if configured[col].strip().lower() not in names:
+                    raise ReginaldException(f"{bt}: Column '{configured[col]}' (configured for {col}) not found in header!")
+                columns[col] = names.index(configured[col].strip().lower())
+                continue
+            for alias in aliases:
+                if alias in names:
+                    columns[col] = names.index(alias)
+                    break
+
+        for col in CSV_REQUIRED_COLUMNS:
+            if col not in columns:
+                raise ReginaldException(f"{bt}: No '{col}' column found in header! Name it in a column mapping config.")
+
+        return columns
+
+
+def csv_int(text: str, bt: str) -> int:
+    text = text.strip().lower().replace("_", "")
+    try:
+        if text.endswith('h'):
+            return int(text[:-1], 16)
+        if text.startswith("'h"):
+            return int(text[2:], 16)
+        if text.startswith("'b"):
+            return int(text[2:], 2)
+        return int(text, 0)
+    except ValueError:
+        raise ReginaldException(f"{bt}: Invalid number '{text}'!")
+
+
+def csv_bits(text: str, bt: str) -> Tuple[int, int]:
+    match = re.match(r"^\[?\s*([0-9]+)\s*(?:[:\-]\s*([0-9]+)\s*)?\]?$", text.strip())
+    if match is None:
+        raise ReginaldException(f"{bt}: Invalid bit range '{text}'!")
+    a = int(match.group(1))
+    b = int(match.group(2)) if match.group(2) is not None else a
+    return min(a, b), max(a, b)
+
+
+def csv_access(text: str, bt: str) -> Optional[List[str]]:
+    match text.strip().upper().replace("/", ""):
+        case "":
+            return None
+        case "R" | "RO":
+            return ['r']
+        case "W" | "WO":
+            return ['w']
+        case "RW":
+            return ['r', 'w']
+        case "RW1C" | "W1C":
+            return ['r', 'w1c'] if text.strip().upper().startswith("R") else ['w1c']
+        case "RW1S" | "W1S":
+            return ['r', 'w1s'] if text.strip().upper().startswith("R") else ['w1s']
+        case "RW1T" | "W1T":
+            return ['r', 'w1t'] if text.strip().upper().startswith("R") else ['w1t']
+        case "RC" | "RCLR":
+            return ['rc']
+        case other:
+            raise ReginaldException(f"{bt}: Unknown access '{other}'!")
+
+
+def csv_brief(text: str) -> Optional[str]:
+    brief = " ".join(text.split())
+    if len(brief) == 0:
+        return None
+    return brief
+
+
+def csv_identifier(text: str, bt: str) -> str:
+    name = re.sub(r"\s+", "_", text.strip())
+    if re.match(r"^[A-Za-z_][A-Za-z0-9_]*$", name) is None:
+        raise ReginaldException(f"{bt}: Invalid name '{text}'!")
+    return name
@@ -0,0 +1,268 @@
+import re
+import xml.etree.ElementTree as ET
+from typing import Dict, List, Optional, Tuple, Union
+
+from reginald.error import ReginaldException, warn
+from reginald.input.parse_yaml import (YAML_Field, YAML_RegEnumEntry,
+                                       YAML_Register, YAML_RegisterBlock,
+                                       YAML_RegisterMap)
+
+# Converts an IP-XACT (IEEE 1685-2014, and the register subset of 1685-2009)
+# component description into a reginald listing:
+#   - Every address block of every memory map becomes a register block with
+#     a single instance at the block's base address.
+#   - Registers with 'dim' are expanded into one register per element.
+#   - Register reset values are taken from the register (1685-2009) or
+#     assembled from the field resets (1685-2014).
+#   - Enumerated values become (inline) field enums.
+# XML namespaces are ignored, so both standard versions share one parser.
+
+
+def listing_from_ipxact_file(file_name: str) -> YAML_RegisterMap:
+    try:
+        tree = ET.parse(file_name)
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except ET.ParseError as e:
+        raise ReginaldException(f"{file_name}: Invalid XML: {e}")
+    except LookupError as e:
+        raise ReginaldException(f"{file_name}: Invalid XML: {e}")  # Unknown encoding in the XML declaration.
+
+    root = tree.getroot()
+    for element in root.iter():
+        element.tag = ipxact_strip_ns(element.tag)
+    return IPXACTConverter(root).convert()
+
+
+class IPXACTConverter:
+    def __init__(self, component: ET.Element):
+        self.component = component
+
+    def convert(self) -> YAML_RegisterMap:
+        if self.component.tag != 'component':
+            raise ReginaldException(f"ipxact: Expected 'component' root element, found '{self.component.tag}'")
+
+        bt = "ipxact -> component"
+        map_name = ipxact_text(self.component, 'name', bt)
+
+        registers = {}  # type: Dict[str, Union[YAML_Register, YAML_RegisterBlock]]
+        default_bitwidth = None  # type: Optional[int]
+
+        for memory_map in self.component.findall('memoryMaps/memoryMap'):
+            mbt = bt + f" -> {ipxact_text(memory_map, 'name', bt)}"
+            adr_unit_bits = ipxact_int_opt(memory_map, 'addressUnitBits', mbt) or 8
+
+            for address_block in memory_map.findall('addressBlock'):
+                name = ipxact_text(address_block, 'name', mbt)
+                abt = mbt + f" -> {name}"
+                base = ipxact_int(address_block, 'baseAddress', abt)
+                width = ipxact_int(address_block, 'width', abt)
+                if default_bitwidth is None:
+                    default_bitwidth = width
+
+                if name in registers:
+                    raise ReginaldException(f"{abt}: Address block {name} declared twice!")
+
+                templates = {}  # type: Dict[str, YAML_Register]
+                for reg in address_block.findall('register'):
+                    for reg_name, adr in ipxact_dim_expand(reg, width, adr_unit_bits, abt):
+                        template_name = "_" + reg_name
+                        if template_name in templates:
+                            raise ReginaldException(f"{abt}: Register {reg_name} declared twice!")
+                        templates[template_name] = self._convert_register(reg, adr, abt + f" -> {reg_name}")
+
+                if len(address_block.findall('registerFile')) > 0:
+                    warn(f"{abt}: Register files are not supported and are ignored.")
+
+                if len(templates) == 0:
+                    warn(f"{abt}: Address block has no registers and is ignored.")
+                    continue
+
+                registers[name] = YAML_RegisterBlock(
+                    instances={name: base},
+                    brief=ipxact_brief(address_block),
+                    registers=templates,
+                )
+
+        return YAML_RegisterMap(
+            map_name=map_name,
+            default_register_bitwidth=default_bitwidth or 32,
+            registers=registers,
+            brief=ipxact_brief(self.component),
+        )
+
+    def _convert_register(self, reg: ET.Element, adr: int, bt: str) -> YAML_Register:
+        bitwidth = ipxact_int(reg, 'size', bt)
+
+        # 1685-2009: Reset value given for the complete register.
+        reset_val = ipxact_int_opt(reg, 'reset/value', bt)
+
+        fields = {}
+        field_resets = []  # type: List[Optional[int]]
+        for field in reg.findall('field'):
+            field_name = ipxact_text(field, 'name', bt)
+            fbt = bt + f" -> {field_name}"
+            fields[field_name] = self._convert_field(field, fbt)
+
+            # 1685-2014: Reset values given per field.
+            field_reset = ipxact_int_opt(field, 'resets/reset/value', fbt)
+            if field_reset is None:
+                field_resets.append(None)
+            else:
+                lsb = ipxact_int(field, 'bitOffset', fbt)
+                field_resets.append(field_reset << lsb)
+
+        if reset_val is None and len(field_resets) > 0 and None not in field_resets:
+            reset_val = 0
+            for field_reset in field_resets:
+                assert field_reset is not None
+                reset_val |= field_reset
+
+        return YAML_Register(
+            adr=adr,
+            bitwidth=bitwidth,
+            reset_val=reset_val,
+            access=ipxact_access(reg, bt),
+            brief=ipxact_brief(reg),
+            fields=fields,
+        )
+
+    def _convert_field(self, field: ET.Element, bt: str) -> YAML_Field:
+        lsb = ipxact_int(field, 'bitOffset', bt)
+        msb = lsb + ipxact_int(field, 'bitWidth', bt) - 1
+
+        enum = None
+        for value in field.findall('enumeratedValues/enumeratedValue'):
+            name = ipxact_text(value, 'name', bt)
+            if enum is None:
+                enum = {}
+            enum[name] = YAML_RegEnumEntry(val=ipxact_int(value, 'value', bt + f" -> {name}"), brief=ipxact_brief(value))
+
+        if lsb == msb:
+            bits = lsb  # type: Union[int, str]
+        else:
+            bits = f"{msb}-{lsb}"
+
+        # Valid range of written values:
+        constraint = field.find('writeValueConstraint')
+        if constraint is not None:
+            min_val = ipxact_int_opt(constraint, 'minimum', bt)
+            max_val = ipxact_int_opt(constraint, 'maximum', bt)
+            if any(val is not None and val < 0 for val in [min_val, max_val]):
+                raise ReginaldException(f"{bt}: Negative writeValueConstraint!")
+        else:
+            min_val, max_val = None, None
+
+        return YAML_Field(
+            bits=bits,
+            access=ipxact_access(field, bt),
+            brief=ipxact_brief(field),
+            enum=enum,
+            min=min_val,
+            max=max_val,
+        )
+
+
+def ipxact_strip_ns(tag: str) -> str:
+    if tag.startswith('{'):
+        return tag.split('}', 1)[1]
+    return tag
+
+
+def ipxact_text(element: ET.Element, tag: str, bt: str) -> str:
+    child = element.find(tag)
+    if child is None or child.text is None:
+        raise ReginaldException(f"{bt}: Missing '{tag}'!")
+    return child.text.strip()
+
+
+def ipxact_parse_int(text: str, bt: str) -> int:
+    # Accepts plain decimal, C-style hex/binary, and Verilog-style ([width]'[base]digits) literals.
+    text = text.strip().lower().replace('_', '')
+    try:
+        verilog = re.match(r"^([0-9]*)'([hdbo])([0-9a-f]+)$", text)
+        if verilog is not None:
+            base = {'h': 16, 'd': 10, 'b': 2, 'o': 8}[verilog.group(2)]
+            return int(verilog.group(3), base)
+        if text.startswith('0x'):
+            return int(text[2:], 16)
+        if text.startswith('0b'):
+            return int(text[2:], 2)
+        return int(text, 10)
+    except ValueError:
+        raise ReginaldException(f"{bt}: Invalid number '{text}'!")
+
+
+def ipxact_int(element: ET.Element, tag: str, bt: str) -> int:
+    return ipxact_parse_int(ipxact_text(element, tag, bt), bt + f" -> {tag}")
+
+
+def ipxact_int_opt(element: ET.Element, tag: str, bt: str) -> Optional[int]:
+    child = element.find(tag)
+    if child is None or child.text is None:
+        return None
+    return ipxact_parse_int(child.text, bt + f" -> {tag}")
+
+
+def ipxact_brief(element: ET.Element) -> Optional[str]:
+    desc = element.find('description')
+    if desc is None or desc.text is None:
+        return None
+    brief = " ".join(desc.text.split())
+    if len(brief) == 0:
+        return None
+    return brief
+
+
+def ipxact_access(element: ET.Element, bt: str) -> Optional[List[str]]:
+    child = element.find('access')
+    if child is None or child.text is None:
+        access = None
+    else:
+        match child.text.strip():
+            case 'read-only':
+                access = ['r']
+            case 'write-only' | 'writeOnce':
+                access = ['w']
+            case 'read-write' | 'read-writeOnce':
+                access = ['r', 'w']
+            case other:
+                raise ReginaldException(f"{bt}: Unknown access '{other}'!")
+
+    # 'modifiedWriteValue' and 'readAction' refine the write/read access:
+    write_mode = ipxact_side_effect(element, 'modifiedWriteValue', {'oneToClear': 'w1c', 'oneToSet': 'w1s', 'oneToToggle': 'w1t'}, bt)
+    read_mode = ipxact_side_effect(element, 'readAction', {'clear': 'rc'}, bt)
+    if write_mode is None and read_mode is None:
+        return access
+    if access is None:
+        access = ['r', 'w']
+    return [write_mode if mode == 'w' and write_mode is not None else
+            read_mode if mode == 'r' and read_mode is not None else mode for mode in access]
+
+
+def ipxact_side_effect(element: ET.Element, tag: str, modes: Dict[str, str], bt: str) -> Optional[str]:
+    child = element.find(tag)
+    if child is None or child.text is None or child.text.strip() == 'modify':
+        return None
+    if child.text.strip() not in modes:
+        warn(f"{bt}: Unsupported {tag} '{child.text.strip()}', ignoring.")
+        return None
+    return modes[child.text.strip()]
+
+
+def ipxact_dim_expand(reg: ET.Element, default_size: int, adr_unit_bits: int, bt: str) -> List[Tuple[str, int]]:
+    # Returns the name and address offset of every element of a (possibly dim'ed) register.
+    name = ipxact_text(reg, 'name', bt)
+    offset = ipxact_int(reg, 'addressOffset', bt + f" -> {name}")
+
+    dims = reg.findall('dim')
+    if len(dims) == 0:
+        return [(name, offset)]
+    if len(dims) > 1:
+        raise ReginaldException(f"{bt} -> {name}: Multi-dimensional register arrays are not supported!")
+
+    dim = ipxact_parse_int(dims[0].text or "", bt + f" -> {name} -> dim")
+    size = ipxact_int_opt(reg, 'size', bt) or default_size
+    stride = ipxact_int_opt(reg, 'stride', bt) or (size + adr_unit_bits - 1) // adr_unit_bits
+
+    return [(f"{name}{i}", offset + i * stride) for i in range(dim)]
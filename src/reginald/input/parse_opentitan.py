@@ -0,0 +1,470 @@
+import re
+from os import path
+from typing import Any, Dict, List, Optional, Tuple, Union
+
+from reginald.error import ReginaldException, warn
+from reginald.input.parse_yaml import (YAML_Field, YAML_RegEnumEntry,
+                                       YAML_Register, YAML_RegisterBlock,
+                                       YAML_RegisterMap)
+
+# Reads register descriptions in the HJSON dialect of OpenTitan's 'regtool':
+#   - Registers are laid out in order, one register width apart, starting at
+#     0. 'skipto' and 'reserved' entries move the address, 'window' entries
+#     are skipped over (with a warning).
+#   - 'swaccess' becomes the access mode. Side effects (clear on read, write
+#     one to clear, ...) cannot be represented and are reduced to plain
+#     read/write access. 'hwaccess' describes the hardware side and is
+#     ignored.
+#   - A 'multireg' becomes a register block with one instance per register.
+#     Compact multiregs (a single narrow field, packed several times into
+#     each register) become individual registers with one field per copy.
+#     Counts may refer to entries of 'param_list'.
+#   - Field reset values ('resval') are combined into the register reset
+#     value. Field enums become (inline) field enums.
+# All other keys (clocking, interrupts, alerts, hwext, tags, ...) are ignored.
+
+OT_SWACCESS = {
+    'ro': ['r'],
+    'rc': ['rc'],
+    'rw': ['r', 'w'],
+    'rw0c': ['r', 'w'],
+    'rw1c': ['r', 'w1c'],
+    'rw1s': ['r', 'w1s'],
+    'wo': ['w'],
+    'r0w1c': ['w1c'],
+    'none': [],
+}
+
+
+def listing_from_opentitan_file(file_name: str) -> YAML_RegisterMap:
+    try:
+        with open(file_name) as f:
+            text = f.read()
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+
+    data = HJSONParser(text, file_name).parse()
+    if not isinstance(data, dict):
+        raise ReginaldException(f"{file_name}: Expected an object at the top level!")
+
+    return OpenTitanConverter(data, file_name).convert()
+
+
+class OpenTitanConverter:
+    def __init__(self, data: Dict[str, Any], file_name: str):
+        self.data = data
+        self.file_name = file_name
+        self.params = {}  # type: Dict[str, int]
+
+    def convert(self) -> YAML_RegisterMap:
+        bt = "opentitan"
+        map_name = ot_str(self.data, 'name', bt)
+        bt += f" -> {map_name}"
+
+        regwidth = ot_int(self.data.get('regwidth', 32), bt + " -> regwidth")
+        if regwidth % 8 != 0:
+            raise ReginaldException(f"{bt}: Register width must be a multiple of 8!")
+        self.regbytes = regwidth // 8
+
+        param_list = self.data.get('param_list', [])
+        for param in param_list if isinstance(param_list, list) else []:
+            if isinstance(param, dict) and 'name' in param and 'default' in param:
+                try:
+                    self.params[param['name']] = ot_int(param['default'], bt)
+                except ReginaldException:
+                    pass  # Non-integer parameters are not used for counts.
+
+        reg_list = self.data.get('registers', [])
+        if isinstance(reg_list, dict):
+            # Devices with several bus interfaces list registers by interface:
+            if len(reg_list) > 1:
+                warn(f"{bt}: Registers of several interfaces, only using the first one ({next(iter(reg_list))}).")
+            reg_list = next(iter(reg_list.values()), [])
+        if not isinstance(reg_list, list):
+            raise ReginaldException(f"{bt} -> registers: Expected a list!")
+
+        registers = {}  # type: Dict[str, Union[YAML_Register, YAML_RegisterBlock]]
+        adr = 0
+        for entry in reg_list:
+            if not isinstance(entry, dict):
+                raise ReginaldException(f"{bt} -> registers: Expected a list of objects!")
+
+            if 'skipto' in entry:
+                new_adr = ot_int(entry['skipto'], bt + " -> skipto")
+                if new_adr < adr:
+                    raise ReginaldException(f"{bt}: skipto 0x{new_adr:X} moves backwards (currently at 0x{adr:X})!")
+                adr = new_adr
+            elif 'reserved' in entry:
+                adr += ot_int(entry['reserved'], bt + " -> reserved") * self.regbytes
+            elif 'window' in entry:
+                adr = self._skip_window(ot_object(entry['window'], bt + " -> window"), adr, bt)
+            elif 'multireg' in entry:
+                adr = self._convert_multireg(ot_object(entry['multireg'], bt + " -> multireg"), adr, registers, regwidth, bt)
+            elif 'name' in entry:
+                name = ot_str(entry, 'name', bt)
+                self._add(registers, name, self._convert_register(entry, adr, bt + f" -> {name}"), bt)
+                adr += self.regbytes
+            else:
+                warn(f"{bt}: Ignoring unknown register list entry with keys {', '.join(entry.keys())}.")
+
+        if len(registers) == 0:
+            raise ReginaldException(f"{bt}: No registers found!")
+
+        return YAML_RegisterMap(
+            map_name=map_name,
+            default_register_bitwidth=regwidth,
+            registers=registers,
+            brief=ot_brief(self.data),
+        )
+
+    def _add(self, registers: Dict, name: str, reg: Union[YAML_Register, YAML_RegisterBlock], bt: str):
+        if name in registers:
+            raise ReginaldException(f"{bt}: Register {name} declared twice!")
+        registers[name] = reg
+
+    def _skip_window(self, window: Dict[str, Any], adr: int, bt: str) -> int:
+        name = window.get('name', '?')
+        size = ot_int(window.get('items', 0), bt + f" -> {name} -> items") * self.regbytes
+        # Windows are aligned to their size, rounded up to a power of two:
+        align = 1 << max(0, (size - 1).bit_length())
+        adr = (adr + align - 1) // align * align
+        warn(f"{bt} -> {name}: Memory windows are not supported, skipping 0x{adr:X}-0x{adr + size - 1:X}.")
+        return adr + size
+
+    def _convert_multireg(self, multireg: Dict[str, Any], adr: int, registers: Dict, regwidth: int, bt: str) -> int:
+        name = ot_str(multireg, 'name', bt)
+        mbt = bt + f" -> {name}"
+        count = self._count(multireg.get('count'), mbt)
+
+        fields = ot_objects(multireg.get('fields', []), mbt + " -> fields")
+        compact = str(multireg.get('compact', 'true')).lower() != 'false'
+        if compact and len(fields) == 1:
+            lsb, msb = ot_bits(fields[0].get('bits'), mbt)
+            per_reg = regwidth // (msb + 1)
+        else:
+            per_reg = 1
+
+        if per_reg > 1:
+            # Compact: Replicate the field within each register.
+            field_name = fields[0].get('name', name)
+            width = msb + 1
+            for reg_idx in range((count + per_reg - 1) // per_reg):
+                reg_fields = []
+                for i in range(reg_idx * per_reg, min(count, (reg_idx + 1) * per_reg)):
+                    offset = (i - reg_idx * per_reg) * width
+                    reg_fields.append(fields[0] | {'name': f"{field_name}_{i}", 'bits': f"{msb + offset}:{lsb + offset}"})
+                reg_name = f"{name}_{reg_idx}"
+                self._add(registers, reg_name, self._convert_register(multireg | {'fields': reg_fields}, adr, mbt), bt)
+                adr += self.regbytes
+            return adr
+
+        template = self._convert_register(multireg, 0, mbt)
+        instances = {}
+        for i in range(count):
+            instances[f"{name}_{i}"] = adr
+            adr += self.regbytes
+        self._add(registers, name + "_n", YAML_RegisterBlock(
+            instances=instances,
+            brief=template.brief,
+            registers={"": template},
+        ), bt)
+        return adr
+
+    def _count(self, count: Any, bt: str) -> int:
+        if count is None:
+            raise ReginaldException(f"{bt}: Multireg has no count!")
+        if isinstance(count, str) and count in self.params:
+            return self.params[count]
+        try:
+            return ot_int(count, bt + " -> count")
+        except ReginaldException:
+            raise ReginaldException(f"{bt}: Count '{count}' is neither a number nor a known parameter!")
+
+    def _convert_register(self, reg: Dict[str, Any], adr: int, bt: str) -> YAML_Register:
+        access = ot_access(reg, bt)
+
+        fields = {}
+        reset_val = 0
+        reset_known = True
+        reg_fields = ot_objects(reg.get('fields', []), bt + " -> fields")
+        for field in reg_fields:
+            # A single field without a name is named after the register:
+            field_name = field.get('name', reg.get('name') if len(reg_fields) == 1 else None)
+            if field_name is None:
+                raise ReginaldException(f"{bt}: Field without a name!")
+            fbt = bt + f" -> {field_name}"
+            if field_name in fields:
+                raise ReginaldException(f"{fbt}: Field declared twice!")
+
+            lsb, msb = ot_bits(field.get('bits'), fbt)
+            field_access = ot_access(field, fbt)
+            fields[field_name] = YAML_Field(
+                bits=lsb if lsb == msb else f"{msb}-{lsb}",
+                access=field_access if field_access != access else None,
+                brief=ot_brief(field),
+                enum=self._convert_enum(field.get('enum'), fbt),
+            )
+
+            # Fields reset to zero unless specified, 'x' is an unknown reset value:
+            resval = field.get('resval', 0)
+            if str(resval).lower() == 'x':
+                reset_known = False
+            else:
+                reset_val |= ot_int(resval, fbt + " -> resval") << lsb
+
+        if 'resval' in reg:
+            reset_val = ot_int(reg['resval'], bt + " -> resval")
+            reset_known = True
+
+        return YAML_Register(
+            adr=adr,
+            access=access,
+            reset_val=reset_val if reset_known and len(fields) > 0 else None,
+            brief=ot_brief(reg),
+            fields=fields,
+        )
+
+    def _convert_enum(self, enum: Optional[List[Dict[str, Any]]], bt: str) -> Optional[Dict[str, YAML_RegEnumEntry]]:
+        if enum is None or len(ot_objects(enum, bt + " -> enum")) == 0:
+            return None
+        entries = {}
+        for entry in enum:
+            name = ot_str(entry, 'name', bt)
+            entries[ot_identifier(name)] = YAML_RegEnumEntry(val=ot_int(entry.get('value'), bt + f" -> {name}"),
+                                                             brief=ot_brief(entry))
+        return entries
+
+
+def ot_object(value: Any, bt: str) -> Dict[str, Any]:
+    if not isinstance(value, dict):
+        raise ReginaldException(f"{bt}: Expected an object!")
+    return value
+
+
+def ot_objects(value: Any, bt: str) -> List[Dict[str, Any]]:
+    if not isinstance(value, list) or not all(isinstance(v, dict) for v in value):
+        raise ReginaldException(f"{bt}: Expected a list of objects!")
+    return value
+
+
+def ot_str(obj: Dict[str, Any], key: str, bt: str) -> str:
+    value = obj.get(key)
+    if not isinstance(value, str):
+        raise ReginaldException(f"{bt}: Missing '{key}'!")
+    return value
+
+
+def ot_int(value: Any, bt: str) -> int:
+    if isinstance(value, bool):
+        raise ReginaldException(f"{bt}: Invalid number '{value}'!")
+    if isinstance(value, int):
+        return value
+    try:
+        return int(str(value).strip().replace("_", ""), 0)
+    except ValueError:
+        raise ReginaldException(f"{bt}: Invalid number '{value}'!")
+
+
+def ot_bits(value: Any, bt: str) -> Tuple[int, int]:
+    if value is None:
+        raise ReginaldException(f"{bt}: Field has no bits!")
+    match = re.match(r"^([0-9]+)(?::([0-9]+))?$", str(value).strip())
+    if match is None:
+        raise ReginaldException(f"{bt}: Invalid bits '{value}'!")
+    msb = int(match.group(1))
+    lsb = int(match.group(2)) if match.group(2) is not None else msb
+    return lsb, msb
+
+
+def ot_access(obj: Dict[str, Any], bt: str) -> Optional[List[str]]:
+    swaccess = obj.get('swaccess')
+    if swaccess is None:
+        return None
+    if not isinstance(swaccess, str) or swaccess not in OT_SWACCESS:
+        raise ReginaldException(f"{bt}: Unknown swaccess '{swaccess}'!")
+    return OT_SWACCESS[swaccess]
+
+
+def ot_brief(obj: Dict[str, Any]) -> Optional[str]:
+    desc = obj.get('desc')
+    if not isinstance(desc, str):
+        return None
+    brief = " ".join(desc.split())
+    if len(brief) == 0:
+        return None
+    return brief
+
+
+def ot_identifier(name: str) -> str:
+    return re.sub(r"[^A-Za-z0-9_]", "_", name)
+
+
+class HJSONParser:
+    # Minimal HJSON reader, covering what regtool files use: Comments ('#',
+    # '//', '/* */'), optional commas, unquoted keys, quoteless strings
+    # (until the end of the line), and multi-line ''' strings.
+
+    PUNCTUATORS = "{}[],:"
+
+    def __init__(self, text: str, file_name: str):
+        self.text = text
+        self.pos = 0
+        self.file_name = file_name
+
+    def error(self, msg: str) -> ReginaldException:
+        line = self.text.count("\n", 0, self.pos) + 1
+        return ReginaldException(f"{path.basename(self.file_name)}:{line}: Invalid HJSON: {msg}")
+
+    def parse(self) -> Any:
+        self.skip_ws()
+        if self.peek() == '{':
+            value = self.parse_value()
+        else:
+            # Root braces may be omitted:
+            value = self.parse_members(None)
+        self.skip_ws()
+        if self.pos < len(self.text):
+            raise self.error("Trailing content")
+        return value
+
+    def peek(self) -> str:
+        return self.text[self.pos] if self.pos < len(self.text) else ""
+
+    def skip_ws(self, newlines: bool = True):
+        while self.pos < len(self.text):
+            c = self.text[self.pos]
+            if c == "\n" and not newlines:
+                return
+            if c.isspace():
+                self.pos += 1
+            elif c == '#' or self.text.startswith("//", self.pos):
+                end = self.text.find("\n", self.pos)
+                self.pos = len(self.text) if end == -1 else end
+            elif self.text.startswith("/*", self.pos):
+                end = self.text.find("*/", self.pos)
+                if end == -1:
+                    raise self.error("Unterminated comment")
+                self.pos = end + 2
+            else:
+                return
+
+    def skip_separator(self):
+        self.skip_ws()
+        if self.peek() == ',':
+            self.pos += 1
+            self.skip_ws()
+
+    def parse_members(self, close: Optional[str]) -> Dict[str, Any]:
+        members = {}
+        while True:
+            self.skip_ws()
+            if self.peek() == close or (close is None and self.pos >= len(self.text)):
+                if close is not None:
+                    self.pos += 1
+                return members
+            if self.pos >= len(self.text):
+                raise self.error("Unterminated object")
+
+            key = self.parse_key()
+            self.skip_ws()
+            if self.peek() != ':':
+                raise self.error(f"Expected ':' after key '{key}'")
+            self.pos += 1
+            members[key] = self.parse_value()
+            self.skip_separator()
+
+    def parse_key(self) -> str:
+        if self.peek() in "\"'":
+            return self.parse_quoted()
+        start = self.pos
+        while self.pos < len(self.text) and not self.text[self.pos].isspace() and self.text[self.pos] not in self.PUNCTUATORS:
+            self.pos += 1
+        if start == self.pos:
+            raise self.error("Expected a key")
+        return self.text[start:self.pos]
+
+    def parse_value(self) -> Any:
+        self.skip_ws()
+        c = self.peek()
+        if c == '{':
+            self.pos += 1
+            return self.parse_members('}')
+        if c == '[':
+            self.pos += 1
+            items = []
+            while True:
+                self.skip_ws()
+                if self.peek() == ']':
+                    self.pos += 1
+                    return items
+                if self.pos >= len(self.text):
+                    raise self.error("Unterminated array")
+                items.append(self.parse_value())
+                self.skip_separator()
+        if self.text.startswith("'''", self.pos):
+            return self.parse_multiline()
+        if c in "\"'":
+            return self.parse_quoted()
+        if c == "" or c in ",:]}":
+            raise self.error("Expected a value")
+        return self.parse_quoteless()
+
+    def parse_quoted(self) -> str:
+        quote = self.text[self.pos]
+        self.pos += 1
+        result = []
+        while True:
+            if self.pos >= len(self.text):
+                raise self.error("Unterminated string")
+            c = self.text[self.pos]
+            self.pos += 1
+            if c == quote:
+                return "".join(result)
+            if c == "\\":
+                esc = self.text[self.pos:self.pos + 1]
+                self.pos += 1
+                if esc == 'u':
+                    result.append(chr(int(self.text[self.pos:self.pos + 4], 16)))
+                    self.pos += 4
+                else:
+                    result.append({'n': "\n", 't': "\t", 'r': "\r", 'b': "\b", 'f': "\f"}.get(esc, esc))
+            else:
+                result.append(c)
+
+    def parse_multiline(self) -> str:
+        # Indentation of the opening quotes is removed from every line:
+        indent = self.pos - (self.text.rfind("\n", 0, self.pos) + 1)
+        self.pos += 3
+        end = self.text.find("'''", self.pos)
+        if end == -1:
+            raise self.error("Unterminated multi-line string")
+        lines = self.text[self.pos:end].split("\n")
+        self.pos = end + 3
+        if len(lines) > 1 and lines[0].strip() == "":
+            lines = lines[1:]
+        if len(lines) > 1 and lines[-1].strip() == "":
+            lines = lines[:-1]
+        return "\n".join(line[indent:] if line[:indent].strip() == "" else line.lstrip() for line in lines)
+
+    def parse_quoteless(self) -> Any:
+        # Numbers and literals end at a punctuator, strings at the end of the line:
+        match = re.compile(r"(-?[0-9][0-9a-fA-FxX_.]*|true|false|null)\s*(?=[,\]}\n#]|//|/\*|$)").match(self.text, self.pos)
+        if match is not None:
+            token = match.group(1)
+            literals = {'true': True, 'false': False, 'null': None}
+            if token in literals:
+                self.pos = match.end(1)
+                return literals[token]
+            for parse in [lambda t: int(t, 0), float]:
+                try:
+                    value = parse(token)
+                    self.pos = match.end(1)
+                    return value
+                except ValueError:
+                    pass
+
+        end = self.text.find("\n", self.pos)
+        end = len(self.text) if end == -1 else end
+        value = self.text[self.pos:end].strip()
+        self.pos = end
+        return value
@@ -0,0 +1,327 @@
+import re
+import xml.etree.ElementTree as ET
+from typing import Dict, List, Optional, Tuple, Union
+
+from reginald.error import ReginaldException, warn
+from reginald.input.parse_yaml import (YAML_Field, YAML_RegEnumEntry,
+                                       YAML_Register, YAML_RegisterBlock,
+                                       YAML_RegisterMap)
+
+# Converts a CMSIS-SVD device description into a reginald listing:
+#   - Every peripheral becomes a register block. Peripherals derived from
+#     another peripheral (without registers of their own) become additional
+#     instances of that block.
+#   - Clusters are flattened into their peripheral.
+#   - Registers and clusters with 'dim' are expanded into one register per
+#     element.
+#   - Enumerated values become (inline) field enums.
+#   - The device's addressUnitBits become the map's address unit.
+
+
+def listing_from_svd_file(file_name: str) -> YAML_RegisterMap:
+    try:
+        tree = ET.parse(file_name)
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except ET.ParseError as e:
+        raise ReginaldException(f"{file_name}: Invalid XML: {e}")
+    except LookupError as e:
+        raise ReginaldException(f"{file_name}: Invalid XML: {e}")  # Unknown encoding in the XML declaration.
+
+    return SVDConverter(tree.getroot()).convert()
+
+
+class SVDConverter:
+    def __init__(self, device: ET.Element):
+        self.device = device
+
+    def convert(self) -> YAML_RegisterMap:
+        if self.device.tag != 'device':
+            raise ReginaldException(f"svd: Expected 'device' root element, found '{self.device.tag}'")
+
+        bt = "svd -> device"
+        map_name = svd_text(self.device, 'name', bt)
+        default_bitwidth = svd_int_opt(self.device, 'size', bt) or 32
+        address_unit_bits = svd_int_opt(self.device, 'addressUnitBits', bt) or 8
+
+        registers = {}  # type: Dict[str, Union[YAML_Register, YAML_RegisterBlock]]
+        block_of = {}  # type: Dict[str, str]
+
+        for peripheral in self.device.findall('peripherals/peripheral'):
+            name = svd_text(peripheral, 'name', bt)
+            pbt = bt + f" -> {name}"
+            base = svd_int(peripheral, 'baseAddress', pbt)
+
+            # Peripherals derived from another peripheral without registers of their own
+            # are instances of the same register block:
+            derived_from = peripheral.get('derivedFrom')
+            if derived_from is not None and peripheral.find('registers') is None:
+                if derived_from not in block_of:
+                    raise ReginaldException(f"{pbt}: Derived from unknown (or not previously declared) peripheral {derived_from}!")
+                block_of[name] = block_of[derived_from]
+                block = registers[block_of[name]]
+                assert isinstance(block, YAML_RegisterBlock)
+                block.instances[name] = base
+                continue
+
+            size = svd_int_opt(peripheral, 'size', pbt) or default_bitwidth
+            templates = {}  # type: Dict[str, YAML_Register]
+            for reg_name, reg in self._convert_register_list(peripheral.find('registers'), size, pbt):
+                template_name = "_" + reg_name
+                if template_name in templates:
+                    raise ReginaldException(f"{pbt}: Register {reg_name} declared twice!")
+                templates[template_name] = reg
+
+            if len(templates) == 0:
+                warn(f"{pbt}: Peripheral has no registers and is ignored.")
+                continue
+
+            block_of[name] = name
+            registers[name] = YAML_RegisterBlock(
+                instances={name: base},
+                brief=svd_brief(peripheral),
+                registers=templates,
+            )
+
+        return YAML_RegisterMap(
+            map_name=map_name,
+            default_register_bitwidth=default_bitwidth,
+            address_unit_bits=address_unit_bits,
+            registers=registers,
+            brief=svd_brief(self.device),
+        )
+
+    def _convert_register_list(self, registers: Optional[ET.Element], size: int, bt: str,
+                               offset: int = 0, prefix: str = "") -> List[Tuple[str, YAML_Register]]:
+        result = []
+        if registers is None:
+            return result
+
+        for element in registers:
+            if element.tag == 'register':
+                for name, adr_offset in svd_dim_expand(element, bt):
+                    rbt = bt + f" -> {name}"
+                    result.append((prefix + name, self._convert_register(element, offset + adr_offset, size, rbt)))
+            elif element.tag == 'cluster':
+                for name, adr_offset in svd_dim_expand(element, bt):
+                    cluster_size = svd_int_opt(element, 'size', bt) or size
+                    result.extend(self._convert_register_list(element, cluster_size, bt + f" -> {name}",
+                                                              offset + adr_offset, prefix + name + "_"))
+
+        return result
+
+    def _convert_register(self, reg: ET.Element, adr: int, size: int, bt: str) -> YAML_Register:
+        bitwidth = svd_int_opt(reg, 'size', bt) or size
+        reset_val = svd_int_opt(reg, 'resetValue', bt)
+        if reset_val is not None:
+            # Reset values are frequently given with more bits than the register has:
+            reset_val = reset_val & ((1 << bitwidth) - 1)
+
+        fields = {}
+        for field in reg.findall('fields/field'):
+            field_name = svd_text(field, 'name', bt)
+            if field.find('dim') is not None:
+                raise ReginaldException(f"{bt} -> {field_name}: Field arrays are not supported!")
+            fields[field_name] = self._convert_field(field, bt + f" -> {field_name}")
+
+        return YAML_Register(
+            adr=adr,
+            bitwidth=bitwidth,
+            reset_val=reset_val,
+            access=svd_access(reg, bt),
+            brief=svd_brief(reg),
+            fields=fields,
+        )
+
+    def _convert_field(self, field: ET.Element, bt: str) -> YAML_Field:
+        lsb, msb = svd_field_position(field, bt)
+
+        enum = None
+        for enumerated_values in field.findall('enumeratedValues'):
+            entries = {}
+            default = None
+            for value in enumerated_values.findall('enumeratedValue'):
+                name = svd_text(value, 'name', bt)
+                if value.find('value') is None:
+                    # 'isDefault' entries cover all other values:
+                    default = value
+                    continue
+                text = svd_text(value, 'value', bt)
+                if svd_has_dont_care(text):
+                    warn(f"{bt} -> {name}: Enumerated values with don't-care bits ('{text}') are not supported, skipping.")
+                    continue
+                entries[name] = YAML_RegEnumEntry(val=svd_parse_int(text, bt + f" -> {name}"), brief=svd_brief(value))
+            if default is not None and len(entries) > 0:
+                # Catch-all entry, using the first value without an entry (if any):
+                used = set(entry.val for entry in entries.values())
+                unused = next((val for val in range(2 ** (msb - lsb + 1)) if val not in used), None)
+                if unused is not None:
+                    entries[svd_text(default, 'name', bt)] = YAML_RegEnumEntry(val=unused, catch_all=True, brief=svd_brief(default))
+            if len(entries) > 0:
+                if enum is not None:
+                    warn(f"{bt}: Field has more than one set of enumerated values, only using the first one.")
+                    break
+                enum = entries
+
+        if lsb == msb:
+            bits = lsb  # type: Union[int, str]
+        else:
+            bits = f"{msb}-{lsb}"
+
+        # Valid range of written values:
+        min_val, max_val = None, None
+        write_range = field.find('writeConstraint/range')
+        if write_range is not None:
+            min_val = svd_parse_int(svd_text(write_range, 'minimum', bt), bt)
+            max_val = svd_parse_int(svd_text(write_range, 'maximum', bt), bt)
+            if min_val < 0 or max_val < 0:
+                raise ReginaldException(f"{bt}: Negative writeConstraint range!")
+
+        return YAML_Field(
+            bits=bits,
+            access=svd_access(field, bt),
+            brief=svd_brief(field),
+            enum=enum,
+            min=min_val,
+            max=max_val,
+        )
+
+
+def svd_text(element: ET.Element, tag: str, bt: str) -> str:
+    child = element.find(tag)
+    if child is None or child.text is None:
+        raise ReginaldException(f"{bt}: Missing '{tag}'!")
+    return child.text.strip()
+
+
+def svd_parse_int(text: str, bt: str) -> int:
+    text = text.strip().lower()
+    try:
+        if text.startswith('#'):
+            return int(text[1:], 2)
+        if text.startswith('0b'):
+            return int(text[2:], 2)
+        if text.startswith('0x'):
+            return int(text[2:], 16)
+        return int(text, 10)
+    except ValueError:
+        raise ReginaldException(f"{bt}: Invalid number '{text}'!")
+
+
+def svd_has_dont_care(text: str) -> bool:
+    text = text.strip().lower()
+    if text.startswith('#'):
+        return 'x' in text[1:]
+    if text.startswith('0b'):
+        return 'x' in text[2:]
+    return False
+
+
+def svd_int(element: ET.Element, tag: str, bt: str) -> int:
+    return svd_parse_int(svd_text(element, tag, bt), bt + f" -> {tag}")
+
+
+def svd_int_opt(element: ET.Element, tag: str, bt: str) -> Optional[int]:
+    child = element.find(tag)
+    if child is None or child.text is None:
+        return None
+    return svd_parse_int(child.text, bt + f" -> {tag}")
+
+
+def svd_brief(element: ET.Element) -> Optional[str]:
+    desc = element.find('description')
+    if desc is None or desc.text is None:
+        return None
+    brief = " ".join(desc.text.split())
+    if len(brief) == 0:
+        return None
+    return brief
+
+
+def svd_access(element: ET.Element, bt: str) -> Optional[List[str]]:
+    child = element.find('access')
+    if child is None or child.text is None:
+        access = None
+    else:
+        match child.text.strip():
+            case 'read-only':
+                access = ['r']
+            case 'write-only' | 'writeOnce':
+                access = ['w']
+            case 'read-write' | 'read-writeOnce':
+                access = ['r', 'w']
+            case other:
+                raise ReginaldException(f"{bt}: Unknown access '{other}'!")
+
+    # 'modifiedWriteValues' and 'readAction' refine the write/read access:
+    write_mode = svd_side_effect(element, 'modifiedWriteValues', {'oneToClear': 'w1c', 'oneToSet': 'w1s', 'oneToToggle': 'w1t'}, bt)
+    read_mode = svd_side_effect(element, 'readAction', {'clear': 'rc'}, bt)
+    if write_mode is None and read_mode is None:
+        return access
+    if access is None:
+        access = ['r', 'w']
+    return [write_mode if mode == 'w' and write_mode is not None else
+            read_mode if mode == 'r' and read_mode is not None else mode for mode in access]
+
+
+def svd_side_effect(element: ET.Element, tag: str, modes: Dict[str, str], bt: str) -> Optional[str]:
+    child = element.find(tag)
+    if child is None or child.text is None or child.text.strip() == 'modify':
+        return None
+    if child.text.strip() not in modes:
+        warn(f"{bt}: Unsupported {tag} '{child.text.strip()}', ignoring.")
+        return None
+    return modes[child.text.strip()]
+
+
+def svd_field_position(field: ET.Element, bt: str) -> Tuple[int, int]:
+    if field.find('bitOffset') is not None:
+        lsb = svd_int(field, 'bitOffset', bt)
+        width = svd_int_opt(field, 'bitWidth', bt) or 1
+        return lsb, lsb + width - 1
+
+    if field.find('lsb') is not None:
+        return svd_int(field, 'lsb', bt), svd_int(field, 'msb', bt)
+
+    if field.find('bitRange') is not None:
+        match = re.match(r"^\[([0-9]+):([0-9]+)\]$", svd_text(field, 'bitRange', bt))
+        if match is None:
+            raise ReginaldException(f"{bt}: Invalid bitRange!")
+        return int(match.group(2)), int(match.group(1))
+
+    raise ReginaldException(f"{bt}: Field has no position!")
+
+
+def svd_dim_expand(element: ET.Element, bt: str) -> List[Tuple[str, int]]:
+    # Returns the name and address offset of every element of a (possibly dim'ed) register or cluster.
+    name = svd_text(element, 'name', bt)
+    offset = svd_int_opt(element, 'addressOffset', bt) or 0
+
+    if element.find('dim') is None:
+        return [(name, offset)]
+
+    dim = svd_int(element, 'dim', bt)
+    increment = svd_int(element, 'dimIncrement', bt)
+
+    index_text = element.find('dimIndex')
+    if index_text is not None and index_text.text is not None:
+        text = index_text.text.strip()
+        range_match = re.match(r"^([0-9]+)-([0-9]+)$", text)
+        if range_match is not None:
+            indices = [str(i) for i in range(int(range_match.group(1)), int(range_match.group(2)) + 1)]
+        else:
+            indices = [i.strip() for i in text.split(',')]
+    else:
+        indices = [str(i) for i in range(dim)]
+
+    if len(indices) != dim:
+        raise ReginaldException(f"{bt} -> {name}: dimIndex does not match dim!")
+
+    result = []
+    for i, index in enumerate(indices):
+        if "[%s]" in name:
+            element_name = name.replace("[%s]", index)
+        else:
+            element_name = name.replace("%s", index)
+        result.append((element_name, offset + i * increment))
+    return result
@@ -0,0 +1,581 @@
+import re
+from dataclasses import dataclass, field
+from math import ceil
+from typing import Dict, List, Optional, Tuple, Union
+
+from reginald.error import ReginaldException, warn
+from reginald.input.parse_yaml import (YAML_Enum, YAML_Field,
+                                       YAML_RegEnumEntry, YAML_Register,
+                                       YAML_RegisterBlock, YAML_RegisterMap)
+
+# Converts a SystemRDL 2.0 description into a reginald listing. Only the
+# structural subset of the language is supported:
+#   - Named and anonymous addrmap/regfile/reg/field definitions, explicit
+#     instantiations, single-dimension arrays, '@' addresses, '+=' strides,
+#     field bit ranges/widths and '=' reset values.
+#   - Property assignments and 'default' property assignments. Only 'name',
+#     'desc', 'sw', 'reset', 'regwidth', 'fieldwidth', 'encode', and the side
+#     effects 'woclr', 'woset', 'wot', 'rclr', 'onwrite', and 'onread' have an
+#     effect, all others are accepted and ignored.
+#   - Enum definitions, which become shared enums if referenced by a field
+#     'encode' property.
+# Parameters, dynamic assignments ('->'), user-defined properties, and the
+# preprocessor are not supported.
+#
+# The last root-level addrmap definition is the register map. Every register
+# instance inside it becomes a register, every regfile or addrmap instance
+# becomes a register block (with nested regfiles flattened into it). Arrays of
+# registers are expanded into one register per element, arrays of regfiles
+# become multiple instances of the same block.
+#
+# Instances without an explicit address are placed at the next address
+# aligned to their size (registers) or to their largest register (regfiles).
+
+COMPONENT_KINDS = ['addrmap', 'regfile', 'reg', 'field', 'mem', 'signal']
+
+TOKEN_RE = re.compile(r"""
+    (?P<ws>\s+)|
+    (?P<comment>//[^\n]*|/\*.*?\*/)|
+    (?P<string>"(?:[^"\\]|\\.)*")|
+    (?P<number>[0-9]*'[sS]?[bBoOdDhH][0-9a-fA-F_xXzZ]+|0[xX][0-9a-fA-F_]+|[0-9][0-9_]*)|
+    (?P<ident>\\?[A-Za-z_][A-Za-z0-9_]*)|
+    (?P<punct>->|\+=|%=|[{}\[\]();,=@:.#`])
+""", re.VERBOSE | re.DOTALL)
+
+RDL_Value = Union[bool, int, str, 'RDLEnum']
+
+
+@dataclass
+class RDLToken:
+    kind: str
+    text: str
+    line: int
+
+
+@dataclass
+class RDLEnum:
+    name: str
+    entries: Dict[str, Tuple[int, Dict[str, RDL_Value]]]
+
+
+@dataclass
+class RDLInstance:
+    type: 'RDLComponent'
+    name: str
+    brackets: List[Tuple[int, Optional[int]]]
+    reset: Optional[int]
+    at: Optional[int]
+    stride: Optional[int]
+    line: int
+
+
+@dataclass
+class RDLComponent:
+    kind: str
+    name: Optional[str]
+    props: Dict[str, RDL_Value]
+    line: int
+    instances: List[RDLInstance] = field(default_factory=list)
+
+
+class RDLScope:
+    def __init__(self, parent: Optional['RDLScope'] = None):
+        self.parent = parent
+        self.components = {}  # type: Dict[str, RDLComponent]
+        self.enums = {}  # type: Dict[str, RDLEnum]
+        self.defaults = {}  # type: Dict[str, RDL_Value]
+
+    def lookup_component(self, name: str) -> Optional[RDLComponent]:
+        if name in self.components:
+            return self.components[name]
+        return self.parent.lookup_component(name) if self.parent is not None else None
+
+    def lookup_enum(self, name: str) -> Optional[RDLEnum]:
+        if name in self.enums:
+            return self.enums[name]
+        return self.parent.lookup_enum(name) if self.parent is not None else None
+
+    def all_defaults(self) -> Dict[str, RDL_Value]:
+        parent_defaults = self.parent.all_defaults() if self.parent is not None else {}
+        return parent_defaults | self.defaults
+
+
+def listing_from_systemrdl_file(file_name: str) -> YAML_RegisterMap:
+    try:
+        with open(file_name) as f:
+            text = f.read()
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+
+    root = RDLParser(rdl_tokenize(text, file_name), file_name).parse()
+    return RDLConverter(root, file_name).convert()
+
+
+def rdl_tokenize(text: str, file_name: str) -> List[RDLToken]:
+    tokens = []
+    pos = 0
+    line = 1
+    while pos < len(text):
+        match = TOKEN_RE.match(text, pos)
+        if match is None:
+            raise ReginaldException(f"{file_name}:{line}: Unexpected character '{text[pos]}'")
+        kind = match.lastgroup
+        assert kind is not None
+        if kind not in ['ws', 'comment']:
+            tokens.append(RDLToken(kind, match.group(), line))
+        line += match.group().count("\n")
+        pos = match.end()
+    return tokens
+
+
+def rdl_parse_int(text: str, bt: str) -> int:
+    text = text.lower().replace('_', '')
+    try:
+        verilog = re.match(r"^([0-9]*)'s?([bodh])([0-9a-f]+)$", text)
+        if verilog is not None:
+            base = {'h': 16, 'd': 10, 'b': 2, 'o': 8}[verilog.group(2)]
+            return int(verilog.group(3), base)
+        if text.startswith('0x'):
+            return int(text[2:], 16)
+        return int(text, 10)
+    except ValueError:
+        raise ReginaldException(f"{bt}: Invalid number '{text}'!")
+
+
+class RDLParser:
+    def __init__(self, tokens: List[RDLToken], file_name: str):
+        self.tokens = tokens
+        self.file_name = file_name
+        self.pos = 0
+
+    def parse(self) -> List[RDLComponent]:
+        scope = RDLScope()
+        definitions = []
+        while not self._at_end():
+            comp = self._parse_element(scope, None)
+            if comp is not None:
+                definitions.append(comp)
+        return definitions
+
+    # Token helpers:
+
+    def _at_end(self) -> bool:
+        return self.pos >= len(self.tokens)
+
+    def _peek(self, offset: int = 0) -> Optional[RDLToken]:
+        if self.pos + offset >= len(self.tokens):
+            return None
+        return self.tokens[self.pos + offset]
+
+    def _next(self) -> RDLToken:
+        if self._at_end():
+            line = self.tokens[-1].line if len(self.tokens) > 0 else 1
+            raise ReginaldException(f"{self.file_name}:{line}: Unexpected end of file")
+        tok = self.tokens[self.pos]
+        self.pos += 1
+        return tok
+
+    def _error(self, tok: RDLToken, msg: str) -> ReginaldException:
+        return ReginaldException(f"{self.file_name}:{tok.line}: {msg}")
+
+    def _expect(self, text: str) -> RDLToken:
+        tok = self._next()
+        if tok.text != text:
+            raise self._error(tok, f"Expected '{text}', found '{tok.text}'")
+        return tok
+
+    def _expect_ident(self) -> str:
+        tok = self._next()
+        if tok.kind != 'ident':
+            raise self._error(tok, f"Expected identifier, found '{tok.text}'")
+        return tok.text.lstrip('\\')
+
+    def _expect_number(self) -> int:
+        tok = self._next()
+        if tok.kind != 'number':
+            raise self._error(tok, f"Expected number, found '{tok.text}'")
+        return rdl_parse_int(tok.text, f"{self.file_name}:{tok.line}")
+
+    def _accept(self, text: str) -> bool:
+        tok = self._peek()
+        if tok is not None and tok.text == text:
+            self.pos += 1
+            return True
+        return False
+
+    # Grammar:
+
+    def _parse_element(self, scope: RDLScope, parent: Optional[RDLComponent]) -> Optional[RDLComponent]:
+        # Parses one element of a component body (or the root). Returns component definitions.
+        tok = self._peek()
+        if tok is None:
+            self._next()  # Raises 'Unexpected end of file'
+        assert tok is not None
+
+        if tok.text == '`':
+            raise self._error(tok, "Preprocessor directives are not supported")
+
+        if tok.text == 'enum':
+            self._parse_enum(scope)
+            return None
+
+        if tok.text == 'default':
+            self._next()
+            name, value = self._parse_property(scope)
+            scope.defaults[name] = value
+            return None
+
+        if tok.text in ['external', 'internal']:
+            self._next()
+            tok = self._peek()
+            assert tok is not None
+
+        if tok.text in COMPONENT_KINDS:
+            comp = self._parse_component(scope)
+            if not self._accept(';'):
+                self._parse_instances(comp, parent)
+            return comp
+
+        if tok.kind == 'ident':
+            following = self._peek(1)
+            if following is not None and following.kind == 'ident':
+                comp = scope.lookup_component(tok.text)
+                if comp is None:
+                    raise self._error(tok, f"Unknown component type '{tok.text}'")
+                self._next()
+                self._parse_instances(comp, parent)
+                return None
+
+            if following is not None and following.text == '->':
+                raise self._error(tok, "Dynamic property assignments are not supported")
+
+            if parent is None:
+                raise self._error(tok, "Property assignment outside of a component")
+            name, value = self._parse_property(scope)
+            parent.props[name] = value
+            return None
+
+        raise self._error(tok, f"Unexpected '{tok.text}'")
+
+    def _parse_component(self, scope: RDLScope) -> RDLComponent:
+        kind_tok = self._next()
+        name = None
+        if self._peek() is not None and self._peek().kind == 'ident':  # type: ignore
+            name = self._expect_ident()
+        if self._peek() is not None and self._peek().text == '#':  # type: ignore
+            raise self._error(kind_tok, "Parameterized components are not supported")
+
+        comp = RDLComponent(kind_tok.text, name, scope.all_defaults(), kind_tok.line)
+
+        self._expect('{')
+        inner = RDLScope(scope)
+        while not self._accept('}'):
+            self._parse_element(inner, comp)
+
+        if name is not None:
+            scope.components[name] = comp
+        return comp
+
+    def _parse_instances(self, comp: RDLComponent, parent: Optional[RDLComponent]):
+        while True:
+            tok = self._peek()
+            assert tok is not None
+            if parent is None:
+                raise self._error(tok, "Instances at root level are not supported")
+
+            name = self._expect_ident()
+            brackets = []  # type: List[Tuple[int, Optional[int]]]
+            while self._accept('['):
+                first = self._expect_number()
+                second = self._expect_number() if self._accept(':') else None
+                self._expect(']')
+                brackets.append((first, second))
+
+            reset = self._expect_number() if self._accept('=') else None
+            at = self._expect_number() if self._accept('@') else None
+            stride = self._expect_number() if self._accept('+=') else None
+            if self._accept('%='):
+                align_tok = self._next()
+                warn(f"{self.file_name}:{align_tok.line}: Alignment ('%=') is ignored.")
+
+            parent.instances.append(RDLInstance(comp, name, brackets, reset, at, stride, tok.line))
+
+            if self._accept(';'):
+                return
+            self._expect(',')
+
+    def _parse_property(self, scope: RDLScope) -> Tuple[str, RDL_Value]:
+        name = self._expect_ident()
+        if self._accept(';'):
+            return name, True
+        self._expect('=')
+        tok = self._next()
+        value = None  # type: Optional[RDL_Value]
+        if tok.kind == 'number':
+            value = rdl_parse_int(tok.text, f"{self.file_name}:{tok.line}")
+        elif tok.kind == 'string':
+            value = re.sub(r"\\(.)", r"\1", tok.text[1:-1])
+        elif tok.kind == 'ident' and tok.text in ['true', 'false']:
+            value = tok.text == 'true'
+        elif tok.kind == 'ident' and name == 'encode':
+            value = scope.lookup_enum(tok.text)
+            if value is None:
+                raise self._error(tok, f"Unknown enum '{tok.text}'")
+        elif tok.kind == 'ident':
+            value = tok.text
+        else:
+            raise self._error(tok, f"Unsupported value '{tok.text}' for property '{name}'")
+        self._expect(';')
+        return name, value
+
+    def _parse_enum(self, scope: RDLScope):
+        self._expect('enum')
+        name = self._expect_ident()
+        self._expect('{')
+        entries = {}  # type: Dict[str, Tuple[int, Dict[str, RDL_Value]]]
+        next_val = 0
+        while not self._accept('}'):
+            entry_name = self._expect_ident()
+            val = self._expect_number() if self._accept('=') else next_val
+            props = {}  # type: Dict[str, RDL_Value]
+            if self._accept('{'):
+                while not self._accept('}'):
+                    prop_name, prop_value = self._parse_property(scope)
+                    props[prop_name] = prop_value
+            self._expect(';')
+            entries[entry_name] = (val, props)
+            next_val = val + 1
+        self._expect(';')
+        scope.enums[name] = RDLEnum(name, entries)
+
+
+class RDLConverter:
+    def __init__(self, definitions: List[RDLComponent], file_name: str):
+        self.definitions = definitions
+        self.file_name = file_name
+        self.enums = {}  # type: Dict[str, YAML_Enum]
+        self.enum_sources = {}  # type: Dict[str, RDLEnum]
+
+    def convert(self) -> YAML_RegisterMap:
+        addrmaps = [d for d in self.definitions if d.kind == 'addrmap']
+        if len(addrmaps) == 0:
+            raise ReginaldException(f"{self.file_name}: No addrmap defined!")
+        top = addrmaps[-1]
+        map_name = top.name or "map"
+        bt = f"systemrdl -> {map_name}"
+
+        registers = {}  # type: Dict[str, Union[YAML_Register, YAML_RegisterBlock]]
+
+        for inst, base, count, stride in self._layout(top):
+            ibt = bt + f" -> {inst.name}"
+            names = rdl_element_names(inst.name, count)
+            adrs = [base + i * stride for i in range(count)]
+
+            if inst.type.kind == 'reg':
+                for name, adr in zip(names, adrs):
+                    self._add(registers, name, self._convert_register(inst.type, adr, bt + f" -> {name}"), bt)
+
+            elif inst.type.kind in ['regfile', 'addrmap']:
+                templates = {}  # type: Dict[str, YAML_Register]
+                for reg_name, reg_comp, adr in self._flatten(inst.type, 0, ""):
+                    templates["_" + reg_name] = self._convert_register(reg_comp, adr, ibt + f" -> {reg_name}")
+                if len(templates) == 0:
+                    warn(f"{ibt}: Contains no registers and is ignored.")
+                    continue
+                self._add(registers, inst.name, YAML_RegisterBlock(
+                    instances=dict(zip(names, adrs)),
+                    brief=rdl_str_prop(inst.type, 'name'),
+                    doc=rdl_str_prop(inst.type, 'desc'),
+                    registers=templates,
+                ), bt)
+
+            else:
+                warn(f"{ibt}: Instances of '{inst.type.kind}' are not supported and are ignored.")
+
+        return YAML_RegisterMap(
+            map_name=map_name,
+            default_register_bitwidth=32,
+            registers=registers,
+            enums=self.enums,
+            brief=rdl_str_prop(top, 'name'),
+            doc=rdl_str_prop(top, 'desc'),
+        )
+
+    def _add(self, registers: Dict, name: str, entry: Union[YAML_Register, YAML_RegisterBlock], bt: str):
+        if name in registers:
+            raise ReginaldException(f"{bt}: {name} declared twice!")
+        registers[name] = entry
+
+    def _flatten(self, comp: RDLComponent, offset: int, prefix: str) -> List[Tuple[str, RDLComponent, int]]:
+        # All registers inside a regfile/addrmap, with nested regfiles flattened.
+        result = []
+        for inst, base, count, stride in self._layout(comp):
+            for i, name in enumerate(rdl_element_names(inst.name, count)):
+                adr = offset + base + i * stride
+                if inst.type.kind == 'reg':
+                    result.append((prefix + name, inst.type, adr))
+                elif inst.type.kind in ['regfile', 'addrmap']:
+                    result.extend(self._flatten(inst.type, adr, prefix + name + "_"))
+        return result
+
+    def _layout(self, comp: RDLComponent) -> List[Tuple[RDLInstance, int, int, int]]:
+        # Address, element count, and stride of every instance inside a component.
+        result = []
+        cursor = 0
+        for inst in comp.instances:
+            if len(inst.brackets) > 1 or (len(inst.brackets) == 1 and inst.brackets[0][1] is not None):
+                raise ReginaldException(f"{self.file_name}:{inst.line}: Only single-dimension arrays are supported!")
+            count = inst.brackets[0][0] if len(inst.brackets) == 1 else 1
+
+            size = self._size(inst.type)
+            stride = inst.stride if inst.stride is not None else size
+            if inst.at is not None:
+                base = inst.at
+            else:
+                align = self._alignment(inst.type)
+                base = ceil(cursor / align) * align
+            result.append((inst, base, count, stride))
+            cursor = max(cursor, base + (count - 1) * stride + size)
+        return result
+
+    def _size(self, comp: RDLComponent) -> int:
+        if comp.kind == 'reg':
+            return ceil(rdl_regwidth(comp) / 8)
+        end = 0
+        for inst, base, count, stride in self._layout(comp):
+            end = max(end, base + (count - 1) * stride + self._size(inst.type))
+        return end
+
+    def _alignment(self, comp: RDLComponent) -> int:
+        if comp.kind == 'reg':
+            return ceil(rdl_regwidth(comp) / 8)
+        return max([self._alignment(inst.type) for inst in comp.instances], default=1)
+
+    def _convert_register(self, comp: RDLComponent, adr: int, bt: str) -> YAML_Register:
+        bitwidth = rdl_regwidth(comp)
+
+        fields = {}
+        reset_val = 0  # type: Optional[int]
+        cursor = 0
+        for inst in comp.instances:
+            fbt = bt + f" -> {inst.name}"
+            if inst.type.kind != 'field':
+                raise ReginaldException(f"{fbt}: Registers may only contain fields!")
+
+            if len(inst.brackets) == 0:
+                width = inst.type.props.get('fieldwidth', 1)
+                if not isinstance(width, int):
+                    raise ReginaldException(f"{fbt}: Invalid fieldwidth!")
+                lsb, msb = cursor, cursor + width - 1
+            elif len(inst.brackets) == 1 and inst.brackets[0][1] is None:
+                lsb, msb = cursor, cursor + inst.brackets[0][0] - 1
+            elif len(inst.brackets) == 1:
+                high, low = inst.brackets[0]
+                assert low is not None
+                lsb, msb = min(high, low), max(high, low)
+            else:
+                raise ReginaldException(f"{fbt}: Invalid bit range!")
+            cursor = msb + 1
+
+            field_reset = inst.reset if inst.reset is not None else inst.type.props.get('reset')
+            if isinstance(field_reset, int) and not isinstance(field_reset, bool) and reset_val is not None:
+                reset_val |= field_reset << lsb
+            else:
+                reset_val = None
+
+            fields[inst.name] = YAML_Field(
+                bits=lsb if lsb == msb else f"{msb}-{lsb}",
+                access=rdl_access(inst.type, fbt),
+                brief=rdl_str_prop(inst.type, 'name'),
+                doc=rdl_str_prop(inst.type, 'desc'),
+                enum=self._convert_enum(inst.type, fbt),
+            )
+
+        if len(fields) == 0:
+            reset_val = None
+
+        return YAML_Register(
+            adr=adr,
+            bitwidth=bitwidth,
+            reset_val=reset_val,
+            brief=rdl_str_prop(comp, 'name'),
+            doc=rdl_str_prop(comp, 'desc'),
+            fields=fields,
+        )
+
+    def _convert_enum(self, comp: RDLComponent, bt: str) -> Optional[str]:
+        enum = comp.props.get('encode')
+        if enum is None:
+            return None
+        if not isinstance(enum, RDLEnum):
+            raise ReginaldException(f"{bt}: Invalid encode property!")
+
+        if enum.name in self.enum_sources:
+            if self.enum_sources[enum.name] is not enum:
+                raise ReginaldException(f"{bt}: Two different enums named {enum.name}!")
+            return enum.name
+
+        entries = {}
+        for name, (val, props) in enum.entries.items():
+            brief = props.get('name')
+            doc = props.get('desc')
+            entries[name] = YAML_RegEnumEntry(
+                val=val,
+                brief=brief if isinstance(brief, str) else None,
+                doc=doc if isinstance(doc, str) else None,
+            )
+        self.enums[enum.name] = YAML_Enum(enum=entries)
+        self.enum_sources[enum.name] = enum
+        return enum.name
+
+
+def rdl_element_names(name: str, count: int) -> List[str]:
+    if count == 1:
+        return [name]
+    return [f"{name}{i}" for i in range(count)]
+
+
+def rdl_regwidth(comp: RDLComponent) -> int:
+    regwidth = comp.props.get('regwidth', 32)
+    if not isinstance(regwidth, int) or isinstance(regwidth, bool):
+        raise ReginaldException(f"systemrdl: Invalid regwidth in definition of {comp.name}!")
+    return regwidth
+
+
+def rdl_str_prop(comp: RDLComponent, prop: str) -> Optional[str]:
+    value = comp.props.get(prop)
+    if not isinstance(value, str):
+        return None
+    value = " ".join(value.split())
+    return value if len(value) > 0 else None
+
+
+def rdl_access(comp: RDLComponent, bt: str) -> Optional[List[str]]:
+    sw = comp.props.get('sw')
+    if sw is None:
+        access = None
+    else:
+        match sw:
+            case 'rw' | 'wr' | 'rw1' | 'w1r':
+                access = ['r', 'w']
+            case 'r':
+                access = ['r']
+            case 'w' | 'w1':
+                access = ['w']
+            case 'na':
+                access = []
+            case other:
+                raise ReginaldException(f"{bt}: Unknown sw access '{other}'!")
+
+    # Side effects, as boolean properties ('woclr;') or 'onwrite'/'onread':
+    write_mode = None  # type: Optional[str]
+    for prop, mode in [('woclr', 'w1c'), ('woset', 'w1s'), ('wot', 'w1t')]:
+        if comp.props.get(prop) is True or comp.props.get('onwrite') == prop:
+            write_mode = mode
+    read_mode = 'rc' if comp.props.get('rclr') is True or comp.props.get('onread') == 'rclr' else None
+    if write_mode is None and read_mode is None:
+        return access
+    if access is None:
+        access = ['r', 'w']
+    return [write_mode if mode == 'w' and write_mode is not None else
+            read_mode if mode == 'r' and read_mode is not None else mode for mode in access]
@@ -1,4 +1,5 @@
-from typing import Dict, List, Optional, Union
+import json
+from typing import Any, Dict, List, Literal, Optional, Union
 
 import pydantic
 import yaml
@@ -7,25 +8,44 @@ from pydantic import (BaseModel, ConfigDict, NonNegativeInt, PositiveInt,
 from yaml.loader import SafeLoader
 
 from reginald.error import ReginaldException
+from reginald.input.errors_yaml import (drop_unknown_keys,
+                                        format_validation_error,
+                                        format_yaml_error, yaml_key_marks)
+from reginald.input.include_yaml import resolve_includes
+from reginald.input.migrate_yaml import migrate_listing
+from reginald.input.mount_yaml import resolve_mounts
+from reginald.input.params_yaml import expand_params
 
 YAML_Bits = Union[List[Union[NonNegativeInt, str]], NonNegativeInt, str]
 YAML_Access = Union[List[str], str]
+YAML_Doc = Union[str, Dict[str, str]]
 
 
 class YAML_RegEnumEntry(BaseModel):
     model_config = ConfigDict(extra='forbid', strict=True)
 
     val: NonNegativeInt
-    doc: Optional[str] = None
-    brief: Optional[str] = None
+    catch_all: bool = False
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
 
 
 class YAML_Enum(BaseModel):
     model_config = ConfigDict(extra='forbid', strict=True)
 
     enum: Dict[str, YAML_RegEnumEntry]
-    doc: Optional[str] = None
-    brief: Optional[str] = None
+    reserved: List[Union[NonNegativeInt, str]] = []
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
+class YAML_FixedPoint(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    frac_bits: Optional[NonNegativeInt] = None
+    scale: Optional[float] = None
+    offset: float = 0.0
+    signed: bool = False
 
 
 class YAML_Field(BaseModel):
@@ -33,9 +53,30 @@ class YAML_Field(BaseModel):
 
     bits: YAML_Bits
     access: Optional[YAML_Access] = None
-    doc: Optional[str] = None
-    brief: Optional[str] = None
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
     enum: Optional[Union[Dict[str, YAML_RegEnumEntry], str]] = None
+    view: bool = False
+    fixed_point: Optional[YAML_FixedPoint] = None
+    float_format: Optional[Literal['f16', 'f32']] = None
+    unit: Optional[str] = None
+    min: Optional[NonNegativeInt] = None
+    max: Optional[NonNegativeInt] = None
+    count: Optional[PositiveInt] = None
+    stride: Optional[PositiveInt] = None
+    internal: bool = False
+    read_side_effect: Optional[Literal['clear', 'pop', 'latch']] = None
+    # Trigger bit (e.g. SOFT_RESET), that is cleared by hardware after a 1 is written:
+    self_clearing: bool = False
+    # Updated by hardware (e.g. status, measurement), never cache:
+    volatile: bool = False
+    # Order of the bytes of a multi-byte field in the register value. 'big'
+    # stores the most significant byte of the field in its lowest bits:
+    byte_order: Literal['little', 'big'] = 'little'
+    aliases: List[str] = pydantic.Field(default_factory=list)
+    # Recommended value ("write X for normal operation"), if it differs from
+    # the reset value. Raw value or enum entry name:
+    default: Optional[Union[bool, NonNegativeInt, str]] = None
 
 
 class YAML_AlwaysWrite(BaseModel):
@@ -43,48 +84,196 @@ class YAML_AlwaysWrite(BaseModel):
 
     mask: NonNegativeInt
     val: NonNegativeInt
+    dont_care: NonNegativeInt = 0
+
+
+class YAML_RegisterExample(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    val: NonNegativeInt
+    fields: Dict[str, Union[bool, NonNegativeInt, str]]
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
+class YAML_Unlock(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    key_register: str
+    keys: List[NonNegativeInt]
+
+
+class YAML_Security(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    # TrustZone security state and privilege level required for access:
+    secure: Optional[bool] = None
+    privileged: Optional[bool] = None
+
+
+class YAML_RegisterVariant(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    # Value of the register's tag field (or name of one of its enum entries)
+    # that selects this variant:
+    tag: Union[NonNegativeInt, str]
+    extends: Optional[str] = None
+    fields: Dict[str, YAML_Field] = pydantic.Field(default_factory=dict)
+    access: Optional[YAML_Access] = None
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
 
 
 class YAML_Register(BaseModel):
     model_config = ConfigDict(extra='forbid', strict=True)
 
+    # Name of a register (in the same block, or at the top level) or of a
+    # layout whose fields are inherited. Fields given here add to or override them:
+    extends: Optional[str] = None
     fields: Dict[str, YAML_Field] = pydantic.Field(default_factory=dict)
+    # Registers whose read view differs from their write view (e.g. data/command
+    # ports) name a register or layout for each view instead of giving fields:
+    layout_read: Optional[str] = None
+    layout_write: Optional[str] = None
+    # Alternative layouts of the bits outside of 'fields' (e.g. a packet type
+    # that selects how the payload decodes), selected by the value of the
+    # field named by 'variant_tag':
+    variant_tag: Optional[str] = None
+    variants: Dict[str, YAML_RegisterVariant] = pydantic.Field(default_factory=dict)
     access: Optional[YAML_Access] = None
     adr: NonNegativeInt
     bitwidth: Optional[PositiveInt] = None
     reset_val: Optional[NonNegativeInt] = None
     always_write: Optional[YAML_AlwaysWrite] = None
-    doc: Optional[str] = None
-    brief: Optional[str] = None
+    examples: List[YAML_RegisterExample] = pydantic.Field(default_factory=list)
+    count: Optional[PositiveInt] = None
+    stride: Optional[PositiveInt] = None
+    read_side_effect: Optional[Literal['clear', 'pop', 'latch']] = None
+    # All fields are updated by hardware:
+    volatile: bool = False
+    unlock: Optional[YAML_Unlock] = None
+    aliases: List[str] = pydantic.Field(default_factory=list)
+    security: Optional[YAML_Security] = None
+    # Clock and power domain the register lives in (inaccessible while they are off):
+    clock_domain: Optional[str] = None
+    power_domain: Optional[str] = None
+    reserved_fill: Optional[Literal[0, 1]] = None
+    group: Optional[str] = None
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
+class YAML_Layout(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    # Fields of a register that is not part of the map, only used through
+    # 'extends' (e.g. shared by several chips through a library listing):
+    extends: Optional[str] = None
+    fields: Dict[str, YAML_Field] = pydantic.Field(default_factory=dict)
+    access: Optional[YAML_Access] = None
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
+class YAML_InstanceArray(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    count: PositiveInt
+    start: NonNegativeInt
+    stride: PositiveInt
 
 
 class YAML_RegisterBlock(BaseModel):
     model_config = ConfigDict(extra='forbid', strict=True)
 
-    instances: Dict[str, NonNegativeInt]
-    brief: Optional[str] = None
-    doc: Optional[str] = None
+    instances: Union[YAML_InstanceArray, Dict[str, NonNegativeInt]] = pydantic.Field(union_mode='left_to_right')
+    group: Optional[str] = None
+    # Security of all instances, and of single instances (overriding the former):
+    security: Optional[YAML_Security] = None
+    instance_security: Dict[str, YAML_Security] = pydantic.Field(default_factory=dict)
+    # Domains of all registers (unless they give their own):
+    clock_domain: Optional[str] = None
+    power_domain: Optional[str] = None
+    brief: Optional[YAML_Doc] = None
+    doc: Optional[YAML_Doc] = None
     registers: Dict[str, YAML_Register]
 
 
+class YAML_Transaction(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    registers: List[str]
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
+class YAML_SplitValue(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    parts: List[str]
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
+class YAML_Constant(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    val: NonNegativeInt
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
+
+
 class YAML_RegisterMap(BaseModel):
     model_config = ConfigDict(extra='forbid', strict=True)
 
     map_name: str
     default_register_bitwidth: PositiveInt
+    # Bits per address (e.g. 32 for word-addressed buses), all addresses are given in these units:
+    address_unit_bits: PositiveInt = 8
+    # Value written to all bits that are not part of a field or always_write (if given):
+    reserved_fill: Optional[Literal[0, 1]] = None
     registers: Dict[str, Union[YAML_Register, YAML_RegisterBlock]]
+    layouts: Dict[str, YAML_Layout] = pydantic.Field(default_factory=dict)
     enums: Dict[str, YAML_Enum] = pydantic.Field(default_factory=dict)
-    doc: Optional[str] = None
-    brief: Optional[str] = None
+    transactions: Dict[str, YAML_Transaction] = pydantic.Field(default_factory=dict)
+    split_values: Dict[str, YAML_SplitValue] = pydantic.Field(default_factory=dict)
+    constants: Dict[str, YAML_Constant] = pydantic.Field(default_factory=dict)
+    doc: Optional[YAML_Doc] = None
+    brief: Optional[YAML_Doc] = None
 
     @classmethod
-    def from_yaml_file(cls, file_name: str):
+    def from_yaml_file(cls, file_name: str, params: Optional[Dict[str, int]] = None, permissive: bool = False):
         try:
             with open(file_name) as f:
                 data = yaml.load(f, Loader=SafeLoader)
-                return YAML_RegisterMap(**data)
+        except FileNotFoundError:
+            raise ReginaldException(f"File {file_name} not found")
+        except yaml.YAMLError as e:
+            raise ReginaldException(format_yaml_error(e, file_name))
+
+        return cls.from_data(data, file_name, params, permissive)
 
+    @classmethod
+    def from_json_file(cls, file_name: str, params: Optional[Dict[str, int]] = None, permissive: bool = False):
+        try:
+            with open(file_name) as f:
+                data = json.load(f)
         except FileNotFoundError:
             raise ReginaldException(f"File {file_name} not found")
-        except ValidationError as e:
-            raise ReginaldException(str(e))
+        except json.JSONDecodeError as e:
+            raise ReginaldException(f"{file_name}: Invalid JSON: {e}")
+
+        return cls.from_data(data, file_name, params, permissive)
+
+    @classmethod
+    def from_data(cls, data: Any, file_name: str, params: Optional[Dict[str, int]], permissive: bool):
+        data = resolve_mounts(expand_params(resolve_includes(migrate_listing(data), file_name), params), file_name)
+        while True:
+            try:
+                return YAML_RegisterMap(**data)
+            except ValidationError as e:
+                # JSON is (nearly always) valid YAML, so positions are found the same way for both:
+                marks = yaml_key_marks(file_name)
+                if permissive and drop_unknown_keys(e, data, file_name, marks):
+                    continue  # Try again without the unknown keys.
+                raise ReginaldException(format_validation_error(e, data, file_name, marks))
@@ -0,0 +1,120 @@
+from typing import List, Set
+
+import yaml
+from pydantic import BaseModel, ConfigDict, ValidationError
+from yaml.loader import SafeLoader
+
+from reginald.datamodel import RegisterMap
+from reginald.error import ReginaldException, warn
+
+
+class YAML_UsageManifest(BaseModel):
+    model_config = ConfigDict(extra='forbid', strict=True)
+
+    # Each entry is either the name of a physical register (e.g. 'CH1_CNFG'),
+    # the generic name of a block register (e.g. 'CHn_CNFG', selects all
+    # instances), or the name of a complete block (e.g. 'CHn').
+    # Blocks are pruned to the used instances and the used registers, but
+    # every kept instance keeps all kept registers: Using 'CH1_CNFG' and
+    # 'CH2_STATUS' also keeps 'CH1_STATUS' and 'CH2_CNFG' (with a warning).
+    registers: List[str]
+
+    @classmethod
+    def from_yaml_file(cls, file_name: str):
+        try:
+            with open(file_name) as f:
+                data = yaml.load(f, Loader=SafeLoader)
+                return YAML_UsageManifest(**data)
+        except FileNotFoundError:
+            raise ReginaldException(f"File {file_name} not found")
+        except ValidationError as e:
+            raise ReginaldException(str(e))
+
+
+class MapPruner:
+    def __init__(self, rmap: RegisterMap, manifest: YAML_UsageManifest):
+        self.rmap = rmap
+        self.manifest = manifest
+
+    def prune(self) -> RegisterMap:
+        # The fingerprint identifies the complete map, so that a pruned output
+        # remains compatible with tools using the full map: Pin it before pruning.
+        _ = self.rmap.fingerprint
+
+        used = set(self.manifest.registers)
+        matched = set()  # type: Set[str]
+
+        for block_name in list(self.rmap.register_blocks.keys()):
+            block = self.rmap.register_blocks[block_name]
+
+            if block.name in used:
+                matched.add(block.name)
+                continue
+
+            used_instances = set()
+            selected = set()  # type: Set[str]
+            for template_name in list(block.register_templates.keys()):
+                keep_template = False
+
+                if block.name + template_name in used:
+                    matched.add(block.name + template_name)
+                    keep_template = True
+                    used_instances.update(block.instances.keys())
+                    selected.update(instance_name + template_name for instance_name in block.instances.keys())
+
+                for instance_name in block.instances.keys():
+                    if instance_name + template_name in used:
+                        matched.add(instance_name + template_name)
+                        keep_template = True
+                        used_instances.add(instance_name)
+                        selected.add(instance_name + template_name)
+
+                if not keep_template:
+                    del block.register_templates[template_name]
+
+            if len(block.register_templates) == 0:
+                del self.rmap.register_blocks[block_name]
+            else:
+                block.instances = {name: adr for name, adr in block.instances.items() if name in used_instances}
+                kept = [instance_name + template_name for instance_name in block.instances.keys()
+                        for template_name in block.register_templates.keys()]
+                unused = [name for name in kept if name not in selected]
+                if len(unused) > 0:
+                    warn(f"usage manifest: Block {block.name} keeps all used registers for all used instances, "
+                         f"including the unused {', '.join(unused)}.")
+
+        unknown = used - matched
+        if len(unknown) > 0:
+            raise ReginaldException(f"usage manifest: Unknown registers: {', '.join(sorted(unknown))}")
+
+        # Drop all shared enums that are no longer referenced:
+        used_enums = set()
+        for block in self.rmap.register_blocks.values():
+            for template in block.register_templates.values():
+                for field in template.get_all_fields().values():
+                    if field.enum is not None and field.enum.is_shared:
+                        used_enums.add(field.enum.name)
+        self.rmap.enums = {name: enum for name, enum in self.rmap.enums.items() if name in used_enums}
+
+        # Drop all transactions that are no longer complete:
+        remaining = set()
+        for block in self.rmap.register_blocks.values():
+            for template_name in block.register_templates.keys():
+                remaining.update(instance_name + template_name for instance_name in block.instances.keys())
+        self.rmap.transactions = {name: t for name, t in self.rmap.transactions.items()
+                                  if all(reg in remaining for reg in t.registers)}
+        self.rmap.split_values = {name: v for name, v in self.rmap.split_values.items()
+                                  if all(part.reg_name in remaining for part in v.parts)}
+
+        # Registers that require an unlock sequence cannot be written without their key register:
+        for block in self.rmap.register_blocks.values():
+            for template in block.register_templates.values():
+                if template.unlock is not None and template.unlock.key_register not in remaining:
+                    raise ReginaldException(f"usage manifest: Register {block.name + template.name} requires key register "
+                                            f"{template.unlock.key_register}, which is not used!")
+
+        # Derived properties were computed from the full map (except for the pinned fingerprint):
+        for cached in ['physical_registers', 'addresses', 'max_address', 'max_register_bitwidth', 'groups']:
+            self.rmap.__dict__.pop(cached, None)
+
+        return self.rmap
@@ -0,0 +1,63 @@
+from typing import Any, Dict
+
+from reginald.input.migrate_yaml import SCHEMA_VERSION
+from reginald.input.params_yaml import (EXPANDED_ALWAYS_WRITE_KEYS,
+                                        EXPANDED_FIELD_KEYS,
+                                        EXPANDED_REGISTER_KEYS)
+from reginald.input.parse_yaml import YAML_RegisterMap
+
+# JSON Schema of the listing format, for editor validation and completion.
+# It is derived from the pydantic listing models, and extended with the keys
+# that are handled before the models see a listing (schema version,
+# includes, library uses, mounts, and parameters). Values that may be integer expressions
+# additionally accept strings.
+
+INT_EXPR_SCHEMA = {'type': 'string'}
+
+MOUNT_SCHEMA = {
+    'type': 'object',
+    'properties': {
+        'map': {'type': 'string'},
+        'at': {'anyOf': [{'type': 'integer', 'minimum': 0}, INT_EXPR_SCHEMA]},
+        'prefix': {'type': 'string'},
+    },
+    'required': ['map', 'at'],
+    'additionalProperties': False,
+}
+
+
+def listing_json_schema() -> Dict[str, Any]:
+    schema = YAML_RegisterMap.model_json_schema()
+    defs = schema['$defs']
+
+    properties = schema['properties']
+    properties['schema'] = {'type': 'integer', 'minimum': 1, 'maximum': SCHEMA_VERSION, 'title': 'Schema'}
+    properties['includes'] = {'type': 'array', 'items': {'type': 'string'}, 'title': 'Includes'}
+    properties['uses'] = {'type': 'array', 'items': {'type': 'string'}, 'title': 'Uses'}
+    properties['params'] = {'type': 'object', 'additionalProperties': {'type': 'integer'}, 'title': 'Params'}
+    properties['mount'] = {'anyOf': [MOUNT_SCHEMA, {'type': 'array', 'items': MOUNT_SCHEMA}], 'title': 'Mount'}
+
+    properties['default_register_bitwidth'] = allow_int_expr(properties['default_register_bitwidth'])
+    for key in EXPANDED_REGISTER_KEYS:
+        defs['YAML_Register']['properties'][key] = allow_int_expr(defs['YAML_Register']['properties'][key])
+    for instances in defs['YAML_RegisterBlock']['properties']['instances']['anyOf']:
+        if 'additionalProperties' in instances:
+            instances['additionalProperties'] = allow_int_expr(instances['additionalProperties'])
+    for key in ['count', 'start', 'stride']:
+        defs['YAML_InstanceArray']['properties'][key] = allow_int_expr(defs['YAML_InstanceArray']['properties'][key])
+    for key in EXPANDED_FIELD_KEYS:
+        defs['YAML_Field']['properties'][key] = allow_int_expr(defs['YAML_Field']['properties'][key])
+    for key in EXPANDED_ALWAYS_WRITE_KEYS:
+        defs['YAML_AlwaysWrite']['properties'][key] = allow_int_expr(defs['YAML_AlwaysWrite']['properties'][key])
+    defs['YAML_RegEnumEntry']['properties']['val'] = allow_int_expr(defs['YAML_RegEnumEntry']['properties']['val'])
+    defs['YAML_Constant']['properties']['val'] = allow_int_expr(defs['YAML_Constant']['properties']['val'])
+
+    return {'$schema': 'https://json-schema.org/draft/2020-12/schema'} | schema | {'title': 'reginald listing'}
+
+
+def allow_int_expr(prop: Dict[str, Any]) -> Dict[str, Any]:
+    # Keep title and default at the top, add the expression as an alternative:
+    annotations = {key: val for key, val in prop.items() if key in ['title', 'default']}
+    value = {key: val for key, val in prop.items() if key not in annotations}
+    alternatives = value['anyOf'] if list(value.keys()) == ['anyOf'] else [value]
+    return {'anyOf': alternatives + [INT_EXPR_SCHEMA]} | annotations
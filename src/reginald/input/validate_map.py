@@ -1,8 +1,16 @@
 
 
+from typing import Dict, List, Set, Tuple
+
 from reginald.bits import fits_into_bitwidth
-from reginald.datamodel import Field, Register, RegisterBlock, RegisterMap
-from reginald.error import ReginaldException
+from reginald.datamodel import (Docs, Field, RegEnum, Register, RegisterBlock,
+                                RegisterMap, RegisterTransaction, SplitValue,
+                                plain_doc_ref)
+from reginald.error import ReginaldException, warn
+
+# Register values are not limited in width, but generators (and the reserved
+# fill) work on per-bit lists, so keep registers to a sane size:
+MAX_REGISTER_BITWIDTH = 4096
 
 
 class MapValidator:
@@ -10,14 +18,39 @@ class MapValidator:
         self.rmap = rmap
 
     def validate(self):
+        if self.rmap.address_unit_bits % 8 != 0:
+            raise ReginaldException(f"{self.rmap.map_name} -> address_unit_bits: Has to be a multiple of 8!")
+
+        # Validate all shared enums:
+        for enum in self.rmap.enums.values():
+            self._validate_enum(enum, f"enums -> {enum.name}")
+
         # Validate all registers:
+        aliases = {}  # type: Dict[str, str]
         for block in self.rmap.register_blocks.values():
             for template in block.register_templates.values():
                 self._validate_template(block, template)
+                self._validate_aliases(block, template, aliases)
+
+        # Validate all transactions:
+        for transaction in self.rmap.transactions.values():
+            self._validate_transaction(transaction)
+
+        # Validate all split values:
+        used_bits = {}  # type: Dict[Tuple[str, int], str]
+        for split_value in self.rmap.split_values.values():
+            self._validate_split_value(split_value, used_bits)
+
+        # Validate all documentation cross-references:
+        for bt, thing in self.rmap.documented():
+            self._validate_doc_refs(thing.docs, bt)
 
     def _validate_template(self, block: RegisterBlock, reg: Register):
         bt = f"registers -> {block.name+reg.name}"
 
+        if reg.bitwidth > MAX_REGISTER_BITWIDTH:
+            raise ReginaldException(f"{bt}: Bitwidth {reg.bitwidth} exceeds the maximum of {MAX_REGISTER_BITWIDTH} bits!")
+
         # Validate all fields:
         for field in reg.fields.values():
             self._validate_field(reg, field, bt)
@@ -27,17 +60,30 @@ class MapValidator:
             if not fits_into_bitwidth(reg.reset_val, reg.bitwidth):
                 raise ReginaldException(f"{bt}: reset_val does not fit into register!")
 
-        # Validate that no fields overlap:
-        field_at_bit = {}
-        for field in reg.fields.values():
-            for bit in field.bits.bitlist:
-                if bit in field_at_bit:
-                    raise ReginaldException(f"Field {field.name} overalaps with field {field_at_bit[bit]} at bit {bit}!")
-                field_at_bit[bit] = field.name
+        # Validate that no fields overlap, and views only cover bits of other fields:
+        field_at_bit = self._validate_layout(reg, bt)
+
+        # Validate the write layout of registers with separate layouts, always_write applies to it:
+        if reg.write_fields is not None:
+            write_view = reg.get_layout_views()[1]
+            for field in write_view.fields.values():
+                self._validate_field(write_view, field, bt + " -> layout_write")
+                if field.name in reg.fields:
+                    raise ReginaldException(f"{bt} -> layout_write -> {field.name}: Field is also part of the read layout!")
+                if field.is_readable():
+                    raise ReginaldException(f"{bt} -> layout_write -> {field.name}: Fields of the write layout cannot be readable!")
+            for field in reg.fields.values():
+                if field.is_writable():
+                    raise ReginaldException(f"{bt} -> layout_read -> {field.name}: Fields of the read layout cannot be writable!")
+            field_at_bit = self._validate_layout(write_view, bt + " -> layout_write")
+
+        # Validate the variants, always_write must not overlap with their fields either:
+        if reg.variant_tag is not None:
+            field_at_bit = field_at_bit | self._validate_variants(reg, bt)
 
         if reg.always_write is not None:
             # Validate that always_write fits into register:
-            if reg.always_write.bits.msb_position() + 1 > reg.bitwidth:
+            if not fits_into_bitwidth(reg.always_write.bits.get_bitmask(), reg.bitwidth):
                 raise ReginaldException(f"{bt}: always_write does not fit into register!")
 
             # Validate that always_write does not overlap with fields:
@@ -45,6 +91,146 @@ class MapValidator:
                 if bit in field_at_bit:
                     raise ReginaldException(f"{bt}: always_write overlaps with field {field_at_bit[bit]} at bit {bit}")
 
+        self._check_reset_val(reg, bt)
+
+        # Validate that all examples are consistent with the register layout:
+        for idx, example in enumerate(reg.examples):
+            if not fits_into_bitwidth(example.val, reg.bitwidth):
+                raise ReginaldException(f"{bt} -> examples -> {idx}: Value does not fit into register!")
+            for field_name, field_val in example.fields.items():
+                field = reg.fields[field_name]
+                if field.extract_from(example.val) != field_val:
+                    raise ReginaldException(f"{bt} -> examples -> {idx} -> {field_name}: Value 0x{example.val:X} "
+                                            f"contains 0x{field.extract_from(example.val):X} in this field, "
+                                            f"but example states 0x{field_val:X}!")
+                if not field.is_in_range(field_val):
+                    warn(f"{bt} -> examples -> {idx} -> {field_name}: Value {field_val} is outside of the field's valid range "
+                         f"({field.range_str()}).")
+
+        if reg.unlock is not None:
+            self._validate_unlock(block, reg, bt + " -> unlock")
+
+    def _validate_layout(self, reg: Register, bt: str) -> Dict[int, str]:
+        # Validate that no fields overlap:
+        field_at_bit = {}
+        for field in reg.get_layout_fields().values():
+            for bit in field.bits.bitlist:
+                if bit in field_at_bit:
+                    raise ReginaldException(f"Field {field.name} overalaps with field {field_at_bit[bit]} at bit {bit}!")
+                field_at_bit[bit] = field.name
+
+        # Validate that views only cover bits of other fields:
+        for field in reg.get_view_fields().values():
+            for bit in field.bits.bitlist:
+                if bit not in field_at_bit:
+                    raise ReginaldException(f"{bt} -> {field.name}: View covers bit {bit}, which is not part of any field!")
+
+        return field_at_bit
+
+    def _validate_variants(self, reg: Register, bt: str) -> Dict[int, str]:
+        assert reg.variant_tag is not None
+        tag_field = reg.fields[reg.variant_tag]
+        if tag_field.view or tag_field.array is not None:
+            raise ReginaldException(f"{bt} -> variant_tag: Tag field {tag_field.name} cannot be a view or part of a field array!")
+        if len(tag_field.bits.get_bitranges()) != 1 or tag_field.byte_order == 'big':
+            raise ReginaldException(f"{bt} -> variant_tag: Tag field {tag_field.name} has to be contiguous, and not byte-swapped!")
+
+        tags = {}  # type: Dict[int, str]
+        enums = {enum.name: enum for enum in reg.get_local_enums()}
+        field_at_bit = {}
+        for variant in reg.variants.values():
+            vbt = bt + f" -> variants -> {variant.name}"
+            if variant.tag > tag_field.bits.get_unpositioned_bits().get_bitmask():
+                raise ReginaldException(f"{vbt}: Tag 0x{variant.tag:X} does not fit into tag field {tag_field.name}!")
+            if variant.tag in tags:
+                raise ReginaldException(f"{vbt}: Tag 0x{variant.tag:X} already selects variant {tags[variant.tag]}!")
+            tags[variant.tag] = variant.name
+
+            view = reg.get_variant_view(variant.name)
+            for field in variant.fields.values():
+                self._validate_field(view, field, vbt)
+                if field.name in reg.fields:
+                    raise ReginaldException(f"{vbt} -> {field.name}: Field is also a field of the register!")
+                # Generators name local enums by register, variants cannot reuse a name for a different enum:
+                if isinstance(field.enum, RegEnum) and not field.enum.is_shared and enums[field.enum.name] != field.enum:
+                    raise ReginaldException(f"{vbt} -> {field.name}: Another field of the register has a different "
+                                            f"enum named {field.enum.name}!")
+            for bit, field_name in self._validate_layout(view, vbt).items():
+                if field_name not in variant.fields:
+                    continue
+                if bit in tag_field.bits.bitlist:
+                    raise ReginaldException(f"{vbt} -> {field_name}: Field overlaps with tag field {tag_field.name} at bit {bit}!")
+                field_at_bit[bit] = f"{field_name} (variant {variant.name})"
+        return field_at_bit
+
+    def _validate_unlock(self, block: RegisterBlock, reg: Register, bt: str):
+        assert reg.unlock is not None
+        key_reg = self.rmap.register_by_name(reg.unlock.key_register)
+
+        if key_reg is None:
+            raise ReginaldException(f"{bt}: Unknown key register {reg.unlock.key_register}!")
+        if any(instance_name + reg.name == key_reg.name for instance_name in block.instances.keys()):
+            raise ReginaldException(f"{bt}: A register cannot be its own key register!")
+        if len(reg.unlock.keys) == 0:
+            raise ReginaldException(f"{bt}: An unlock sequence needs at least one key!")
+        for key in reg.unlock.keys:
+            if not fits_into_bitwidth(key, key_reg.bitwidth):
+                raise ReginaldException(f"{bt}: Key 0x{key:X} does not fit into key register {key_reg.name}!")
+
+        # Registers without any access information are assumed to be writable:
+        for r in [reg, key_reg]:
+            fields = (r.fields if r.write_fields is None else r.write_fields).values()
+            if len(fields) > 0 and all(len(field.access) > 0 for field in fields) and not r.is_writable():
+                raise ReginaldException(f"{bt}: Register {r.name if r is key_reg else block.name + reg.name} is not writable!")
+
+    def _validate_aliases(self, block: RegisterBlock, reg: Register, aliases: Dict[str, str]):
+        bt = f"registers -> {block.name+reg.name}"
+
+        for instance_name in block.instances.keys():
+            for alias in block.get_alias_names(reg, instance_name):
+                if self.rmap.register_by_name(alias) is not None:
+                    raise ReginaldException(f"{bt} -> aliases: Alias {alias} is the name of a register!")
+                if alias in aliases:
+                    raise ReginaldException(f"{bt} -> aliases: Alias {alias} is already an alias of {aliases[alias]}!")
+                aliases[alias] = instance_name + reg.name
+
+        field_aliases = set()  # type: Set[str]
+        fields = reg.get_all_fields()
+        for field in fields.values():
+            for alias in field.aliases:
+                if alias in fields:
+                    raise ReginaldException(f"{bt} -> {field.name} -> aliases: Alias {alias} is the name of a field!")
+                if alias in field_aliases:
+                    raise ReginaldException(f"{bt} -> {field.name} -> aliases: Alias {alias} is given more than once!")
+                field_aliases.add(alias)
+
+    def _check_reset_val(self, reg: Register, bt: str):
+        # Check that the reset value is consistent with what the register's fields
+        # and always_write specify. Inconsistencies are likely transcription errors,
+        # but may be legitimate, so only warn.
+        if reg.reset_val is None:
+            return
+
+        for field in reg.fields.values():
+            if field.enum is None:
+                continue
+            field_val = field.extract_from(reg.reset_val)
+            if field.enum.is_reserved(field_val):
+                warn(f"{bt} -> {field.name}: Reset value of field (0x{field_val:X}) is reserved.")
+            elif field.lookup_enum_entry_name(field_val) is None:
+                warn(f"{bt} -> {field.name}: Reset value of field (0x{field_val:X}) does not correspond to any enum entry.")
+
+        for field in reg.fields.values():
+            field_val = field.extract_from(reg.reset_val)
+            if not field.is_in_range(field_val):
+                warn(f"{bt} -> {field.name}: Reset value of field ({field_val}) is outside of its valid range ({field.range_str()}).")
+
+        if reg.always_write is not None:
+            mask = reg.always_write.bits.get_bitmask()
+            if reg.reset_val & mask != reg.always_write.value & mask:
+                warn(f"{bt}: Reset value (0x{reg.reset_val:X}) does not match always_write value "
+                     f"(0x{reg.always_write.value:X}) in always_write bits (mask 0x{mask:X}).")
+
     def _validate_field(self, reg: Register, field: Field, bt: str):
         bt = bt + f" -> {field.name}"
 
@@ -54,7 +240,156 @@ class MapValidator:
 
         # Validate that each enum entry actually fits into field:
         if field.enum is not None:
+            if not field.enum.is_shared:
+                self._validate_enum(field.enum, bt + " -> enum")
             for enum_entry in field.enum.entries.values():
                 mask = field.bits.get_unpositioned_bits().get_bitmask()
                 if enum_entry.value & mask != enum_entry.value:
                     raise ReginaldException(f"{bt}: Enum does not fit into field!")
+
+        # Validate that fixed-point fields are plain numbers, that fit the conversion helpers:
+        if field.fixed_point is not None:
+            if field.enum is not None:
+                raise ReginaldException(f"{bt}: Field cannot be both fixed-point and an enum!")
+            if field.bits.total_width() > 32:
+                raise ReginaldException(f"{bt}: Fixed-point fields can be at most 32 bits wide!")
+
+        # Validate that float fields are exactly as wide as their format:
+        if field.float_format is not None:
+            if field.enum is not None or field.fixed_point is not None:
+                raise ReginaldException(f"{bt}: Float fields cannot be an enum or fixed-point!")
+            width = {'f16': 16, 'f32': 32}[field.float_format]
+            if field.bits.total_width() != width or len(field.bits.get_bitranges()) != 1:
+                raise ReginaldException(f"{bt}: {field.float_format} fields have to be {width} contiguous bits!")
+
+        # Validate that ranges are on plain numeric fields, and fit them:
+        if field.has_range():
+            if field.enum is not None or field.float_format is not None:
+                raise ReginaldException(f"{bt}: Enum and float fields cannot have a min/max range!")
+            if field.min is not None and field.max is not None and field.min > field.max:
+                raise ReginaldException(f"{bt}: min ({field.min}) is larger than max ({field.max})!")
+            unpos_mask = field.bits.get_unpositioned_bits().get_bitmask()
+            for limit in [field.min, field.max]:
+                if limit is not None and limit > unpos_mask:
+                    raise ReginaldException(f"{bt}: Range limit {limit} does not fit into field!")
+
+        # Validate that units are on numeric fields:
+        if field.unit is not None and field.enum is not None:
+            raise ReginaldException(f"{bt}: Enum fields cannot have a unit!")
+
+        # Validate that read side effects are on fields that can be read:
+        if field.read_side_effect is not None and len(field.access) > 0 and not field.is_readable():
+            raise ReginaldException(f"{bt}: Field has a read side effect, but cannot be read!")
+
+        # Validate that byte-swapped fields consist of complete bytes:
+        if field.byte_order == 'big':
+            if len(field.get_bitranges()) != 1 or field.bits.total_width() % 8 != 0 or field.bits.total_width() < 16:
+                raise ReginaldException(f"{bt}: Big-endian fields have to be contiguous and consist of at least two complete bytes!")
+            if field.view:
+                raise ReginaldException(f"{bt}: Views cannot be byte-swapped!")
+
+        # Validate that self-clearing fields are single, writable trigger bits:
+        if field.self_clearing:
+            if len(field.bits.bitlist) != 1:
+                raise ReginaldException(f"{bt}: Self-clearing fields have to be a single bit!")
+            if len(field.access) > 0 and not field.is_writable():
+                raise ReginaldException(f"{bt}: Field is self-clearing, but cannot be written!")
+            if field.view:
+                raise ReginaldException(f"{bt}: Views cannot be self-clearing!")
+
+        # Validate that recommended values fit the field, and can be written:
+        if field.default is not None:
+            if field.default > field.bits.get_unpositioned_bits().get_bitmask():
+                raise ReginaldException(f"{bt}: Default 0x{field.default:X} does not fit into field!")
+            if not field.is_in_range(field.default):
+                raise ReginaldException(f"{bt}: Default 0x{field.default:X} is outside the field's min/max range!")
+            if field.enum is not None and field.enum.get_catch_all() is None and field.lookup_enum_entry_name(field.default) is None:
+                raise ReginaldException(f"{bt}: Default 0x{field.default:X} is not a value of enum {field.enum.name}!")
+            if len(field.access) > 0 and not field.is_writable():
+                raise ReginaldException(f"{bt}: Field has a default, but cannot be written!")
+            if field.view:
+                raise ReginaldException(f"{bt}: Views cannot have a default!")
+
+    def _validate_enum(self, enum: RegEnum, bt: str):
+        catch_alls = [entry.name for entry in enum.entries.values() if entry.catch_all]
+        if len(catch_alls) > 1:
+            raise ReginaldException(f"{bt}: Only one entry can be the catch-all entry ({', '.join(catch_alls)})!")
+
+        for entry in enum.entries.values():
+            if enum.is_reserved(entry.value):
+                raise ReginaldException(f"{bt} -> {entry.name}: Value 0x{entry.value:X} is reserved!")
+
+    def _validate_split_value(self, split_value: SplitValue, used_bits: Dict[Tuple[str, int], str]):
+        bt = f"split_values -> {split_value.name}"
+        regs = {reg.name: reg for reg in self.rmap.physical_registers.values()}
+
+        if len(split_value.parts) < 2:
+            raise ReginaldException(f"{bt}: A split value needs at least two parts!")
+
+        for part in split_value.parts:
+            pbt = bt + f" -> {part.to_str()}"
+            if part.reg_name not in regs:
+                raise ReginaldException(f"{pbt}: Unknown register {part.reg_name}!")
+            if part.field_name is None:
+                continue
+            if part.field_name not in regs[part.reg_name].fields:
+                raise ReginaldException(f"{pbt}: Register {part.reg_name} has no field {part.field_name}!")
+            field = regs[part.reg_name].fields[part.field_name]
+            if field.view or field.array is not None:
+                raise ReginaldException(f"{pbt}: Views and array fields cannot be parts of a split value!")
+            if len(field.bits.get_bitranges()) != 1:
+                raise ReginaldException(f"{pbt}: Parts of a split value have to be contiguous!")
+            if field.enum is not None or field.fixed_point is not None or field.float_format is not None:
+                raise ReginaldException(f"{pbt}: Parts of a split value have to be plain numbers (no enum, fixed-point, or float)!")
+            if field.byte_order != 'little':
+                raise ReginaldException(f"{pbt}: Byte-swapped fields cannot be parts of a split value!")
+
+        layout = self.rmap.split_value_layout(split_value)
+        for part, (reg, _, bits, _) in zip(split_value.parts, layout):
+            for bit in bits.bitlist:
+                if (reg.name, bit) in used_bits:
+                    other = used_bits[(reg.name, bit)]
+                    if other == split_value.name:
+                        raise ReginaldException(f"{bt} -> {part.to_str()}: Part overlaps with another part at bit {bit}!")
+                    raise ReginaldException(f"{bt} -> {part.to_str()}: Part overlaps with split value {other} at bit {bit}!")
+            for bit in bits.bitlist:
+                used_bits[(reg.name, bit)] = split_value.name
+
+        # Complete registers count with all of their fields:
+        fields = []  # type: List[Field]
+        for reg, field, _, _ in layout:
+            fields.extend(reg.get_layout_fields().values() if field is None else [field])
+        fields = [field for field in fields if len(field.access) > 0]
+        if len(set(field.is_readable() for field in fields)) > 1 or len(set(field.is_writable() for field in fields)) > 1:
+            raise ReginaldException(f"{bt}: Parts have inconsistent access ({', '.join(field.access_str() for field in fields)})!")
+
+        # Datasheets place parts at ascending (or descending) addresses:
+        adrs = list(dict.fromkeys(reg.adr for reg, _, _, _ in layout))
+        if adrs != sorted(adrs) and adrs != sorted(adrs, reverse=True):
+            warn(f"{bt}: Parts are not in address order (0x{', 0x'.join(f'{adr:X}' for adr in adrs)}). "
+                 f"Parts have to be given most significant first.")
+
+    def _validate_doc_refs(self, docs: Docs, bt: str):
+        for reg_name, field_name in docs.refs():
+            ref = f"[[{plain_doc_ref(reg_name, field_name)}]]"
+            found = self.rmap.template_by_name(reg_name)
+            if found is None:
+                raise ReginaldException(f"{bt}: Reference {ref} does not resolve, {reg_name} is not a register!")
+            if field_name is not None and field_name not in found[1].get_all_fields():
+                raise ReginaldException(f"{bt}: Reference {ref} does not resolve, {reg_name} has no field {field_name}!")
+
+    def _validate_transaction(self, transaction: RegisterTransaction):
+        bt = f"transactions -> {transaction.name}"
+        regs = {reg.name: reg for reg in self.rmap.physical_registers.values()}
+
+        if len(transaction.registers) < 2:
+            raise ReginaldException(f"{bt}: A transaction needs at least two registers!")
+
+        for name in transaction.registers:
+            if name not in regs:
+                raise ReginaldException(f"{bt}: Unknown register {name}!")
+            if transaction.registers.count(name) > 1:
+                raise ReginaldException(f"{bt}: Register {name} listed more than once!")
+            fields = regs[name].fields.values()
+            if len(fields) > 0 and all(len(field.access) > 0 for field in fields) and not regs[name].is_writable():
+                raise ReginaldException(f"{bt}: Register {name} is not writable!")
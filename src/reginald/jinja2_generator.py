@@ -4,7 +4,7 @@ from typing import List
 from jinja2 import Environment, PackageLoader
 
 import reginald.utils
-from reginald.datamodel import RegisterMap
+from reginald.datamodel import RegisterMap, plain_doc_ref
 from reginald.generator import OutputGenerator
 
 
@@ -24,7 +24,7 @@ class BuiltinJinjaGenerator(OutputGenerator):
 
         template = env.get_template(self.template_name)
 
-        render_jinja2_template(template, rmap, input_file, output_file, args)
+        render_jinja2_template(template, rmap.with_rendered_doc_refs(plain_doc_ref), input_file, output_file, args)
 
 
 def render_jinja2_template(template, rmap: RegisterMap, input_file: str, output_file: str, args: List[str]):
@@ -0,0 +1,212 @@
+import csv
+import json
+from dataclasses import dataclass
+from os import path
+from typing import Dict, List, Optional
+
+from reginald.datamodel import AccessMode, Register, RegisterMap
+from reginald.error import ReginaldException
+
+
+# How access violations are handled:
+#   panic  Stop at the first violation (raises ReginaldException).
+#   error  Collect all violations, the caller fails if there are any.
+#   log    Collect all violations, the caller only reports them.
+STRICTNESS_LEVELS = ['panic', 'error', 'log']
+
+
+@dataclass
+class TraceEntry:
+    line: int
+    op: str
+    register: str
+    value: Optional[int]
+    width: Optional[int] = None
+
+
+class RegisterFileSimulator:
+    # Host-side model of a device's register file: Starts at the reset
+    # values and applies reads and writes, reporting access violations.
+
+    def __init__(self, rmap: RegisterMap, strictness: str = 'error'):
+        if strictness not in STRICTNESS_LEVELS:
+            raise ReginaldException(f"Unknown strictness '{strictness}'!")
+        self.rmap = rmap
+        self.strictness = strictness
+        self.registers = {reg.name: reg for reg in rmap.physical_registers.values()}
+        self.by_adr = rmap.physical_registers
+        self.state = {name: reg.reset_val for name, reg in self.registers.items()}  # type: Dict[str, Optional[int]]
+        self.violations = []  # type: List[str]
+
+    def violation(self, msg: str):
+        if self.strictness == 'panic':
+            raise ReginaldException(msg)
+        self.violations.append(msg)
+
+    def lookup(self, ref: str, bt: str) -> Optional[Register]:
+        if ref in self.registers:
+            return self.registers[ref]
+        try:
+            adr = int(ref, 0)
+        except ValueError:
+            self.violation(f"{bt}: Unknown register {ref}.")
+            return None
+        if adr not in self.by_adr:
+            self.violation(f"{bt}: No register at address 0x{adr:X}.")
+            return None
+        return self.by_adr[adr]
+
+    def replay(self, trace: List[TraceEntry], trace_name: str):
+        for entry in trace:
+            bt = f"{trace_name}:{entry.line}"
+            reg = self.lookup(entry.register, bt)
+            if reg is None:
+                continue
+
+            if entry.width is not None and entry.width != reg.bitwidth:
+                self.violation(f"{bt} ({reg.name}): {entry.width}-bit access to {reg.bitwidth}-bit register.")
+
+            if entry.op == 'write':
+                if entry.value is None:
+                    raise ReginaldException(f"{bt}: Write without value!")
+                self.write(reg, entry.value, bt)
+            elif entry.op == 'read':
+                self.read(reg, entry.value, bt)
+            else:
+                raise ReginaldException(f"{bt}: Unknown operation '{entry.op}' (expected 'read' or 'write')!")
+
+    def write(self, reg: Register, val: int, bt: str):
+        bt = bt + f" ({reg.name})"
+
+        if val >= (1 << reg.bitwidth):
+            self.violation(f"{bt}: Value 0x{val:X} does not fit into {reg.bitwidth} bits.")
+            val &= (1 << reg.bitwidth) - 1
+
+        if has_explicit_access(reg) and not reg.is_writable():
+            self.violation(f"{bt}: Write to read-only register.")
+            return
+
+        if reg.always_write is not None:
+            mask = reg.always_write.bits.get_bitmask()
+            if val & mask != reg.always_write.value & mask:
+                self.violation(f"{bt}: Always-write bits written as 0x{val & mask:X}, "
+                                       f"expected 0x{reg.always_write.value & mask:X}.")
+
+        # Fields of the write layout, or of the variant selected by the written tag:
+        write_fields = reg.write_fields if reg.write_fields is not None else reg.fields
+        variant = reg.lookup_variant(val)
+        if variant is not None:
+            write_fields = write_fields | variant.fields
+        for field in write_fields.values():
+            if field.enum is None or len(field.enum.entries) == 0:
+                continue
+            field_val = field.extract_from(val)
+            if len(field.access) > 0 and not field.is_writable():
+                continue
+            if field.enum.is_reserved(field_val):
+                self.violation(f"{bt}: Field {field.name} written with 0x{field_val:X}, which is reserved.")
+            elif field.lookup_enum_entry_name(field_val) is None:
+                self.violation(f"{bt}: Field {field.name} written with 0x{field_val:X}, which is not a valid enum value.")
+
+        for field in write_fields.values():
+            field_val = field.extract_from(val)
+            if len(field.access) > 0 and not field.is_writable():
+                continue
+            if not field.is_in_range(field_val):
+                self.violation(f"{bt}: Field {field.name} written with {field_val}, which is outside of its valid range "
+                               f"({field.range_str()}).")
+
+        # With separate read and write layouts, what is read back does not
+        # depend on what was written:
+        if reg.write_fields is not None:
+            return
+
+        # Read-only fields keep their state, write-1 fields clear/set/toggle
+        # the bits written as 1:
+        ro_mask = 0
+        for field in reg.get_layout_fields().values():
+            if len(field.access) > 0 and not field.is_writable():
+                ro_mask |= field.bits.get_bitmask()
+        w1c_mask = reg.get_access_mask(AccessMode.WRITE_1_CLEAR)
+        w1s_mask = reg.get_access_mask(AccessMode.WRITE_1_SET)
+        w1t_mask = reg.get_access_mask(AccessMode.WRITE_1_TOGGLE)
+        current = self.state[reg.name]
+        if current is not None and (current ^ val) & ro_mask != 0:
+            self.violation(f"{bt}: Write changes read-only bits (mask 0x{(current ^ val) & ro_mask:X}), which keep their value.")
+        old = current or 0
+        new = (old & ro_mask) | (val & ~(ro_mask | w1c_mask | w1s_mask | w1t_mask))
+        new |= (old & ~val & w1c_mask) | ((old | val) & w1s_mask) | ((old ^ val) & w1t_mask)
+
+        # Self-clearing bits are cleared again by hardware:
+        for field in reg.get_layout_fields().values():
+            if field.self_clearing:
+                new &= ~field.bits.get_bitmask()
+        self.state[reg.name] = new
+
+    def read(self, reg: Register, expected: Optional[int], bt: str):
+        bt = bt + f" ({reg.name})"
+
+        if has_explicit_access(reg) and not reg.is_readable():
+            self.violation(f"{bt}: Read from write-only register.")
+            return
+
+        if expected is not None:
+            current = self.state[reg.name]
+            if current is None:
+                self.violation(f"{bt}: Read expects 0x{expected:X}, but the register has no known value.")
+            elif current != expected:
+                self.violation(f"{bt}: Read expects 0x{expected:X}, but the register holds 0x{current:X}.")
+
+        # Read-clears fields (other read side effects are not modelled):
+        clear_mask = reg.get_access_mask(AccessMode.READ_CLEAR)
+        for field in reg.get_layout_fields().values():
+            if field.read_side_effect == 'clear':
+                clear_mask |= field.bits.get_bitmask()
+        if reg.read_side_effect == 'clear':
+            clear_mask = (1 << reg.bitwidth) - 1
+        if self.state[reg.name] is not None:
+            self.state[reg.name] &= ~clear_mask
+
+
+def has_explicit_access(reg: Register) -> bool:
+    return len(reg.fields) > 0 and all(len(field.access) > 0 for field in reg.fields.values())
+
+
+def load_trace(file_name: str) -> List[TraceEntry]:
+    # CSV (columns: op, register, value, width) or JSON (list of {op, register,
+    # value, width}). Registers are given by name or address, values are
+    # optional for reads. The access width (in bits) is optional.
+    try:
+        with open(file_name, newline='') as f:
+            if path.splitext(file_name)[1].lower() == '.json':
+                rows = json.load(f)
+                if not isinstance(rows, list) or not all(isinstance(r, dict) for r in rows):
+                    raise ReginaldException(f"{file_name}: Trace has to be a list of objects!")
+                lines = range(1, len(rows) + 1)
+            else:
+                rows = list(csv.DictReader(f, skipinitialspace=True))
+                lines = range(2, len(rows) + 2)
+    except FileNotFoundError:
+        raise ReginaldException(f"File {file_name} not found")
+    except json.JSONDecodeError as e:
+        raise ReginaldException(f"{file_name}: Invalid JSON: {e}")
+
+    trace = []
+    for line, row in zip(lines, rows):
+        bt = f"{file_name}:{line}"
+        if 'op' not in row or 'register' not in row:
+            raise ReginaldException(f"{bt}: Trace entry needs 'op' and 'register'!")
+        trace.append(TraceEntry(line=line, op=str(row['op']).strip().lower(), register=str(row['register']).strip(),
+                                value=trace_value(row.get('value'), bt), width=trace_value(row.get('width'), bt)))
+    return trace
+
+
+def trace_value(value, bt: str) -> Optional[int]:
+    if value is None or value == "":
+        return None
+    if isinstance(value, int) and not isinstance(value, bool):
+        return value
+    try:
+        return int(str(value).strip(), 0)
+    except ValueError:
+        raise ReginaldException(f"{bt}: Invalid value '{value}'!")
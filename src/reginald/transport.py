@@ -0,0 +1,240 @@
+import argparse
+import ctypes
+import fcntl
+import os
+import termios
+import tty
+from abc import ABC, abstractmethod
+
+from reginald.error import ReginaldException
+
+
+class Transport(ABC):
+    # Access to the registers of a live device.
+
+    @abstractmethod
+    def read(self, adr: int, length: int) -> bytes:
+        raise NotImplementedError
+
+    @abstractmethod
+    def write(self, adr: int, data: bytes):
+        raise NotImplementedError
+
+    def close(self):
+        pass
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *_):
+        self.close()
+
+
+class _I2CMsg(ctypes.Structure):
+    _fields_ = [('addr', ctypes.c_uint16),
+                ('flags', ctypes.c_uint16),
+                ('len', ctypes.c_uint16),
+                ('buf', ctypes.POINTER(ctypes.c_uint8))]
+
+
+class _I2CRdwrIoctlData(ctypes.Structure):
+    _fields_ = [('msgs', ctypes.POINTER(_I2CMsg)),
+                ('nmsgs', ctypes.c_uint32)]
+
+
+class I2CDevTransport(Transport):
+    # Linux userspace I2C (/dev/i2c-N). Registers are read with a combined
+    # transaction: register address write, repeated start, data read.
+
+    I2C_RDWR = 0x0707
+    I2C_M_RD = 0x0001
+
+    def __init__(self, port: str, device_adr: int, adr_bytes: int = 1):
+        self.device_adr = device_adr
+        self.adr_bytes = adr_bytes
+        try:
+            self.fd = os.open(port, os.O_RDWR)
+        except OSError as e:
+            raise ReginaldException(f"i2c: Could not open {port}: {e.strerror}")
+
+    def _adr_bytes(self, adr: int) -> bytes:
+        try:
+            return adr.to_bytes(self.adr_bytes, 'big')
+        except OverflowError:
+            raise ReginaldException(f"i2c: Register address 0x{adr:X} does not fit into {self.adr_bytes} byte(s)")
+
+    def _transfer(self, msgs):
+        msg_array = (_I2CMsg * len(msgs))(*msgs)
+        data = _I2CRdwrIoctlData(msgs=msg_array, nmsgs=len(msgs))
+        try:
+            fcntl.ioctl(self.fd, self.I2C_RDWR, data)
+        except OSError as e:
+            raise ReginaldException(f"i2c: Transfer to device 0x{self.device_adr:02X} failed: {e.strerror}")
+
+    def read(self, adr: int, length: int) -> bytes:
+        adr_buf = (ctypes.c_uint8 * self.adr_bytes)(*self._adr_bytes(adr))
+        read_buf = (ctypes.c_uint8 * length)()
+        self._transfer([
+            _I2CMsg(addr=self.device_adr, flags=0, len=self.adr_bytes, buf=adr_buf),
+            _I2CMsg(addr=self.device_adr, flags=self.I2C_M_RD, len=length, buf=read_buf),
+        ])
+        return bytes(read_buf)
+
+    def write(self, adr: int, data: bytes):
+        payload = self._adr_bytes(adr) + data
+        write_buf = (ctypes.c_uint8 * len(payload))(*payload)
+        self._transfer([_I2CMsg(addr=self.device_adr, flags=0, len=len(payload), buf=write_buf)])
+
+    def close(self):
+        os.close(self.fd)
+
+
+class _SPIIocTransfer(ctypes.Structure):
+    _fields_ = [('tx_buf', ctypes.c_uint64),
+                ('rx_buf', ctypes.c_uint64),
+                ('len', ctypes.c_uint32),
+                ('speed_hz', ctypes.c_uint32),
+                ('delay_usecs', ctypes.c_uint16),
+                ('bits_per_word', ctypes.c_uint8),
+                ('cs_change', ctypes.c_uint8),
+                ('tx_nbits', ctypes.c_uint8),
+                ('rx_nbits', ctypes.c_uint8),
+                ('word_delay_usecs', ctypes.c_uint8),
+                ('pad', ctypes.c_uint8)]
+
+
+class SPIDevTransport(Transport):
+    # Linux userspace SPI (/dev/spidevB.C). Every access is a single full-duplex
+    # transfer: register address (with the read flag or-ed into the first byte
+    # for reads), followed by the data.
+
+    SPI_IOC_MESSAGE_1 = 0x40206B00
+
+    def __init__(self, port: str, adr_bytes: int = 1, read_flag: int = 0x80, speed_hz: int = 1000000):
+        self.adr_bytes = adr_bytes
+        self.read_flag = read_flag
+        self.speed_hz = speed_hz
+        try:
+            self.fd = os.open(port, os.O_RDWR)
+        except OSError as e:
+            raise ReginaldException(f"spi: Could not open {port}: {e.strerror}")
+
+    def _adr_bytes(self, adr: int) -> bytes:
+        try:
+            return adr.to_bytes(self.adr_bytes, 'big')
+        except OverflowError:
+            raise ReginaldException(f"spi: Register address 0x{adr:X} does not fit into {self.adr_bytes} byte(s)")
+
+    def _transfer(self, tx: bytes) -> bytes:
+        tx_buf = (ctypes.c_uint8 * len(tx))(*tx)
+        rx_buf = (ctypes.c_uint8 * len(tx))()
+        xfer = _SPIIocTransfer(tx_buf=ctypes.addressof(tx_buf), rx_buf=ctypes.addressof(rx_buf),
+                               len=len(tx), speed_hz=self.speed_hz)
+        try:
+            fcntl.ioctl(self.fd, self.SPI_IOC_MESSAGE_1, xfer)
+        except OSError as e:
+            raise ReginaldException(f"spi: Transfer failed: {e.strerror}")
+        return bytes(rx_buf)
+
+    def read(self, adr: int, length: int) -> bytes:
+        cmd = bytearray(self._adr_bytes(adr))
+        cmd[0] |= self.read_flag
+        return self._transfer(bytes(cmd) + bytes(length))[len(cmd):]
+
+    def write(self, adr: int, data: bytes):
+        self._transfer(self._adr_bytes(adr) + data)
+
+    def close(self):
+        os.close(self.fd)
+
+
+class SerialBridgeTransport(Transport):
+    # Simple binary protocol for a microcontroller bridging a serial port to the
+    # device bus. All multi-byte numbers are big-endian:
+    #   Read:  'R' ADR[4] LEN[1]          -> 'K' DATA[LEN]
+    #   Write: 'W' ADR[4] LEN[1] DATA[LEN] -> 'K'
+    # Any other response byte signals an error.
+
+    def __init__(self, port: str, baud: int = 115200, timeout: float = 1.0):
+        try:
+            self.fd = os.open(port, os.O_RDWR | os.O_NOCTTY)
+        except OSError as e:
+            raise ReginaldException(f"serial: Could not open {port}: {e.strerror}")
+
+        speed = getattr(termios, f"B{baud}", None)
+        if speed is None:
+            os.close(self.fd)
+            raise ReginaldException(f"serial: Unsupported baud rate {baud}")
+
+        tty.setraw(self.fd)
+        attrs = termios.tcgetattr(self.fd)
+        attrs[4] = attrs[5] = speed
+        attrs[6][termios.VMIN] = 0
+        attrs[6][termios.VTIME] = max(1, int(timeout * 10))
+        termios.tcsetattr(self.fd, termios.TCSANOW, attrs)
+        termios.tcflush(self.fd, termios.TCIOFLUSH)
+
+    def _header(self, cmd: bytes, adr: int, length: int) -> bytes:
+        if length > 0xFF:
+            raise ReginaldException("serial: Cannot transfer more than 255 bytes at once")
+        return cmd + adr.to_bytes(4, 'big') + bytes([length])
+
+    def _recv(self, length: int) -> bytes:
+        data = b""
+        while len(data) < length:
+            chunk = os.read(self.fd, length - len(data))
+            if len(chunk) == 0:
+                raise ReginaldException("serial: Timeout waiting for bridge response")
+            data += chunk
+        return data
+
+    def _expect_ack(self, adr: int):
+        ack = self._recv(1)
+        if ack != b"K":
+            raise ReginaldException(f"serial: Bridge reported an error accessing 0x{adr:X} (0x{ack[0]:02X})")
+
+    def read(self, adr: int, length: int) -> bytes:
+        os.write(self.fd, self._header(b"R", adr, length))
+        self._expect_ack(adr)
+        return self._recv(length)
+
+    def write(self, adr: int, data: bytes):
+        os.write(self.fd, self._header(b"W", adr, len(data)) + data)
+        self._expect_ack(adr)
+
+    def close(self):
+        os.close(self.fd)
+
+
+TRANSPORT_PROTOCOLS = ['i2c', 'spi', 'serial']
+
+
+def add_transport_args(parser: argparse.ArgumentParser):
+    parser.add_argument('--protocol', choices=TRANSPORT_PROTOCOLS, default='i2c',
+                        help="bus protocol (default: i2c)")
+    parser.add_argument('--port', required=True,
+                        help="bus device, e.g. /dev/i2c-1, /dev/spidev0.0, or /dev/ttyUSB0")
+    parser.add_argument('--addr', type=lambda x: int(x, 0),
+                        help="device bus address (i2c only)")
+    parser.add_argument('--adr-bytes', type=int, default=1,
+                        help="number of bytes in a register address (i2c/spi, default: 1)")
+    parser.add_argument('--spi-read-flag', type=lambda x: int(x, 0), default=0x80,
+                        help="bits set in the first address byte for reads (spi only, default: 0x80)")
+    parser.add_argument('--spi-speed', type=int, default=1000000,
+                        help="clock frequency in Hz (spi only, default: 1000000)")
+    parser.add_argument('--baud', type=int, default=115200,
+                        help="baud rate (serial only, default: 115200)")
+
+
+def open_transport(args: argparse.Namespace) -> Transport:
+    match args.protocol:
+        case 'i2c':
+            if args.addr is None:
+                raise ReginaldException("i2c: --addr is required")
+            return I2CDevTransport(args.port, args.addr, args.adr_bytes)
+        case 'spi':
+            return SPIDevTransport(args.port, args.adr_bytes, args.spi_read_flag, args.spi_speed)
+        case 'serial':
+            return SerialBridgeTransport(args.port, args.baud)
+        case other:
+            raise ReginaldException(f"Unknown protocol '{other}'")